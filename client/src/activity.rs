@@ -0,0 +1,88 @@
+// Volume de mensagens por segundo no último minuto (sparkline do cabeçalho).
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Janela coberta pelo histórico, em segundos.
+pub const ACTIVITY_WINDOW_SECS: usize = 60;
+
+/// Buffer circular com a contagem de mensagens de cada segundo.
+///
+/// `buckets[sec % ACTIVITY_WINDOW_SECS]` guarda o segundo `sec`; segundos sem
+/// registro são zerados ao avançar, então nada antigo "reaparece" na volta do anel.
+#[derive(Clone, Debug)]
+pub struct ActivityHistory {
+    buckets: [u64; ACTIVITY_WINDOW_SECS],
+    /// Último segundo (Unix) registrado; `None` enquanto o histórico está vazio.
+    latest_sec: Option<u64>,
+}
+
+impl Default for ActivityHistory {
+    fn default() -> Self {
+        Self {
+            buckets: [0; ACTIVITY_WINDOW_SECS],
+            latest_sec: None,
+        }
+    }
+}
+
+impl ActivityHistory {
+    /// Soma `count` mensagens ao segundo `now_sec`.
+    /// Segundos já fora da janela (relógio voltou) são ignorados.
+    pub fn record(&mut self, now_sec: u64, count: u64) {
+        if self
+            .latest_sec
+            .is_some_and(|latest| latest.saturating_sub(now_sec) >= ACTIVITY_WINDOW_SECS as u64)
+        {
+            return;
+        }
+        self.advance_to(now_sec);
+        let slot = (now_sec % ACTIVITY_WINDOW_SECS as u64) as usize;
+        self.buckets[slot] = self.buckets[slot].saturating_add(count);
+    }
+
+    /// Contagens do último minuto, da mais antiga para a mais recente
+    /// (sempre `ACTIVITY_WINDOW_SECS` valores; histórico vazio = só zeros).
+    pub fn sparkline_data(&self, now_sec: u64) -> Vec<u64> {
+        let window = ACTIVITY_WINDOW_SECS as u64;
+        (0..window)
+            .map(|offset| {
+                let Some(sec) = (now_sec + offset + 1).checked_sub(window) else {
+                    return 0;
+                };
+                match self.latest_sec {
+                    Some(latest) if sec <= latest && latest - sec < window => {
+                        self.buckets[(sec % window) as usize]
+                    }
+                    _ => 0,
+                }
+            })
+            .collect()
+    }
+
+    /// Zera os segundos pulados entre o último registro e `now_sec`.
+    fn advance_to(&mut self, now_sec: u64) {
+        let window = ACTIVITY_WINDOW_SECS as u64;
+        match self.latest_sec {
+            Some(latest) if now_sec <= latest => return,
+            Some(latest) if now_sec - latest < window => {
+                for sec in latest + 1..=now_sec {
+                    self.buckets[(sec % window) as usize] = 0;
+                }
+            }
+            _ => self.buckets = [0; ACTIVITY_WINDOW_SECS],
+        }
+        self.latest_sec = Some(now_sec);
+    }
+}
+
+/// Segundo Unix atual, base dos índices do histórico.
+pub fn unix_now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+#[path = "tests/activity_tests.rs"]
+mod tests;
@@ -0,0 +1,290 @@
+//! Backends de chat dos bots: o Ollama (padrão) ou um servidor compatível com a
+//! API da OpenAI (LM Studio, vLLM...), escolhido por `ai.backend` / `LLM_BACKEND`.
+//!
+//! A montagem das mensagens e o `normalize_reply` continuam em `ai/mod.rs`; o
+//! backend só leva o pedido até o modelo e devolve o texto bruto da resposta.
+
+use std::{fmt::Display, future::Future, time::Duration};
+
+use ollama_rs::{
+    Ollama,
+    error::OllamaError,
+    generation::chat::{ChatMessage, request::ChatMessageRequest},
+    models::ModelOptions,
+};
+use serde_json::{Value, json};
+use tokio::runtime::Runtime;
+use tokio_stream::StreamExt;
+
+use crate::config::AiConfig;
+
+use super::{retry_delay, timeout_error_message};
+
+/// Servidor local padrão do LM Studio.
+pub(crate) const DEFAULT_OPENAI_URL: &str = "http://127.0.0.1:1234/v1";
+
+/// Qual API atende os pedidos dos bots.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackendKind {
+    Ollama,
+    /// `POST {ai.openai_url}/chat/completions`, com streaming SSE.
+    OpenAi,
+}
+
+impl BackendKind {
+    /// Interpreta `ai.backend`; valores desconhecidos ficam no Ollama.
+    pub fn from_setting(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "openai" => Self::OpenAi,
+            _ => Self::Ollama,
+        }
+    }
+}
+
+/// Opções de amostragem de uma resposta, já validadas.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) struct Sampling {
+    pub temperature: f32,
+    pub top_p: f32,
+    /// Só o Ollama aplica; a API da OpenAI não tem equivalente direto.
+    pub repeat_penalty: f32,
+    pub max_tokens: i32,
+}
+
+impl From<Sampling> for ModelOptions {
+    fn from(sampling: Sampling) -> Self {
+        ModelOptions::default()
+            .num_predict(sampling.max_tokens)
+            .temperature(sampling.temperature)
+            .top_p(sampling.top_p)
+            .repeat_penalty(sampling.repeat_penalty)
+    }
+}
+
+/// Pedido de uma resposta, igual para qualquer backend.
+pub(super) struct CompletionRequest {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    pub sampling: Sampling,
+    /// Espera máxima pela conexão e por cada pedaço do streaming.
+    pub timeout: Duration,
+    /// Tentativas quando a conexão falha (mínimo 1).
+    pub max_attempts: u32,
+}
+
+pub(super) trait ChatBackend {
+    /// Gera a resposta em streaming no runtime do worker; `on_partial` recebe o
+    /// texto acumulado a cada pedaço. Retorna o texto bruto, sem normalizar.
+    fn complete(
+        &self,
+        runtime: &Runtime,
+        request: CompletionRequest,
+        on_partial: &mut dyn FnMut(&str),
+    ) -> Result<String, String>;
+}
+
+/// Backend configurado em `ai.backend`.
+pub(super) fn select_backend(ai_config: &AiConfig) -> Box<dyn ChatBackend> {
+    match BackendKind::from_setting(&ai_config.backend) {
+        BackendKind::Ollama => Box::new(OllamaBackend {
+            host: ai_config.host.clone(),
+            port: ai_config.port,
+        }),
+        BackendKind::OpenAi => Box::new(OpenAiBackend {
+            base_url: ai_config.openai_url.clone(),
+            api_key: ai_config.openai_api_key.clone(),
+        }),
+    }
+}
+
+struct OllamaBackend {
+    host: String,
+    port: u16,
+}
+
+impl ChatBackend for OllamaBackend {
+    fn complete(
+        &self,
+        runtime: &Runtime,
+        request: CompletionRequest,
+        on_partial: &mut dyn FnMut(&str),
+    ) -> Result<String, String> {
+        runtime.block_on(async {
+            let client = Ollama::new(self.host.clone(), self.port);
+            let chat = ChatMessageRequest::new(request.model.clone(), request.messages.clone())
+                .options(ModelOptions::from(request.sampling));
+            let mut stream = send_with_retries(
+                &request,
+                || client.send_chat_messages_stream(chat.clone()),
+                is_connection_error,
+            )
+            .await?;
+
+            // O limite vale para cada pedaço: um modelo lento mas ativo não é cortado.
+            let mut content = String::new();
+            loop {
+                match tokio::time::timeout(request.timeout, stream.next()).await {
+                    Err(_) => return Err(timeout_error_message(request.timeout)),
+                    Ok(None) => break,
+                    Ok(Some(Err(()))) => {
+                        return Err("streaming do Ollama interrompido".to_string());
+                    }
+                    Ok(Some(Ok(chunk))) => {
+                        push_partial(&mut content, &chunk.message.content, on_partial);
+                        if chunk.done {
+                            break;
+                        }
+                    }
+                }
+            }
+            Ok(content)
+        })
+    }
+}
+
+struct OpenAiBackend {
+    base_url: String,
+    /// Vazio = sem `Authorization` (servidores locais costumam dispensar).
+    api_key: String,
+}
+
+impl ChatBackend for OpenAiBackend {
+    fn complete(
+        &self,
+        runtime: &Runtime,
+        request: CompletionRequest,
+        on_partial: &mut dyn FnMut(&str),
+    ) -> Result<String, String> {
+        runtime.block_on(async {
+            let client = reqwest::Client::new();
+            let url = format!("{}/chat/completions", self.base_url.trim_end_matches('/'));
+            let body = openai_request_body(&request);
+            let send = || {
+                let builder = client.post(&url).json(&body);
+                let builder = if self.api_key.is_empty() {
+                    builder
+                } else {
+                    builder.bearer_auth(&self.api_key)
+                };
+                builder.send()
+            };
+            let response = send_with_retries(&request, send, is_request_failure).await?;
+            let status = response.status();
+            if !status.is_success() {
+                let detail = response.text().await.unwrap_or_default();
+                return Err(format!("{status}: {}", detail.trim()));
+            }
+
+            // Eventos SSE (`data: {...}`), um por linha; pedaços podem cortar linhas ao meio.
+            let mut stream = response.bytes_stream();
+            let mut pending: Vec<u8> = Vec::new();
+            let mut content = String::new();
+            loop {
+                match tokio::time::timeout(request.timeout, stream.next()).await {
+                    Err(_) => return Err(timeout_error_message(request.timeout)),
+                    Ok(None) => break,
+                    Ok(Some(Err(err))) => return Err(err.to_string()),
+                    Ok(Some(Ok(bytes))) => pending.extend_from_slice(&bytes),
+                }
+                while let Some(end) = pending.iter().position(|&b| b == b'\n') {
+                    let line: Vec<u8> = pending.drain(..=end).collect();
+                    match parse_stream_line(&String::from_utf8_lossy(&line)) {
+                        StreamLine::Delta(text) => push_partial(&mut content, &text, on_partial),
+                        StreamLine::Done => return Ok(content),
+                        StreamLine::Ignored => {}
+                    }
+                }
+            }
+            Ok(content)
+        })
+    }
+}
+
+/// Corpo do `chat/completions` no formato da OpenAI, sempre com streaming.
+pub(super) fn openai_request_body(request: &CompletionRequest) -> Value {
+    let messages: Vec<Value> = request
+        .messages
+        .iter()
+        .map(|m| json!({ "role": m.role, "content": m.content }))
+        .collect();
+    json!({
+        "model": request.model,
+        "messages": messages,
+        "stream": true,
+        "temperature": request.sampling.temperature,
+        "top_p": request.sampling.top_p,
+        "max_tokens": request.sampling.max_tokens,
+    })
+}
+
+/// Linha do streaming SSE da API da OpenAI.
+#[derive(Debug, PartialEq)]
+pub(super) enum StreamLine {
+    /// Trecho novo da resposta.
+    Delta(String),
+    /// `data: [DONE]`: fim da resposta.
+    Done,
+    /// Linha vazia, comentário ou evento sem texto.
+    Ignored,
+}
+
+pub(super) fn parse_stream_line(line: &str) -> StreamLine {
+    let Some(data) = line.trim().strip_prefix("data:") else {
+        return StreamLine::Ignored;
+    };
+    let data = data.trim();
+    if data == "[DONE]" {
+        return StreamLine::Done;
+    }
+    serde_json::from_str::<Value>(data)
+        .ok()
+        .and_then(|event| {
+            event["choices"][0]["delta"]["content"]
+                .as_str()
+                .map(str::to_string)
+        })
+        .map_or(StreamLine::Ignored, StreamLine::Delta)
+}
+
+fn push_partial(content: &mut String, piece: &str, on_partial: &mut dyn FnMut(&str)) {
+    content.push_str(piece);
+    if !content.trim().is_empty() {
+        on_partial(content);
+    }
+}
+
+/// Envia o pedido com o limite de `request.timeout`, repetindo só falhas de conexão.
+///
+/// O erro final vira um único aviso no chat.
+async fn send_with_retries<T, E, F, Fut>(
+    request: &CompletionRequest,
+    mut send: F,
+    retryable: impl Fn(&E) -> bool,
+) -> Result<T, String>
+where
+    E: Display,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut attempt = 1;
+    loop {
+        match tokio::time::timeout(request.timeout, send()).await {
+            Err(_) => return Err(timeout_error_message(request.timeout)),
+            Ok(Ok(value)) => return Ok(value),
+            Ok(Err(err)) if attempt < request.max_attempts && retryable(&err) => {
+                tokio::time::sleep(retry_delay(attempt)).await;
+                attempt += 1;
+            }
+            Ok(Err(err)) => return Err(err.to_string()),
+        }
+    }
+}
+
+/// Falha de transporte (conexão recusada, queda no meio do pedido) que vale repetir.
+fn is_connection_error(err: &OllamaError) -> bool {
+    matches!(err, OllamaError::ReqwestError(e) if is_request_failure(e))
+}
+
+fn is_request_failure(err: &reqwest::Error) -> bool {
+    err.is_connect() || err.is_timeout() || err.is_request()
+}
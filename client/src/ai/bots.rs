@@ -1,8 +1,17 @@
+use std::collections::HashSet;
+
 use rand::{
     prelude::{IndexedRandom, SliceRandom},
     rng,
 };
 
+use serde::Deserialize;
+
+use super::{
+    DEFAULT_MODEL,
+    config::{AI_RESPONDER_BASE_WEIGHT, AI_RESPONDER_KEYWORD_WEIGHT},
+};
+
 /// Nomes base disponíveis para seleção no startup.
 pub const FANTASY_NAMES: &[&str] = &[
     "Aelric",
@@ -39,42 +48,115 @@ pub const RPG_PROFESSIONS: &[&str] = &[
     "Monge",
 ];
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize)]
 pub struct AiBotProfile {
     pub name: String,
     pub profession: String,
+    /// Tom de roleplay; vazio usa o estilo padrão da profissão.
+    #[serde(default)]
+    pub style: String,
+    /// Modelo do Ollama usado por este bot (`ai.models` em rodízio, ou `ai.model`).
+    #[serde(default)]
+    pub model: String,
 }
 
 /// Gera perfis de bots com nomes/profissões sorteados no início da aplicação.
 pub fn generate_bot_profiles(count: usize) -> Vec<AiBotProfile> {
+    generate_bot_profiles_excluding(count, &[])
+}
+
+/// Gera perfis evitando nomes já usados no chat (comparação sem caixa).
+///
+/// Usado no re-roll em tempo de execução para não colidir com usuários atuais.
+pub fn generate_bot_profiles_excluding(count: usize, excluded: &[&str]) -> Vec<AiBotProfile> {
     if count == 0 {
         return Vec::new();
     }
 
     let mut rng = rng();
-    let mut names_pool: Vec<&str> = FANTASY_NAMES.to_vec();
+    let taken: HashSet<String> = excluded.iter().map(|n| n.trim().to_lowercase()).collect();
+    let mut names_pool: Vec<&str> = FANTASY_NAMES
+        .iter()
+        .copied()
+        .filter(|name| !taken.contains(&name.to_lowercase()))
+        .collect();
+    if names_pool.is_empty() {
+        names_pool = FANTASY_NAMES.to_vec();
+    }
     names_pool.shuffle(&mut rng);
 
-    (0..count)
-        .map(|i| {
-            let base_name = names_pool[i % names_pool.len()];
-            let name = if i < names_pool.len() {
-                base_name.to_string()
-            } else {
-                format!("{base_name}-{}", (i / names_pool.len()) + 1)
-            };
-
+    let ai_config = &crate::config::current().ai;
+    let models = bot_models(count, &ai_config.models, &ai_config.model);
+    unique_bot_names(&names_pool, count, taken)
+        .into_iter()
+        .zip(models)
+        .map(|(name, model)| {
             let profession = RPG_PROFESSIONS
                 .choose(&mut rng)
                 .copied()
                 .unwrap_or("Aventureiro")
                 .to_string();
-
-            AiBotProfile { name, profession }
+            AiBotProfile {
+                name,
+                profession,
+                style: String::new(),
+                model,
+            }
         })
         .collect()
 }
 
+/// Modelo de cada um dos `count` bots: `models` em rodízio, na ordem dada.
+///
+/// Sem lista (ou só com nomes em branco), todos usam `fallback`; sem ele, `DEFAULT_MODEL`.
+pub fn bot_models(count: usize, models: &[String], fallback: &str) -> Vec<String> {
+    let mut listed: Vec<&str> = models
+        .iter()
+        .map(|model| model.trim())
+        .filter(|model| !model.is_empty())
+        .collect();
+    if listed.is_empty() {
+        listed.push(match fallback.trim() {
+            "" => DEFAULT_MODEL,
+            fallback => fallback,
+        });
+    }
+    listed
+        .iter()
+        .cycle()
+        .take(count)
+        .map(|model| model.to_string())
+        .collect()
+}
+
+/// Escolhe `count` nomes distintos (sem diferenciar caixa) a partir de `pool`.
+///
+/// Cada rodada percorre o pool inteiro; a partir da segunda, o nome ganha o
+/// sufixo `-N` da rodada. Nomes em `taken` e os já escolhidos são pulados, então
+/// não há repetição para nenhum `count`, nem com nomes repetidos no pool.
+fn unique_bot_names(pool: &[&str], count: usize, mut taken: HashSet<String>) -> Vec<String> {
+    let mut names = Vec::with_capacity(count);
+    if pool.is_empty() {
+        return names;
+    }
+    for round in 1usize.. {
+        for base in pool {
+            if names.len() == count {
+                return names;
+            }
+            let name = if round == 1 {
+                base.to_string()
+            } else {
+                format!("{base}-{round}")
+            };
+            if taken.insert(name.to_lowercase()) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
 pub fn profession_roleplay_style(profession: &str) -> &'static str {
     match profession {
         "Mago" => "Tom curioso e observador, com referencias leves a magia quando couber.",
@@ -91,11 +173,111 @@ pub fn profession_roleplay_style(profession: &str) -> &'static str {
     }
 }
 
+/// Radicais de assunto ligados a cada profissão, nos mesmos temas do estilo de roleplay.
+///
+/// Sem acento e em minúsculas; casam com o começo de uma palavra da mensagem.
+fn profession_keywords(profession: &str) -> &'static [&'static str] {
+    match profession {
+        "Mago" => &[
+            "magia", "feitico", "grimorio", "runa", "arcan", "estud", "livro",
+        ],
+        "Guerreiro" => &[
+            "luta", "espada", "batalha", "treino", "armadura", "forca", "combate",
+        ],
+        "Ladino" => &[
+            "furtiv", "escond", "sombra", "tranca", "roub", "segredo", "silencio",
+        ],
+        "Clerigo" => &["cura", "ferid", "doen", "reza", "templo", "bencao"],
+        "Ranger" => &[
+            "trilha", "floresta", "rastro", "caca", "arco", "acampa", "mapa",
+        ],
+        "Bardo" => &[
+            "musica", "cancao", "cantar", "historia", "festa", "alaude", "poesia",
+        ],
+        "Paladino" => &[
+            "proteg",
+            "justica",
+            "juramento",
+            "honra",
+            "guarda",
+            "escudo",
+        ],
+        "Druida" => &[
+            "natureza", "planta", "animal", "arvore", "erva", "chuva", "estacao",
+        ],
+        "Feiticeiro" => &["fogo", "faisca", "poder", "energia", "raio", "explo"],
+        "Monge" => &[
+            "medita",
+            "calma",
+            "foco",
+            "disciplina",
+            "respira",
+            "equilibrio",
+        ],
+        _ => &[],
+    }
+}
+
+/// Peso do bot no sorteio de quem responde `message`: o piso
+/// `AI_RESPONDER_BASE_WEIGHT` mais um bônus por palavra-chave da profissão citada.
+///
+/// Cada radical conta uma vez se alguma palavra começar por ele ("curandeiro"
+/// casa com "cura"; "procurar", não).
+pub fn profession_weight(profession: &str, message: &str) -> f64 {
+    let words: Vec<String> = message
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(fold_word)
+        .collect();
+    let hits = profession_keywords(profession)
+        .iter()
+        .filter(|keyword| words.iter().any(|word| word.starts_with(*keyword)))
+        .count();
+    AI_RESPONDER_BASE_WEIGHT + AI_RESPONDER_KEYWORD_WEIGHT * hits as f64
+}
+
+/// Minúsculas e sem acento ("Bênção" -> "bencao"), como os radicais de `profession_keywords`.
+fn fold_word(word: &str) -> String {
+    word.to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'á' | 'à' | 'â' | 'ã' | 'ä' => 'a',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'í' | 'ì' | 'î' | 'ï' => 'i',
+            'ó' | 'ò' | 'ô' | 'õ' | 'ö' => 'o',
+            'ú' | 'ù' | 'û' | 'ü' => 'u',
+            'ç' => 'c',
+            other => other,
+        })
+        .collect()
+}
+
+/// Status de roleplay que cada bot publica com `set_status` ao conectar.
+pub fn profession_status(profession: &str) -> &'static str {
+    match profession {
+        "Mago" => "Estudando um grimorio antigo",
+        "Guerreiro" => "Afiando a espada",
+        "Ladino" => "Observando das sombras",
+        "Clerigo" => "Em vigilia no templo",
+        "Ranger" => "Seguindo rastros na floresta",
+        "Bardo" => "Afinando o alaude",
+        "Paladino" => "De guarda no portao",
+        "Druida" => "Conversando com os carvalhos",
+        "Feiticeiro" => "Controlando faiscas arcanas",
+        "Monge" => "Meditando",
+        _ => "Descansando na taverna",
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
 
-    use super::{FANTASY_NAMES, RPG_PROFESSIONS, generate_bot_profiles};
+    use super::{
+        FANTASY_NAMES, RPG_PROFESSIONS, generate_bot_profiles, generate_bot_profiles_excluding,
+        profession_status, profession_weight, unique_bot_names,
+    };
+    use crate::ai::config::{AI_RESPONDER_BASE_WEIGHT, AI_RESPONDER_KEYWORD_WEIGHT};
 
     #[test]
     fn generate_bot_profiles_respects_count() {
@@ -115,4 +297,117 @@ mod tests {
             assert!(professions.contains(bot.profession.as_str()));
         }
     }
+
+    #[test]
+    fn relevant_keywords_raise_the_matching_profession_weight() {
+        let healing = "alguem sabe curar essa ferida?";
+        assert!(profession_weight("Clerigo", healing) > profession_weight("Ladino", healing));
+        let stealth = "preciso passar escondido pelas sombras";
+        assert!(profession_weight("Ladino", stealth) > profession_weight("Clerigo", stealth));
+        // Sem assunto ligado, todas ficam no piso e seguem sorteáveis.
+        for profession in RPG_PROFESSIONS {
+            assert_eq!(
+                profession_weight(profession, "bom dia"),
+                AI_RESPONDER_BASE_WEIGHT
+            );
+        }
+        assert_eq!(
+            profession_weight("Pirata", healing),
+            AI_RESPONDER_BASE_WEIGHT
+        );
+
+        // Palavras acentuadas casam com os radicais sem acento.
+        let boosted = AI_RESPONDER_BASE_WEIGHT + AI_RESPONDER_KEYWORD_WEIGHT;
+        for (profession, message) in [
+            ("Mago", "que feitiço é esse?"),
+            ("Guerreiro", "Força, pessoal"),
+            ("Clerigo", "preciso de uma bênção"),
+            ("Bardo", "toca uma canção"),
+            ("Ranger", "bora de caça"),
+            ("Paladino", "isso é justiça"),
+            ("Monge", "falta equilíbrio"),
+            ("Druida", "que estação linda"),
+            ("Ladino", "silêncio agora"),
+        ] {
+            assert_eq!(profession_weight(profession, message), boosted, "{message}");
+        }
+        // Radical no meio de outra palavra não conta.
+        for (profession, message) in [
+            ("Clerigo", "vou procurar um lugar seguro"),
+            ("Clerigo", "segura essa"),
+            ("Guerreiro", "certeza absoluta"),
+            ("Paladino", "aguarda um pouco"),
+            ("Druida", "fiz a reserva e ele observa"),
+            ("Ranger", "o barco chega em marco"),
+        ] {
+            assert_eq!(
+                profession_weight(profession, message),
+                AI_RESPONDER_BASE_WEIGHT,
+                "{message}"
+            );
+        }
+    }
+
+    #[test]
+    fn reroll_preserves_count_and_avoids_taken_names() {
+        let taken = ["Aelric", "branna", "Cedric"];
+        let bots = generate_bot_profiles_excluding(3, &taken);
+        assert_eq!(bots.len(), 3);
+
+        let names: HashSet<String> = bots.iter().map(|b| b.name.to_lowercase()).collect();
+        assert_eq!(names.len(), 3);
+        for name in taken {
+            assert!(!names.contains(&name.to_lowercase()));
+        }
+    }
+
+    #[test]
+    fn reroll_still_fills_count_when_every_base_name_is_taken() {
+        let bots = generate_bot_profiles_excluding(2, FANTASY_NAMES);
+        assert_eq!(bots.len(), 2);
+        for bot in bots {
+            assert!(!FANTASY_NAMES.contains(&bot.name.as_str()));
+        }
+    }
+
+    #[test]
+    fn generated_names_are_unique_for_any_count() {
+        for count in 0..=FANTASY_NAMES.len() * 3 {
+            let bots = generate_bot_profiles(count);
+            let names: HashSet<String> = bots.iter().map(|b| b.name.to_lowercase()).collect();
+            assert_eq!(bots.len(), count);
+            assert_eq!(names.len(), count, "duplicated name for count {count}");
+        }
+    }
+
+    #[test]
+    fn generated_names_stay_unique_around_taken_suffixes() {
+        // Nomes já usados que colidiriam com os sufixos das próximas rodadas.
+        let taken = ["Aelric", "aelric-2", "Branna-2", "Cedric-3"];
+        for count in 0..=FANTASY_NAMES.len() * 3 {
+            let bots = generate_bot_profiles_excluding(count, &taken);
+            let names: HashSet<String> = bots.iter().map(|b| b.name.to_lowercase()).collect();
+            assert_eq!(names.len(), count);
+            for name in taken {
+                assert!(!names.contains(&name.to_lowercase()));
+            }
+        }
+    }
+
+    #[test]
+    fn unique_bot_names_skips_repeated_pool_entries() {
+        let names = unique_bot_names(&["Lia", "lia", "Rui"], 5, HashSet::new());
+        assert_eq!(names, vec!["Lia", "Rui", "Lia-2", "Rui-2", "Lia-3"]);
+        assert!(unique_bot_names(&[], 3, HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn every_profession_has_its_own_status() {
+        let statuses: HashSet<&str> = RPG_PROFESSIONS
+            .iter()
+            .map(|p| profession_status(p))
+            .collect();
+        assert_eq!(statuses.len(), RPG_PROFESSIONS.len());
+        assert!(!statuses.contains(profession_status("Aventureiro")));
+    }
 }
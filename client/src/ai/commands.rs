@@ -0,0 +1,78 @@
+//! Comandos que os bots entendem no chat (`@Kael /mood`).
+//!
+//! Respondidos com texto pronto, antes de qualquer chamada ao modelo: a tabela
+//! `BOT_COMMANDS` define o conjunto e cada entrada monta a própria resposta.
+
+use super::{AiBotProfile, mood::mood_label, profession_status};
+
+/// O que um comando pode consultar para responder.
+pub struct BotCommandContext<'a> {
+    pub bot: &'a AiBotProfile,
+    /// Temperatura atual do humor do bot.
+    pub temperature: f32,
+}
+
+pub struct BotCommand {
+    /// Nome após a barra, em minúsculas.
+    pub name: &'static str,
+    pub description: &'static str,
+    reply: fn(&BotCommandContext<'_>) -> String,
+}
+
+pub const BOT_COMMANDS: &[BotCommand] = &[
+    BotCommand {
+        name: "mood",
+        description: "humor e persona atuais",
+        reply: |ctx| {
+            format!(
+                "{} aqui, {} no momento ({}). Humor: {} (temp {:.2}).",
+                ctx.bot.name,
+                profession_status(&ctx.bot.profession).to_lowercase(),
+                ctx.bot.profession,
+                mood_label(ctx.temperature),
+                ctx.temperature
+            )
+        },
+    },
+    BotCommand {
+        name: "whoami",
+        description: "nome e profissão do bot",
+        reply: |ctx| format!("Sou {}, {}.", ctx.bot.name, ctx.bot.profession),
+    },
+    BotCommand {
+        name: "commands",
+        description: "lista os comandos dos bots",
+        reply: |_| {
+            let list: Vec<String> = BOT_COMMANDS
+                .iter()
+                .map(|command| format!("/{} ({})", command.name, command.description))
+                .collect();
+            format!("Comandos: {}", list.join(", "))
+        },
+    },
+];
+
+/// Comando endereçado a `bot_name`: `@Nome /comando` em qualquer ponto do texto.
+///
+/// Comandos fora da tabela não contam; a mensagem segue para o modelo.
+pub fn find_bot_command(bot_name: &str, text: &str) -> Option<&'static BotCommand> {
+    let mention = format!("@{}", bot_name.to_lowercase());
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    tokens.windows(2).find_map(|pair| {
+        let target = pair[0].trim_end_matches([',', ':']).to_lowercase();
+        let name = pair[1]
+            .strip_prefix('/')?
+            .trim_end_matches(['?', '!', '.'])
+            .to_lowercase();
+        if target != mention {
+            return None;
+        }
+        BOT_COMMANDS.iter().find(|command| command.name == name)
+    })
+}
+
+/// Resposta pronta do bot se `text` tiver um comando para ele; `None` segue para o modelo.
+pub fn bot_command_reply(bot: &AiBotProfile, text: &str, temperature: f32) -> Option<String> {
+    let command = find_bot_command(&bot.name, text)?;
+    Some((command.reply)(&BotCommandContext { bot, temperature }))
+}
@@ -1,12 +1,36 @@
-/// Quantidade de bots de IA instanciados no startup.
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use super::FANTASY_NAMES;
+
+/// Quantidade padrão de bots de IA instanciados no startup (`ai.bot_count`).
 pub const AI_BOT_COUNT: usize = 3;
 
+/// Limita a quantidade pedida de bots a `0..=FANTASY_NAMES.len()`.
+///
+/// Mais bots que nomes do pool exigiriam sufixos (`Aelric-2`); zero roda o chat só com humanos.
+pub fn clamp_bot_count(requested: i64) -> usize {
+    usize::try_from(requested.max(0))
+        .unwrap_or(usize::MAX)
+        .min(FANTASY_NAMES.len())
+}
+
 /// Chance de uma IA responder outra IA quando nao ha humanos online.
 pub const AI_TO_AI_REPLY_CHANCE_IDLE: f64 = 0.22;
 /// Chance de uma IA responder outra IA quando ha humanos online.
 pub const AI_TO_AI_REPLY_CHANCE_WITH_HUMANS: f64 = 0.06;
 /// Limite de encadeamento IA->IA para evitar flood.
 pub const MAX_AI_CHAIN_MESSAGES: usize = 5;
+/// Orçamento padrão, em tokens estimados, do histórico de cada bot no prompt.
+pub const AI_HISTORY_TOKEN_BUDGET: usize = 400;
+/// Padrão de mensagens novas que podem acionar bots em um único tick.
+pub const AI_MAX_DISPATCH_PER_TICK: usize = 3;
+
+/// Peso mínimo de cada bot no sorteio de quem responde: todos podem responder.
+pub const AI_RESPONDER_BASE_WEIGHT: f64 = 1.0;
+/// Peso somado por palavra-chave da mensagem ligada à profissão do bot.
+pub const AI_RESPONDER_KEYWORD_WEIGHT: f64 = 2.0;
 
 /// Chance de iniciar conversa espontanea entre IAs em cada tentativa.
 pub const AI_PROACTIVE_START_CHANCE: f64 = 0.45;
@@ -14,3 +38,77 @@ pub const AI_PROACTIVE_START_CHANCE: f64 = 0.45;
 pub const AI_PROACTIVE_COOLDOWN_SECS: u64 = 18;
 /// Janela de inatividade do chat antes de permitir conversa espontanea.
 pub const AI_PROACTIVE_IDLE_SECS: u64 = 8;
+
+/// Ritmo da conversa entre bots (`[ai.tuning]` ou variáveis de ambiente);
+/// o padrão são as constantes acima.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AiTuning {
+    /// Chance de iniciar conversa espontânea em cada tentativa.
+    pub proactive_start_chance: f64,
+    /// Intervalo mínimo entre tentativas de conversa espontânea, em segundos.
+    pub proactive_cooldown_secs: u64,
+    /// Inatividade do chat antes de permitir conversa espontânea, em segundos.
+    pub proactive_idle_secs: u64,
+    /// Chance de um bot responder outro sem humanos online.
+    pub ai_reply_chance_idle: f64,
+    /// Chance de um bot responder outro com humanos online.
+    pub ai_reply_chance_with_humans: f64,
+}
+
+impl Default for AiTuning {
+    fn default() -> Self {
+        Self {
+            proactive_start_chance: AI_PROACTIVE_START_CHANCE,
+            proactive_cooldown_secs: AI_PROACTIVE_COOLDOWN_SECS,
+            proactive_idle_secs: AI_PROACTIVE_IDLE_SECS,
+            ai_reply_chance_idle: AI_TO_AI_REPLY_CHANCE_IDLE,
+            ai_reply_chance_with_humans: AI_TO_AI_REPLY_CHANCE_WITH_HUMANS,
+        }
+    }
+}
+
+impl AiTuning {
+    /// Chances trazidas para `0.0..=1.0`; valores inválidos (NaN) voltam ao padrão.
+    pub fn validated(self) -> Self {
+        let chance = |value: f64, default: f64| {
+            if value.is_nan() {
+                default
+            } else {
+                value.clamp(0.0, 1.0)
+            }
+        };
+        let defaults = Self::default();
+        Self {
+            proactive_start_chance: chance(
+                self.proactive_start_chance,
+                defaults.proactive_start_chance,
+            ),
+            ai_reply_chance_idle: chance(self.ai_reply_chance_idle, defaults.ai_reply_chance_idle),
+            ai_reply_chance_with_humans: chance(
+                self.ai_reply_chance_with_humans,
+                defaults.ai_reply_chance_with_humans,
+            ),
+            ..self
+        }
+    }
+}
+
+/// "Digitação" simulada antes de um bot publicar: tempo por caractere da resposta.
+pub const AI_TYPING_DELAY_PER_CHAR: Duration = Duration::from_millis(40);
+/// Teto da digitação simulada, para respostas longas não sumirem por muito tempo.
+pub const AI_MAX_TYPING_DELAY: Duration = Duration::from_secs(4);
+
+/// Mensagens recentes do chat comparadas com cada resposta antes de publicar.
+pub const AI_ECHO_WINDOW: usize = 8;
+/// Semelhança (Jaccard entre palavras) a partir da qual a resposta é descartada como eco.
+pub const AI_ECHO_THRESHOLD: f64 = 0.7;
+/// Respostas mais curtas que isso ("sim", "haha") nunca contam como eco.
+pub const AI_ECHO_MIN_WORDS: usize = 3;
+
+/// Quanto um texto de `/ai`/`/ask` espera a própria mensagem voltar do servidor;
+/// depois disso (envio rejeitado, texto alterado) a frase volta a acionar bots.
+pub const AI_FORCED_PROMPT_TTL: Duration = Duration::from_secs(10);
+
+/// Threads fixas que atendem os pedidos ao Ollama; o excedente espera na fila.
+pub const AI_WORKER_THREADS: usize = 2;
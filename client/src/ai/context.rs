@@ -2,7 +2,62 @@ use std::collections::{HashMap, HashSet};
 
 use crate::state::AppState;
 
-use super::{MAX_CONTEXT_MESSAGE_LEN, MAX_CONTEXT_MESSAGES, short_identity, truncate_for_context};
+use super::{
+    MAX_CONTEXT_ANNOUNCEMENTS, MAX_CONTEXT_MESSAGE_LEN, MAX_CONTEXT_MESSAGES, short_identity,
+    truncate_for_context,
+};
+
+/// Idioma predominante da conversa, usado para escolher o prompt base.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Lang {
+    #[default]
+    Portuguese,
+    English,
+}
+
+/// Palavras frequentes que só aparecem em um dos idiomas; as ambíguas ("a",
+/// "no", "do", "me") ficam de fora.
+const PT_STOPWORDS: &[&str] = &[
+    "e", "o", "os", "as", "um", "uma", "de", "da", "das", "dos", "em", "na", "nas", "nos", "que",
+    "nao", "não", "com", "para", "pra", "por", "mas", "voce", "você", "eu", "ele", "ela", "isso",
+    "esse", "essa", "tem", "tudo", "muito", "mais", "como", "ja", "já", "sim", "bem", "aqui",
+    "entao", "então", "tambem", "também", "ou", "seu", "sua", "meu", "minha", "foi", "vai", "ta",
+    "tá", "esta", "está", "sao", "são",
+];
+const EN_STOPWORDS: &[&str] = &[
+    "the", "and", "is", "are", "was", "were", "you", "your", "i", "it", "this", "that", "what",
+    "with", "for", "of", "to", "in", "on", "have", "has", "not", "but", "just", "they", "we", "my",
+    "be", "can", "will", "how", "there", "here", "about", "yeah", "yes", "so", "if", "at", "don't",
+    "it's", "i'm", "all", "from",
+];
+/// Mínimo de palavras típicas do inglês antes de trocar de idioma.
+const MIN_ENGLISH_HITS: usize = 3;
+
+/// Palpite do idioma do chat pela contagem de palavras frequentes de cada língua.
+///
+/// Só vira inglês com vantagem clara (o dobro de ocorrências); na dúvida, português.
+pub(crate) fn guess_language(messages: &[String]) -> Lang {
+    let (mut pt, mut en) = (0usize, 0usize);
+    for message in messages {
+        // "[hora] nome: texto": o prefixo não diz nada sobre o idioma.
+        let text = message
+            .split_once(": ")
+            .map_or(message.as_str(), |(_, t)| t);
+        for word in text.split(|c: char| !(c.is_alphanumeric() || c == '\'')) {
+            let word = word.trim_matches('\'').to_lowercase();
+            if PT_STOPWORDS.contains(&word.as_str()) {
+                pt += 1;
+            } else if EN_STOPWORDS.contains(&word.as_str()) {
+                en += 1;
+            }
+        }
+    }
+    if en >= MIN_ENGLISH_HITS && en > pt * 2 {
+        Lang::English
+    } else {
+        Lang::Portuguese
+    }
+}
 
 /// Dados consolidados do estado atual para injeção no prompt.
 #[derive(Default)]
@@ -11,6 +66,8 @@ pub(crate) struct AiPromptContext {
     pub(crate) requester_name: String,
     pub(crate) online_users: Vec<String>,
     pub(crate) recent_messages: Vec<String>,
+    /// Avisos recentes do host, injetados com prioridade no prompt.
+    pub(crate) announcements: Vec<String>,
 }
 
 /// Extrai do estado as informações úteis para resposta contextual da IA.
@@ -61,7 +118,7 @@ pub(crate) fn build_prompt_context(state: &AppState) -> AiPromptContext {
         .messages
         .iter()
         .rev()
-        .filter(|m| !m.text.trim().is_empty())
+        .filter(|m| !m.text.trim().is_empty() && !m.is_pending)
         .filter(|m| m.sender == "System" || online_identities.contains(m.sender.as_str()))
         .take(MAX_CONTEXT_MESSAGES)
         .map(|m| {
@@ -82,11 +139,24 @@ pub(crate) fn build_prompt_context(state: &AppState) -> AiPromptContext {
         .collect();
     recent_messages.reverse();
 
+    // Avisos do host valem mesmo que ele esteja offline.
+    let mut announcements: Vec<String> = state
+        .ui
+        .messages
+        .iter()
+        .rev()
+        .filter(|m| m.is_announcement && !m.text.trim().is_empty())
+        .take(MAX_CONTEXT_ANNOUNCEMENTS)
+        .map(|m| truncate_for_context(&m.text, MAX_CONTEXT_MESSAGE_LEN))
+        .collect();
+    announcements.reverse();
+
     AiPromptContext {
         requester_identity,
         requester_name,
         online_users,
         recent_messages,
+        announcements,
     }
 }
 
@@ -103,8 +173,21 @@ pub(crate) fn build_context_system_prompt(context: &AiPromptContext) -> String {
         context.recent_messages.join("\n")
     };
 
+    let announcements = if context.announcements.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "Avisos do host (prioridade, respeite-os):\n{}\n",
+            context.announcements.join("\n")
+        )
+    };
+
     format!(
-        "Contexto do chat atual:\n- Usuario que te chamou: {} ({})\n- Usuarios online: {}\n- Ultimas mensagens no chat (ordem cronologica):\n{}\nFoque apenas em quem esta online agora e nao puxe conversa com usuarios offline.\nUse esse contexto para responder de forma coerente.",
-        context.requester_name, context.requester_identity, online_users, recent_messages
+        "{}Contexto do chat atual:\n- Usuario que te chamou: {} ({})\n- Usuarios online: {}\n- Ultimas mensagens no chat (ordem cronologica):\n{}\nFoque apenas em quem esta online agora e nao puxe conversa com usuarios offline.\nUse esse contexto para responder de forma coerente.",
+        announcements,
+        context.requester_name,
+        context.requester_identity,
+        online_users,
+        recent_messages
     )
 }
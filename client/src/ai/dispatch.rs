@@ -0,0 +1,201 @@
+use std::time::Instant;
+
+use super::config::{AI_FORCED_PROMPT_TTL, MAX_AI_CHAIN_MESSAGES};
+
+/// Quando os bots podem falar no chat.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BotReplyMode {
+    /// Comportamento padrão: respostas por menção, por sorteio e conversa espontânea.
+    #[default]
+    Ambient,
+    /// Bots só respondem quando chamados pelo nome; sem sorteio nem conversa espontânea.
+    SpokenToOnly,
+}
+
+impl BotReplyMode {
+    /// Interpreta `ai.bots_mode` / `SHELLRELAY_BOTS_MODE` (`spoken-to` ativa o modo restrito).
+    pub fn from_setting(raw: &str) -> Self {
+        if raw.trim().eq_ignore_ascii_case("spoken-to") {
+            Self::SpokenToOnly
+        } else {
+            Self::Ambient
+        }
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Ambient => Self::SpokenToOnly,
+            Self::SpokenToOnly => Self::Ambient,
+        }
+    }
+
+    /// Conversa espontânea entre bots só existe no modo ambiente.
+    pub fn allows_proactive_chat(self) -> bool {
+        self == Self::Ambient
+    }
+}
+
+/// Decide qual bot responde a uma mensagem.
+///
+/// O bot chamado diretamente sempre tem prioridade; o sorteio ambiente
+/// (`ambient`) só é consultado quando o modo permite.
+pub fn choose_dispatch_target<T>(
+    mode: BotReplyMode,
+    directed: Option<T>,
+    ambient: impl FnOnce() -> Option<T>,
+) -> Option<T> {
+    match (directed, mode) {
+        (Some(bot), _) => Some(bot),
+        (None, BotReplyMode::Ambient) => ambient(),
+        (None, BotReplyMode::SpokenToOnly) => None,
+    }
+}
+
+/// Índices dos `names` citados em `text` (sem diferenciar caixa), na ordem em
+/// que aparecem na mensagem.
+pub fn mentioned_names(names: &[&str], text: &str) -> Vec<usize> {
+    let lowered = text.to_lowercase();
+    let mut found: Vec<(usize, usize)> = names
+        .iter()
+        .enumerate()
+        .filter(|(_, name)| !name.trim().is_empty())
+        .filter_map(|(index, name)| {
+            lowered
+                .find(&name.to_lowercase())
+                .map(|position| (position, index))
+        })
+        .collect();
+    found.sort_unstable();
+    found.into_iter().map(|(_, index)| index).collect()
+}
+
+/// Quantos dos bots citados numa mensagem respondem: o que ainda cabe na
+/// sequência de falas de bots (`MAX_AI_CHAIN_MESSAGES`), mas o primeiro citado
+/// sempre responde.
+pub fn directed_reply_limit(consecutive_ai_messages: usize) -> usize {
+    MAX_AI_CHAIN_MESSAGES
+        .saturating_sub(consecutive_ai_messages)
+        .max(1)
+}
+
+/// Pergunta aberta ao chat: termina com `?` e não menciona ninguém com `@`.
+pub fn is_open_question(text: &str) -> bool {
+    let trimmed = text.trim();
+    trimmed.ends_with('?') && !trimmed.contains('@')
+}
+
+/// Rodízio justo entre bots para perguntas abertas: cada pergunta vai para o
+/// próximo da fila, sem sorteio.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RoundRobin {
+    next: usize,
+}
+
+impl RoundRobin {
+    /// Escolhe exatamente um candidato (ou nenhum se a lista estiver vazia).
+    pub fn pick<T: Copy>(&mut self, candidates: &[T]) -> Option<T> {
+        if candidates.is_empty() {
+            return None;
+        }
+        let chosen = candidates[self.next % candidates.len()];
+        self.next = self.next.wrapping_add(1);
+        Some(chosen)
+    }
+}
+
+/// Textos de `/ai`/`/ask` já atendidos: a mensagem, ao chegar, não aciona outro bot.
+///
+/// Cada texto vale por `AI_FORCED_PROMPT_TTL`; um envio que falha não deixa a
+/// mesma frase ignorada para sempre.
+#[derive(Clone, Debug, Default)]
+pub struct ForcedPrompts {
+    entries: Vec<(String, Instant)>,
+}
+
+impl ForcedPrompts {
+    pub fn push(&mut self, text: &str, now: Instant) {
+        self.expire(now);
+        self.entries.push((text.trim().to_string(), now));
+    }
+
+    /// `true` (e consome a entrada) se `text` foi pedido com `/ai`/`/ask` há pouco.
+    pub fn take_match(&mut self, text: &str, now: Instant) -> bool {
+        self.expire(now);
+        let text = text.trim();
+        match self.entries.iter().position(|(prompt, _)| prompt == text) {
+            Some(index) => {
+                self.entries.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn expire(&mut self, now: Instant) {
+        self.entries
+            .retain(|(_, at)| now.saturating_duration_since(*at) < AI_FORCED_PROMPT_TTL);
+    }
+}
+
+/// Bot que atende `/ai` e `/ask` entre os nomes `online`: o pedido, se estiver
+/// online; senão qualquer um (`random` escolhe). O `bool` indica que o pedido
+/// não pôde ser atendido e outro bot responde no lugar. `None` sem bots online.
+pub fn forced_responder(
+    online: &[&str],
+    requested: Option<&str>,
+    random: usize,
+) -> Option<(usize, bool)> {
+    if online.is_empty() {
+        return None;
+    }
+    let any = random % online.len();
+    match requested {
+        Some(name) => Some(
+            online
+                .iter()
+                .position(|bot| bot.eq_ignore_ascii_case(name))
+                .map_or((any, true), |index| (index, false)),
+        ),
+        None => Some((any, false)),
+    }
+}
+
+/// Índice a partir do qual as mensagens novas de um tick podem acionar bots.
+///
+/// Só as `cap` mais recentes disparam respostas; as anteriores são apenas
+/// marcadas como vistas, evitando uma avalanche de respostas. `cap = 0` desativa o limite.
+pub fn capped_dispatch_start(new_messages: usize, cap: usize) -> usize {
+    if cap == 0 {
+        return 0;
+    }
+    new_messages.saturating_sub(cap)
+}
+
+/// Ordem em que as mensagens novas de um tick acionam bots (índices cronológicos).
+///
+/// `from_ai[i]` diz se a mensagem `i` veio de um bot. Sem `human_first`, vale o
+/// corte de `capped_dispatch_start`, em ordem cronológica. Com `human_first`, as
+/// mensagens de humanos ocupam o limite primeiro (as mais novas) e são despachadas
+/// antes das de bots; as vagas restantes ficam com os bots mais recentes.
+pub fn dispatch_order(from_ai: &[bool], cap: usize, human_first: bool) -> Vec<usize> {
+    if !human_first {
+        return (capped_dispatch_start(from_ai.len(), cap)..from_ai.len()).collect();
+    }
+    let of_kind =
+        |ai: bool| -> Vec<usize> { (0..from_ai.len()).filter(|&i| from_ai[i] == ai).collect() };
+    let humans = of_kind(false);
+    let bots = of_kind(true);
+    let limit = if cap == 0 { from_ai.len() } else { cap };
+    let human_slots = humans.len().min(limit);
+    let bot_slots = bots.len().min(limit - human_slots);
+
+    humans[humans.len() - human_slots..]
+        .iter()
+        .chain(&bots[bots.len() - bot_slots..])
+        .copied()
+        .collect()
+}
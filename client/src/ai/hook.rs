@@ -0,0 +1,59 @@
+//! Gancho de pós-processamento das respostas dos bots.
+//!
+//! Quem usa o client como biblioteca registra um `ReplyHook` antes de
+//! `client::run()` para assinar, traduzir ou filtrar as respostas sem fork.
+//! Sem registro, vale `NoopReplyHook`.
+
+use std::sync::OnceLock;
+
+use super::AiBotProfile;
+
+/// Transforma a resposta já normalizada de um bot antes de ela ir para o chat.
+///
+/// Devolver texto vazio descarta a resposta.
+pub trait ReplyHook: Send + Sync {
+    fn process(&self, bot: &AiBotProfile, reply: String) -> String;
+}
+
+impl<F> ReplyHook for F
+where
+    F: Fn(&AiBotProfile, String) -> String + Send + Sync,
+{
+    fn process(&self, bot: &AiBotProfile, reply: String) -> String {
+        self(bot, reply)
+    }
+}
+
+/// Gancho padrão: devolve a resposta intacta.
+pub struct NoopReplyHook;
+
+impl ReplyHook for NoopReplyHook {
+    fn process(&self, _bot: &AiBotProfile, reply: String) -> String {
+        reply
+    }
+}
+
+static REPLY_HOOK: OnceLock<Box<dyn ReplyHook>> = OnceLock::new();
+
+/// Registra o gancho global; só o primeiro registro vale (retorna `false` nos seguintes).
+pub fn set_reply_hook(hook: impl ReplyHook + 'static) -> bool {
+    REPLY_HOOK.set(Box::new(hook)).is_ok()
+}
+
+/// Gancho atual (`NoopReplyHook` enquanto nada for registrado).
+pub fn reply_hook() -> &'static dyn ReplyHook {
+    REPLY_HOOK
+        .get()
+        .map_or(&NoopReplyHook, |hook| hook.as_ref())
+}
+
+/// Aplica o gancho a uma resposta; `None` quando ele a descartou.
+pub(crate) fn post_process_reply(
+    hook: &dyn ReplyHook,
+    bot: &AiBotProfile,
+    reply: String,
+) -> Option<String> {
+    let processed = hook.process(bot, reply);
+    let processed = processed.trim();
+    (!processed.is_empty()).then(|| processed.to_string())
+}
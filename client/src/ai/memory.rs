@@ -0,0 +1,41 @@
+//! Memória dos bots entre execuções (`--persist-memory FILE`).
+//!
+//! O histórico de cada bot (`ai_histories`) vai para um JSON no encerramento
+//! e volta no startup, indexado pelo nome do bot:
+//!
+//! ```json
+//! { "Aelric": [{ "role": "user", "content": "Oi!" }, { "role": "assistant", "content": "Olá." }] }
+//! ```
+
+use std::{collections::HashMap, fs, io::ErrorKind, path::Path};
+
+use crate::state::AiHistoryEntry;
+
+use super::trim_history;
+
+pub type BotMemory = HashMap<String, Vec<AiHistoryEntry>>;
+
+/// Lê a memória de `text`, aplicando o mesmo corte de histórico das respostas.
+pub fn parse_memory(text: &str, token_budget: usize) -> Result<BotMemory, String> {
+    let mut memory: BotMemory = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    memory.retain(|_, history| {
+        trim_history(history, token_budget);
+        !history.is_empty()
+    });
+    Ok(memory)
+}
+
+/// Carrega a memória salva; arquivo ausente equivale a memória vazia (primeira execução).
+pub fn load_memory(path: &Path) -> Result<BotMemory, String> {
+    match fs::read_to_string(path) {
+        Ok(text) => parse_memory(&text, crate::config::current().ai.history_token_budget),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(BotMemory::new()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Grava a memória dos bots, substituindo o arquivo anterior.
+pub fn save_memory(path: &Path, memory: &BotMemory) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(memory).map_err(|e| e.to_string())?;
+    fs::write(path, text).map_err(|e| e.to_string())
+}
@@ -1,45 +1,95 @@
 use std::{
-    env,
     sync::{Arc, mpsc::Sender},
-    thread,
+    time::Duration,
 };
 
-use ollama_rs::{
-    Ollama,
-    generation::chat::{ChatMessage, request::ChatMessageRequest},
-    models::ModelOptions,
-};
+use ollama_rs::{Ollama, generation::chat::ChatMessage};
+use tokio::runtime::Runtime;
 
 use crate::{
+    config::AiConfig,
     state::{AiHistoryEntry, AiRole, AppState, SharedState, update_state},
-    sync::add_local_system_message,
+    sync::{
+        add_local_system_message, push_local_system_message, remove_local_system_message,
+        update_local_system_message,
+    },
 };
 
+mod backend;
 mod bots;
+mod commands;
 mod config;
 mod context;
+mod dispatch;
+mod hook;
+mod memory;
+mod mood;
+mod personas;
+mod pool;
+mod schedule;
 mod text;
 
-use context::AiPromptContext;
+use backend::{BackendKind, CompletionRequest, Sampling, select_backend};
+use context::{AiPromptContext, Lang, guess_language};
+use hook::post_process_reply;
+use mood::{MOOD_DEFAULT_TEMPERATURE, next_mood};
+use pool::ai_pool;
+use text::{CHARS_PER_TOKEN, estimate_tokens};
 
 pub use bots::{
-    AiBotProfile, FANTASY_NAMES, RPG_PROFESSIONS, generate_bot_profiles, profession_roleplay_style,
+    AiBotProfile, FANTASY_NAMES, RPG_PROFESSIONS, bot_models, generate_bot_profiles,
+    generate_bot_profiles_excluding, profession_roleplay_style, profession_status,
+    profession_weight,
 };
 pub use config::{
-    AI_BOT_COUNT, AI_PROACTIVE_COOLDOWN_SECS, AI_PROACTIVE_IDLE_SECS, AI_PROACTIVE_START_CHANCE,
-    AI_TO_AI_REPLY_CHANCE_IDLE, AI_TO_AI_REPLY_CHANCE_WITH_HUMANS, MAX_AI_CHAIN_MESSAGES,
+    AI_BOT_COUNT, AI_ECHO_WINDOW, AI_HISTORY_TOKEN_BUDGET, AI_MAX_DISPATCH_PER_TICK,
+    AI_PROACTIVE_COOLDOWN_SECS, AI_PROACTIVE_IDLE_SECS, AI_PROACTIVE_START_CHANCE,
+    AI_TO_AI_REPLY_CHANCE_IDLE, AI_TO_AI_REPLY_CHANCE_WITH_HUMANS, AiTuning, MAX_AI_CHAIN_MESSAGES,
+    clamp_bot_count,
+};
+pub use dispatch::{
+    BotReplyMode, ForcedPrompts, RoundRobin, choose_dispatch_target, directed_reply_limit,
+    dispatch_order, forced_responder, is_open_question, mentioned_names,
 };
+pub use hook::{NoopReplyHook, ReplyHook, reply_hook, set_reply_hook};
+pub use memory::{load_memory, save_memory};
+pub use personas::load_personas;
+pub use pool::{shutdown_ai_workers, start_ai_workers};
+pub use schedule::QuietHours;
+pub use text::{is_echo, typing_delay_for};
 
 /// Configuração padrão de acesso ao Ollama local.
-const DEFAULT_MODEL: &str = "mistral:7b";
-const DEFAULT_HOST: &str = "http://127.0.0.1";
-const DEFAULT_PORT: u16 = 11434;
+pub(crate) const DEFAULT_MODEL: &str = "mistral:7b";
+pub(crate) const DEFAULT_HOST: &str = "http://127.0.0.1";
+pub(crate) const DEFAULT_PORT: u16 = 11434;
+pub(crate) use backend::DEFAULT_OPENAI_URL;
+/// Tempo máximo de espera por uma resposta do Ollama, em segundos.
+pub(crate) const DEFAULT_TIMEOUT_SECS: u64 = 30;
+/// Tentativas por resposta quando a conexão com o Ollama falha.
+pub(crate) const DEFAULT_RETRIES: u32 = 3;
+/// Teto de `ai.retries`: um worker preso em tentativas deixa os outros bots na fila.
+pub(crate) const MAX_RETRIES: u32 = 10;
+/// Amostragem padrão das respostas (`ai.temperature`, `ai.top_p`...).
+pub(crate) const DEFAULT_TEMPERATURE: f32 = MOOD_DEFAULT_TEMPERATURE;
+pub(crate) const DEFAULT_TOP_P: f32 = 0.95;
+pub(crate) const DEFAULT_REPEAT_PENALTY: f32 = 1.35;
+pub(crate) const DEFAULT_NUM_PREDICT: i32 = 70;
+/// Faixa aceita de temperatura; valores fora são trazidos para a borda.
+const TEMPERATURE_RANGE: std::ops::RangeInclusive<f32> = 0.0..=2.0;
+/// Espera antes da segunda tentativa; dobra a cada nova falha.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(250);
+/// Teto da espera entre tentativas.
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(5);
 /// Quantidade máxima de turns guardados no histórico local da IA.
 const MAX_HISTORY_ENTRIES: usize = 12;
 /// Janela de contexto de mensagens recentes do chat.
 const MAX_CONTEXT_MESSAGES: usize = 16;
+/// Quantidade de avisos do host mantidos no prompt contextual.
+const MAX_CONTEXT_ANNOUNCEMENTS: usize = 3;
 /// Limite de tamanho por mensagem antes de injetar no prompt contextual.
 const MAX_CONTEXT_MESSAGE_LEN: usize = 320;
+/// Orçamento aproximado de tokens do texto de lore compartilhado.
+const MAX_LORE_TOKENS: usize = 300;
 /// Limite duro de tamanho da resposta final enviada ao chat.
 const MAX_REPLY_CHARS: usize = 220;
 
@@ -55,57 +105,115 @@ Nao use linguagem teatral, mistica, floreada ou palestrinha. \
 Nao diga que e IA, modelo ou assistente virtual. \
 Evite repetir a pergunta do usuario e evite repetir assunto sem novidade.";
 
+/// Variante do prompt base para salas que conversam em inglês.
+const BASE_SYSTEM_PROMPT_EN: &str = "You are a real person taking part in an English-speaking chat. \
+Always reply in English, naturally, short and direct, like everyday conversation. \
+Use at most 2 short sentences. \
+Match the tone of the conversation, no canned speeches and no catchphrases. \
+Avoid always starting with a greeting and avoid repeating the person's name in every message. \
+Do not introduce yourself, do not give a message of the day and do not become a narrator. \
+Do not offer help out of nowhere; only help if asked directly. \
+Avoid excessive formality (e.g. \"a pleasure\", \"at your service\", \"schedule a meeting\"). \
+Do not use theatrical, mystical, flowery or preachy language. \
+Never say you are an AI, a model or a virtual assistant. \
+Avoid repeating the user's question and avoid rehashing a topic with nothing new.";
+
+/// Pergunta usada na prévia de estilo quando o usuário não informa uma.
+const PREVIEW_DEFAULT_PROMPT: &str = "Oi! Como foi seu dia?";
+
 #[derive(Clone, Debug)]
 pub struct AiGeneratedReply {
     pub bot_name: String,
     pub text: String,
+    /// Pedida com `/ai`/`/ask`: se não for publicada, o usuário precisa saber.
+    pub forced: bool,
 }
 
 /// Dispara a geração da IA para um bot específico sem bloquear a UI.
+///
+/// `forced` marca os pedidos de `/ai`/`/ask` na resposta gerada.
 pub fn request_bot_reply(
     state: &SharedState,
     bot: AiBotProfile,
     incoming_text: String,
+    forced: bool,
     reply_tx: Sender<AiGeneratedReply>,
 ) {
-    let (history, prompt_context) = {
+    let (history, prompt_context, temperature) = {
         let mut snapshot = Vec::new();
         let mut prompt_context = AiPromptContext::default();
+        let mut temperature = MOOD_DEFAULT_TEMPERATURE;
         let history_key = bot.name.clone();
+        let token_budget = crate::config::current().ai.history_token_budget;
         update_state(state, |s| {
+            // Humor do bot: temperatura varia levemente a cada resposta.
+            let mood = s
+                .ai_moods
+                .entry(history_key.clone())
+                .or_insert(MOOD_DEFAULT_TEMPERATURE);
+            *mood = next_mood(*mood, &mut rand::rng());
+            temperature = *mood;
+
             let bot_history = s.ai_histories.entry(history_key).or_default();
             bot_history.push(AiHistoryEntry {
                 role: AiRole::User,
                 content: incoming_text.clone(),
             });
-            trim_history(bot_history);
+            trim_history(bot_history, token_budget);
             snapshot = bot_history.clone();
             prompt_context = build_prompt_context(s);
         });
-        (snapshot, prompt_context)
+        (snapshot, prompt_context, temperature)
     };
 
-    let state = Arc::clone(state);
-    thread::spawn(move || {
-        let result = fetch_ollama_reply(history, prompt_context, &bot);
+    submit_ai_job(state, move |runtime, state| {
+        let show_partial = crate::config::current().ai.show_partial_replies;
+        let mut partial_id = None;
+        let on_partial = |partial: &str| {
+            if !show_partial {
+                return;
+            }
+            let text = partial_reply_text(partial);
+            match partial_id {
+                Some(id) => update_local_system_message(state, id, text),
+                None => partial_id = Some(push_local_system_message(state, bot.name.clone(), text)),
+            }
+        };
+        // O gancho roda depois de `normalize_reply` e antes do histórico e do chat.
+        let result = fetch_ollama_reply(
+            runtime,
+            history,
+            prompt_context,
+            &bot,
+            temperature,
+            on_partial,
+        )
+        .map(|reply| post_process_reply(reply_hook(), &bot, reply));
+        // A resposta final chega ao chat pelo servidor; a prévia parcial sai antes.
+        if let Some(id) = partial_id {
+            remove_local_system_message(state, id);
+        }
         match result {
-            Ok(reply) => {
+            Ok(None) => {}
+            Ok(Some(reply)) => {
                 let history_key = bot.name.clone();
-                update_state(&state, |s| {
+                let token_budget = crate::config::current().ai.history_token_budget;
+                update_state(state, |s| {
                     let bot_history = s.ai_histories.entry(history_key).or_default();
                     bot_history.push(AiHistoryEntry {
                         role: AiRole::Assistant,
                         content: reply.clone(),
                     });
-                    trim_history(bot_history);
+                    trim_history(bot_history, token_budget);
                 });
 
                 if let Err(err) = reply_tx.send(AiGeneratedReply {
                     bot_name: bot.name.clone(),
                     text: reply,
+                    forced,
                 }) {
                     add_local_system_message(
-                        &state,
+                        state,
                         "System",
                         format!("Erro ao enfileirar resposta da IA ({}): {err}", bot.name),
                     );
@@ -113,7 +221,7 @@ pub fn request_bot_reply(
             }
             Err(err) => {
                 add_local_system_message(
-                    &state,
+                    state,
                     "System",
                     format!("Erro ao chamar Ollama ({}): {err}", bot.name),
                 );
@@ -122,61 +230,241 @@ pub fn request_bot_reply(
     });
 }
 
+/// Tira do histórico de `bot_name` a resposta `text` que não chegou ao chat
+/// (descartada como eco), para o bot não "lembrar" de algo que não disse.
+pub fn forget_reply(state: &SharedState, bot_name: &str, text: &str) {
+    update_state(state, |s| {
+        if let Some(history) = s.ai_histories.get_mut(bot_name) {
+            remove_last_reply(history, text);
+        }
+    });
+}
+
+/// Remove a entrada `Assistant` mais recente com `text`; `false` se não houver.
+fn remove_last_reply(history: &mut Vec<AiHistoryEntry>, text: &str) -> bool {
+    let position = history
+        .iter()
+        .rposition(|entry| entry.role == AiRole::Assistant && entry.content == text);
+    position.map(|index| history.remove(index)).is_some()
+}
+
+/// Resposta pronta de `@bot /comando` com o humor atual do bot; `None` segue para o modelo.
+pub fn bot_command_reply(state: &AppState, bot: &AiBotProfile, text: &str) -> Option<String> {
+    let temperature = state
+        .ai_moods
+        .get(&bot.name)
+        .copied()
+        .unwrap_or(MOOD_DEFAULT_TEMPERATURE);
+    commands::bot_command_reply(bot, text, temperature)
+}
+
+/// Pedido avulso de prévia: mesmo prompt de sistema do bot, sem histórico.
+struct BotPreview {
+    history: Vec<AiHistoryEntry>,
+    temperature: f32,
+}
+
+/// Monta a prévia lendo o humor atual do bot, sem avançá-lo nem gravar histórico.
+fn build_bot_preview(state: &AppState, bot: &AiBotProfile, prompt: &str) -> BotPreview {
+    let prompt = match prompt.trim() {
+        "" => PREVIEW_DEFAULT_PROMPT,
+        prompt => prompt,
+    };
+    BotPreview {
+        history: vec![AiHistoryEntry {
+            role: AiRole::User,
+            content: prompt.to_string(),
+        }],
+        temperature: state
+            .ai_moods
+            .get(&bot.name)
+            .copied()
+            .unwrap_or(MOOD_DEFAULT_TEMPERATURE),
+    }
+}
+
+fn format_preview(bot_name: &str, temperature: f32, reply: &str) -> String {
+    format!("Preview {bot_name} (temp {temperature:.2}): {reply}")
+}
+
+/// Gera uma resposta de amostra do bot e mostra como mensagem local do sistema.
+///
+/// Nada vai para o chat: o histórico e o humor do bot ficam intactos.
+pub fn request_bot_preview(state: &SharedState, bot: AiBotProfile, prompt: String) {
+    let (preview, prompt_context) = {
+        let guard = state.lock().expect("state poisoned");
+        (
+            build_bot_preview(&guard, &bot, &prompt),
+            build_prompt_context(&guard),
+        )
+    };
+
+    submit_ai_job(state, move |runtime, state| {
+        let text = match fetch_ollama_reply(
+            runtime,
+            preview.history,
+            prompt_context,
+            &bot,
+            preview.temperature,
+            |_| {},
+        )
+        .map(|reply| post_process_reply(reply_hook(), &bot, reply))
+        {
+            Ok(Some(reply)) => format_preview(&bot.name, preview.temperature, &reply),
+            Ok(None) => format!("Preview {}: resposta descartada pelo gancho", bot.name),
+            Err(err) => format!("Erro ao chamar Ollama ({}): {err}", bot.name),
+        };
+        add_local_system_message(state, "System", text);
+    });
+}
+
+/// Enfileira um pedido no pool de workers da IA; se o pool não subiu, avisa no chat.
+fn submit_ai_job(state: &SharedState, job: impl FnOnce(&Runtime, &SharedState) + Send + 'static) {
+    let job_state = Arc::clone(state);
+    let submitted = ai_pool().and_then(|pool| pool.submit(move |runtime| job(runtime, &job_state)));
+    if let Err(err) = submitted {
+        add_local_system_message(state, "System", format!("Erro ao chamar Ollama: {err}"));
+    }
+}
+
+/// Texto da prévia enquanto o bot ainda gera: espaços compactados e reticências.
+fn partial_reply_text(partial: &str) -> String {
+    let text = partial.split_whitespace().collect::<Vec<_>>().join(" ");
+    format!("{text}…")
+}
+
+/// Pede a resposta ao backend configurado (`ai.backend`) em streaming; `on_partial`
+/// recebe o texto acumulado a cada pedaço e o resultado final passa por
+/// `normalize_reply` como sempre.
 fn fetch_ollama_reply(
+    runtime: &Runtime,
     history: Vec<AiHistoryEntry>,
     prompt_context: AiPromptContext,
     bot: &AiBotProfile,
+    temperature: f32,
+    mut on_partial: impl FnMut(&str),
 ) -> Result<String, String> {
-    let model = env::var("OLLAMA_MODEL").unwrap_or_else(|_| DEFAULT_MODEL.to_string());
-    let host = env::var("OLLAMA_HOST").unwrap_or_else(|_| DEFAULT_HOST.to_string());
-    let port = env::var("OLLAMA_PORT")
-        .ok()
-        .and_then(|p| p.parse::<u16>().ok())
-        .unwrap_or(DEFAULT_PORT);
-
-    let runtime = tokio::runtime::Builder::new_current_thread()
-        .enable_all()
-        .build()
-        .map_err(|e| format!("falha ao iniciar runtime async: {e}"))?;
-
-    runtime.block_on(async move {
-        let client = Ollama::new(host, port);
-        let roleplay_prompt = build_roleplay_system_prompt(bot);
-        let mut messages = vec![
-            ChatMessage::system(BASE_SYSTEM_PROMPT.to_string()),
-            ChatMessage::system(roleplay_prompt),
-            ChatMessage::system(build_context_system_prompt(&prompt_context)),
-        ];
-
-        for entry in history {
-            if entry.content.trim().is_empty() {
-                continue;
-            }
+    let ai_config = &crate::config::current().ai;
+    let lang = guess_language(&prompt_context.recent_messages);
+    let base_prompt = base_system_prompt(&ai_config.system_prompt, lang);
+    let request = CompletionRequest {
+        model: bot.model.clone(),
+        messages: build_chat_messages(base_prompt, &ai_config.lore, bot, &prompt_context, history),
+        sampling: sampling_options(ai_config, temperature),
+        // Sem o limite, um modelo travado prende a thread do pedido para sempre.
+        timeout: Duration::from_secs(ai_config.timeout_secs),
+        max_attempts: ai_config.retries.max(1),
+    };
 
-            let message = match entry.role {
-                AiRole::User => ChatMessage::user(entry.content),
-                AiRole::Assistant => ChatMessage::assistant(entry.content),
-            };
-            messages.push(message);
-        }
+    let content = select_backend(ai_config).complete(runtime, request, &mut on_partial)?;
+    let reply = normalize_reply(&content);
+    if reply.is_empty() {
+        return Err("resposta vazia do modelo".to_string());
+    }
+    Ok(reply)
+}
+
+/// Opções de amostragem da configuração, já validadas.
+///
+/// O humor do bot (`mood_temperature`) desloca a temperatura configurada na
+/// mesma medida em que se afasta de `MOOD_DEFAULT_TEMPERATURE`.
+fn sampling_options(ai_config: &AiConfig, mood_temperature: f32) -> Sampling {
+    let temperature = (ai_config.temperature + mood_temperature - MOOD_DEFAULT_TEMPERATURE)
+        .clamp(*TEMPERATURE_RANGE.start(), *TEMPERATURE_RANGE.end());
+    Sampling {
+        temperature,
+        top_p: ai_config.top_p.clamp(0.0, 1.0),
+        repeat_penalty: ai_config.repeat_penalty.max(0.0),
+        max_tokens: ai_config.num_predict.max(1),
+    }
+}
+
+/// Prompt base dos bots: o configurado (`ai.system_prompt`) ou, vazio, o embutido
+/// no idioma detectado da conversa.
+fn base_system_prompt(configured: &str, lang: Lang) -> &str {
+    match (configured.trim(), lang) {
+        ("", Lang::Portuguese) => BASE_SYSTEM_PROMPT,
+        ("", Lang::English) => BASE_SYSTEM_PROMPT_EN,
+        (prompt, _) => prompt,
+    }
+}
 
-        let options = ModelOptions::default()
-            .num_predict(70)
-            .temperature(0.85)
-            .top_p(0.95)
-            .repeat_penalty(1.35);
-        let request = ChatMessageRequest::new(model, messages).options(options);
-        let response = client
-            .send_chat_messages(request)
-            .await
-            .map_err(|e| e.to_string())?;
-
-        let reply = normalize_reply(&response.message.content);
-        if reply.is_empty() {
-            return Err("resposta vazia do modelo".to_string());
+/// Mensagens enviadas ao modelo: prompt base, lore, roleplay do bot, contexto do
+/// chat e, por fim, o histórico do bot (entradas vazias são puladas).
+fn build_chat_messages(
+    base_prompt: &str,
+    lore: &str,
+    bot: &AiBotProfile,
+    prompt_context: &AiPromptContext,
+    history: Vec<AiHistoryEntry>,
+) -> Vec<ChatMessage> {
+    let mut messages = vec![ChatMessage::system(base_prompt.to_string())];
+    if let Some(lore_prompt) = build_lore_system_prompt(lore) {
+        messages.push(ChatMessage::system(lore_prompt));
+    }
+    messages.push(ChatMessage::system(build_roleplay_system_prompt(bot)));
+    messages.push(ChatMessage::system(build_context_system_prompt(
+        prompt_context,
+    )));
+
+    for entry in history {
+        if entry.content.trim().is_empty() {
+            continue;
         }
-        Ok(reply)
-    })
+
+        let message = match entry.role {
+            AiRole::User => ChatMessage::user(entry.content),
+            AiRole::Assistant => ChatMessage::assistant(entry.content),
+        };
+        messages.push(message);
+    }
+    messages
+}
+
+/// Confere em segundo plano se o Ollama responde; se não, explica no chat como subi-lo.
+pub fn check_ollama_health(state: &SharedState) {
+    if BackendKind::from_setting(&crate::config::current().ai.backend) != BackendKind::Ollama {
+        return;
+    }
+    submit_ai_job(state, |runtime, state| {
+        let ai_config = &crate::config::current().ai;
+        let client = Ollama::new(ai_config.host.clone(), ai_config.port);
+        let timeout = Duration::from_secs(ai_config.timeout_secs);
+        let result = runtime
+            .block_on(async { tokio::time::timeout(timeout, client.list_local_models()).await });
+        let err = match result {
+            Ok(Ok(_)) => return,
+            Ok(Err(err)) => err.to_string(),
+            Err(_) => timeout_error_message(timeout),
+        };
+        add_local_system_message(
+            state,
+            "System",
+            ollama_unavailable_notice(&ai_config.host, ai_config.port, &err),
+        );
+    });
+}
+
+/// Aviso de Ollama fora do ar, com o comando para subir e as variáveis de ambiente.
+fn ollama_unavailable_notice(host: &str, port: u16, err: &str) -> String {
+    format!(
+        "Ollama indisponível em {host}:{port} ({err}): os bots não vão responder. \
+        Rode `ollama serve` (e `ollama pull <modelo>`), ou ajuste OLLAMA_HOST, OLLAMA_PORT e OLLAMA_MODEL."
+    )
+}
+
+/// Texto exibido no chat quando o Ollama não responde dentro de `timeout`.
+fn timeout_error_message(timeout: Duration) -> String {
+    format!("sem resposta do Ollama em {}s", timeout.as_secs())
+}
+
+/// Espera antes da próxima tentativa depois de `failed_attempts` falhas seguidas,
+/// dobrando até `RETRY_MAX_DELAY`.
+fn retry_delay(failed_attempts: u32) -> Duration {
+    let factor = 1u32
+        .checked_shl(failed_attempts.saturating_sub(1))
+        .unwrap_or(u32::MAX);
+    RETRY_BASE_DELAY.saturating_mul(factor).min(RETRY_MAX_DELAY)
 }
 
 fn build_roleplay_system_prompt(bot: &AiBotProfile) -> String {
@@ -187,15 +475,49 @@ fn build_roleplay_system_prompt(bot: &AiBotProfile) -> String {
         Mantenha respostas curtas e naturais.",
         bot.name,
         bot.profession,
-        profession_roleplay_style(&bot.profession)
+        match bot.style.trim() {
+            "" => profession_roleplay_style(&bot.profession),
+            style => style,
+        }
     )
 }
 
-fn trim_history(history: &mut Vec<AiHistoryEntry>) {
+/// Fatos do mundo compartilhados por todos os bots (`ai.lore` na config).
+/// Separado do roleplay individual; `None` quando não configurado.
+fn build_lore_system_prompt(lore: &str) -> Option<String> {
+    if lore.trim().is_empty() {
+        return None;
+    }
+    let lore = truncate_for_context(lore, MAX_LORE_TOKENS * CHARS_PER_TOKEN);
+    Some(format!(
+        "Fatos do mundo que todos no chat conhecem (use quando fizer sentido, sem recitar): {lore}"
+    ))
+}
+
+/// Descarta as entradas mais antigas até caber em `MAX_HISTORY_ENTRIES` e no
+/// orçamento de tokens estimados (`0` = sem orçamento).
+///
+/// A entrada mais recente sempre fica, mesmo sozinha acima do orçamento.
+fn trim_history(history: &mut Vec<AiHistoryEntry>, token_budget: usize) {
     if history.len() > MAX_HISTORY_ENTRIES {
         let to_drop = history.len() - MAX_HISTORY_ENTRIES;
         history.drain(0..to_drop);
     }
+    if token_budget == 0 {
+        return;
+    }
+
+    let mut used = 0usize;
+    let fitting = history
+        .iter()
+        .rev()
+        .take_while(|entry| {
+            used += estimate_tokens(&entry.content);
+            used <= token_budget
+        })
+        .count();
+    let to_drop = history.len().saturating_sub(fitting.max(1));
+    history.drain(0..to_drop);
 }
 
 fn build_prompt_context(state: &AppState) -> AiPromptContext {
@@ -0,0 +1,31 @@
+use rand::{Rng, RngExt};
+
+/// Temperatura inicial de todo bot (mesmo valor fixo usado antes do humor).
+pub(crate) const MOOD_DEFAULT_TEMPERATURE: f32 = 0.85;
+/// Limites do passeio aleatório de temperatura.
+pub(crate) const MOOD_MIN_TEMPERATURE: f32 = 0.6;
+pub(crate) const MOOD_MAX_TEMPERATURE: f32 = 1.1;
+/// Variação máxima por resposta.
+const MOOD_MAX_STEP: f32 = 0.08;
+
+/// Rótulo do humor para humanos (`@bot /mood`): mais calmo perto do mínimo, mais agitado perto do máximo.
+pub(crate) fn mood_label(temperature: f32) -> &'static str {
+    match temperature {
+        t if t < 0.75 => "sereno",
+        t if t <= 0.95 => "tranquilo",
+        _ => "agitado",
+    }
+}
+
+/// Avança o humor do bot um passo do passeio aleatório, sempre dentro dos limites.
+///
+/// Recebe o gerador explicitamente para permitir seed fixa em testes.
+pub(crate) fn next_mood<R: Rng + ?Sized>(current: f32, rng: &mut R) -> f32 {
+    let current = if current.is_finite() {
+        current
+    } else {
+        MOOD_DEFAULT_TEMPERATURE
+    };
+    let step = rng.random_range(-MOOD_MAX_STEP..=MOOD_MAX_STEP);
+    (current + step).clamp(MOOD_MIN_TEMPERATURE, MOOD_MAX_TEMPERATURE)
+}
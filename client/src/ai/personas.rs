@@ -0,0 +1,75 @@
+//! Personas dos bots lidas de arquivo (`--personas personas.toml`).
+//!
+//! Substituem os nomes e profissões sorteados para montar salas temáticas:
+//!
+//! ```toml
+//! [[persona]]
+//! name = "Deckard"
+//! profession = "Detetive"
+//! style = "Fala pouco, desconfiado, sempre com chuva no assunto."
+//! model = "llama3"   # opcional: sem ele vale o rodízio de `ai.models`/`ai.model`
+//! ```
+
+use std::{collections::HashSet, fs, path::Path};
+
+use serde::Deserialize;
+
+use super::{AiBotProfile, bots::bot_models};
+use crate::ui::ui_state::MAX_NAME_LEN;
+
+#[derive(Deserialize)]
+struct PersonasFile {
+    #[serde(default)]
+    persona: Vec<AiBotProfile>,
+}
+
+/// Lê as personas de `text`; lista vazia, nome ou profissão em branco, nome
+/// acima de `MAX_NAME_LEN` caracteres (o servidor recusaria o `set_name`) e
+/// nomes repetidos (sem diferenciar caixa) tornam o arquivo inválido.
+///
+/// Personas sem `model` recebem os modelos configurados em rodízio.
+pub fn parse_personas(
+    text: &str,
+    models: &[String],
+    fallback_model: &str,
+) -> Result<Vec<AiBotProfile>, String> {
+    let file: PersonasFile = toml::from_str(text).map_err(|e| e.to_string())?;
+    let mut personas = file.persona;
+    if personas.is_empty() {
+        return Err("nenhuma [[persona]] definida".to_string());
+    }
+
+    let mut seen = HashSet::new();
+    for persona in &mut personas {
+        persona.name = persona.name.trim().to_string();
+        persona.profession = persona.profession.trim().to_string();
+        persona.model = persona.model.trim().to_string();
+        if persona.name.is_empty() || persona.profession.is_empty() {
+            return Err("persona sem name ou profession".to_string());
+        }
+        if persona.name.chars().count() > MAX_NAME_LEN {
+            return Err(format!(
+                "nome com mais de {MAX_NAME_LEN} caracteres: {}",
+                persona.name
+            ));
+        }
+        if !seen.insert(persona.name.to_lowercase()) {
+            return Err(format!("nome repetido: {}", persona.name));
+        }
+    }
+
+    let assigned = bot_models(personas.len(), models, fallback_model);
+    for (persona, model) in personas.iter_mut().zip(assigned) {
+        if persona.model.is_empty() {
+            persona.model = model;
+        }
+    }
+    Ok(personas)
+}
+
+/// Lê e valida o arquivo de personas com os modelos de `config::current()`.
+pub fn load_personas(path: &Path) -> Result<Vec<AiBotProfile>, String> {
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let ai_config = &crate::config::current().ai;
+    parse_personas(&text, &ai_config.models, &ai_config.model)
+}
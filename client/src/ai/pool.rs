@@ -0,0 +1,146 @@
+//! Pool fixo de threads que atende os pedidos ao Ollama.
+//!
+//! Cada worker é uma thread de vida longa com o próprio runtime tokio
+//! (`current_thread`), criado uma vez e reaproveitado em todos os pedidos;
+//! os pedidos entram numa fila única e são atendidos na ordem de chegada.
+//! Com chat movimentado e vários bots, a fila cresce mas o número de threads
+//! fica limitado a `workers`.
+
+use std::{
+    panic::{AssertUnwindSafe, catch_unwind},
+    sync::{
+        Arc, Mutex, OnceLock,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use tokio::runtime::{Builder, Runtime};
+
+use super::config::AI_WORKER_THREADS;
+
+/// Trabalho enfileirado; recebe o runtime do worker que o executa.
+pub type Job = Box<dyn FnOnce(&Runtime) + Send + 'static>;
+
+/// Espera máxima no encerramento por pedidos que já estavam em andamento.
+const SHUTDOWN_GRACE: Duration = Duration::from_millis(500);
+
+pub struct WorkerPool {
+    /// `None` depois do `shutdown`: a fila não aceita mais pedidos.
+    sender: Mutex<Option<Sender<Job>>>,
+    workers: Mutex<Vec<JoinHandle<()>>>,
+    /// Avisa os workers para descartarem o que ainda está na fila.
+    closed: Arc<AtomicBool>,
+}
+
+impl WorkerPool {
+    /// Sobe `workers` threads (mínimo 1), cada uma com seu runtime.
+    ///
+    /// As threads terminam no `shutdown` ou quando o pool é descartado e a fila esvazia.
+    pub fn new(workers: usize) -> Result<Self, String> {
+        let workers = workers.max(1);
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let closed = Arc::new(AtomicBool::new(false));
+        let mut handles = Vec::with_capacity(workers);
+        for index in 0..workers {
+            let runtime = Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(|e| format!("falha ao iniciar runtime async: {e}"))?;
+            let (receiver, closed) = (Arc::clone(&receiver), Arc::clone(&closed));
+            let handle = thread::Builder::new()
+                .name(format!("ai-worker-{index}"))
+                .spawn(move || run_worker(&runtime, &receiver, &closed))
+                .map_err(|e| format!("falha ao iniciar worker da IA: {e}"))?;
+            handles.push(handle);
+        }
+        Ok(Self {
+            sender: Mutex::new(Some(sender)),
+            workers: Mutex::new(handles),
+            closed,
+        })
+    }
+
+    /// Enfileira `job`; erro depois do `shutdown` ou se todos os workers já terminaram.
+    pub fn submit(&self, job: impl FnOnce(&Runtime) + Send + 'static) -> Result<(), String> {
+        let closed = || "workers da IA encerrados".to_string();
+        let sender = self.sender.lock().map_err(|_| closed())?;
+        sender
+            .as_ref()
+            .ok_or_else(closed)?
+            .send(Box::new(job))
+            .map_err(|_| closed())
+    }
+
+    /// Fecha a fila, descarta os pedidos ainda não iniciados e espera até `grace`
+    /// pelos que estão em andamento. Retorna `true` se todos os workers terminaram.
+    ///
+    /// Pedidos presos além do prazo ficam para trás; o processo encerra sem eles.
+    pub fn shutdown(&self, grace: Duration) -> bool {
+        self.closed.store(true, Ordering::SeqCst);
+        if let Ok(mut sender) = self.sender.lock() {
+            sender.take();
+        }
+        let Ok(mut workers) = self.workers.lock() else {
+            return false;
+        };
+        let deadline = Instant::now() + grace;
+        while workers.iter().any(|w| !w.is_finished()) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        let (finished, running): (Vec<_>, Vec<_>) =
+            workers.drain(..).partition(|w| w.is_finished());
+        for worker in finished {
+            let _ = worker.join();
+        }
+        let all_finished = running.is_empty();
+        *workers = running;
+        all_finished
+    }
+}
+
+/// Laço de um worker: pega o próximo trabalho da fila até o canal fechar.
+fn run_worker(runtime: &Runtime, receiver: &Mutex<Receiver<Job>>, closed: &AtomicBool) {
+    loop {
+        // O lock é solto antes de executar, para os outros workers seguirem pegando trabalho.
+        let job = match receiver.lock() {
+            Ok(receiver) => receiver.recv(),
+            Err(_) => return,
+        };
+        if closed.load(Ordering::SeqCst) {
+            return;
+        }
+        match job {
+            // Um pedido que entra em pânico não derruba o worker.
+            Ok(job) => {
+                let _ = catch_unwind(AssertUnwindSafe(|| job(runtime)));
+            }
+            Err(_) => return,
+        }
+    }
+}
+
+static AI_POOL: OnceLock<Result<WorkerPool, String>> = OnceLock::new();
+
+/// Pool global dos pedidos dos bots, criado no startup (ou no primeiro uso).
+pub fn ai_pool() -> Result<&'static WorkerPool, String> {
+    AI_POOL
+        .get_or_init(|| WorkerPool::new(AI_WORKER_THREADS))
+        .as_ref()
+        .map_err(Clone::clone)
+}
+
+/// Sobe os workers da IA antes da TUI, para o primeiro pedido não pagar a criação.
+pub fn start_ai_workers() -> Result<(), String> {
+    ai_pool().map(|_| ())
+}
+
+/// Encerra os workers da IA na saída do app (no-op se nunca subiram).
+pub fn shutdown_ai_workers() {
+    if let Some(Ok(pool)) = AI_POOL.get() {
+        pool.shutdown(SHUTDOWN_GRACE);
+    }
+}
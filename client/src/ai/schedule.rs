@@ -0,0 +1,36 @@
+/// Janela diária em que os bots não puxam conversa sozinhos (`ai.quiet_hours`).
+///
+/// Horários em minutos desde a meia-noite local; `start > end` atravessa a meia-noite.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QuietHours {
+    start: u32,
+    end: u32,
+}
+
+impl QuietHours {
+    /// Lê `"HH:MM-HH:MM"` (ex.: `"23:00-07:00"`); vazio ou inválido desativa.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let (start, end) = raw.trim().split_once('-')?;
+        let quiet = Self {
+            start: parse_clock(start)?,
+            end: parse_clock(end)?,
+        };
+        (quiet.start != quiet.end).then_some(quiet)
+    }
+
+    /// Se `minute_of_day` cai dentro da janela (início incluso, fim excluso).
+    pub fn contains(self, minute_of_day: u32) -> bool {
+        if self.start < self.end {
+            (self.start..self.end).contains(&minute_of_day)
+        } else {
+            minute_of_day >= self.start || minute_of_day < self.end
+        }
+    }
+}
+
+fn parse_clock(raw: &str) -> Option<u32> {
+    let (hours, minutes) = raw.trim().split_once(':')?;
+    let hours: u32 = hours.parse().ok()?;
+    let minutes: u32 = minutes.parse().ok()?;
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
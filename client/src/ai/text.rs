@@ -1,4 +1,53 @@
-use super::MAX_REPLY_CHARS;
+use std::{collections::HashSet, time::Duration};
+
+use super::{
+    MAX_REPLY_CHARS,
+    config::{AI_ECHO_MIN_WORDS, AI_ECHO_THRESHOLD, AI_MAX_TYPING_DELAY, AI_TYPING_DELAY_PER_CHAR},
+};
+
+/// Aproximação usada nos orçamentos de prompt: ~4 caracteres por token.
+pub(super) const CHARS_PER_TOKEN: usize = 4;
+
+/// Estimativa barata de tokens de um texto (arredonda para cima).
+pub(super) fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// Quanto um bot "digita" antes de publicar `text`: proporcional ao tamanho, com teto.
+pub fn typing_delay_for(text: &str) -> Duration {
+    let chars = u32::try_from(text.trim().chars().count()).unwrap_or(u32::MAX);
+    AI_TYPING_DELAY_PER_CHAR
+        .saturating_mul(chars)
+        .min(AI_MAX_TYPING_DELAY)
+}
+
+/// Semelhança de Jaccard entre as palavras (minúsculas, sem pontuação) de `a` e `b`.
+pub fn word_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (word_set(a), word_set(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+/// A resposta só repete, quase igual, alguma das mensagens `recent`.
+///
+/// Respostas com menos de `AI_ECHO_MIN_WORDS` palavras ficam de fora: um "sim"
+/// repetido é conversa normal, não eco.
+pub fn is_echo<S: AsRef<str>>(reply: &str, recent: &[S]) -> bool {
+    word_set(reply).len() >= AI_ECHO_MIN_WORDS
+        && recent
+            .iter()
+            .any(|text| word_similarity(reply, text.as_ref()) >= AI_ECHO_THRESHOLD)
+}
+
+fn word_set(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
 
 pub(super) fn short_identity(identity: &str) -> String {
     const MAX: usize = 18;
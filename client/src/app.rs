@@ -15,31 +15,56 @@ use std::{
     time::{Duration, Instant},
 };
 
+use crate::activity::unix_now_secs;
 use crate::ai::{
-    AI_BOT_COUNT, AI_PROACTIVE_COOLDOWN_SECS, AI_PROACTIVE_IDLE_SECS, AI_PROACTIVE_START_CHANCE,
-    AI_TO_AI_REPLY_CHANCE_IDLE, AI_TO_AI_REPLY_CHANCE_WITH_HUMANS, AiBotProfile, AiGeneratedReply,
-    MAX_AI_CHAIN_MESSAGES, generate_bot_profiles, request_bot_reply,
+    AI_ECHO_WINDOW, AiBotProfile, AiGeneratedReply, AiTuning, BotReplyMode, FANTASY_NAMES,
+    ForcedPrompts, MAX_AI_CHAIN_MESSAGES, QuietHours, RoundRobin, bot_command_reply,
+    check_ollama_health, choose_dispatch_target, clamp_bot_count, directed_reply_limit,
+    dispatch_order, forced_responder, forget_reply, generate_bot_profiles,
+    generate_bot_profiles_excluding, is_echo, is_open_question, load_memory, load_personas,
+    mentioned_names, profession_status, profession_weight, request_bot_preview, request_bot_reply,
+    save_memory, shutdown_ai_workers, start_ai_workers, typing_delay_for,
 };
+use crate::config::{self, Config, ConnectionConfig};
+use crate::connection::{
+    ConnectError, RECONNECT_BASE_DELAY, ReconnectAction, ReconnectBackoff, connect_with_timeout,
+    reconnect_action, spawn_connect_attempt,
+};
+use crate::event_log::EventLog;
 use crate::module_bindings::{
     DbConnection, send_message as SendMessageReducerExt, set_name as SetNameReducerExt,
+    set_status as SetStatusReducerExt,
 };
 use crate::state::{AppState, SharedState, snapshot_state, update_state};
-use crate::sync::{SYSTEM_MESSAGE_ID_BASE, register_table_callbacks, sync_from_tables};
-use crate::ui::key_handler::handle_key_event;
+use crate::sync::{
+    SYSTEM_MESSAGE_ID_BASE, SeenMessages, add_local_system_message, clear_server_state,
+    expire_offline_users, register_reducer_callbacks, register_table_callbacks, sync_from_tables,
+};
+use crate::ui::export::export_chat_json;
+use crate::ui::filter::load_filter_words;
+use crate::ui::key_handler::{handle_key_event, handle_mouse_event};
+use crate::ui::theme::Theme;
 use crate::ui::ui_menu_screen::render_menu_screen;
 use crate::ui::ui_message_screen::render_ui;
-use crate::ui::ui_state::UiScreen;
+use crate::ui::ui_state::{ScrollPosition, UiScreen};
+use chrono::{Local, Timelike};
 use crossterm::{
-    event::{self, Event as CEvent},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event as CEvent, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
     execute,
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+    terminal::{
+        EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+        supports_keyboard_enhancement,
+    },
 };
 use rand::{
     RngExt,
     prelude::{IndexedRandom, SliceRandom},
     rng,
 };
-use ratatui::{Terminal, backend::CrosstermBackend};
+use ratatui::{Terminal, backend::CrosstermBackend, style::Color};
 use spacetimedb_sdk::DbContext;
 
 struct AiBotRuntime {
@@ -47,33 +72,376 @@ struct AiBotRuntime {
     conn: DbConnection,
     online: Arc<AtomicBool>,
     identity: Arc<Mutex<Option<String>>>,
+    /// Token da conexão, para reconectar com a mesma identity.
+    token: Arc<Mutex<Option<String>>>,
     worker: JoinHandle<()>,
+    /// Reconexão em andamento desde que a conexão deste bot caiu.
+    reconnect: Option<Reconnect<AiBotRuntime>>,
 }
 
-pub fn run() -> Result<(), Box<dyn std::error::Error>> {
-    let module_name = env::args()
-        .nth(1)
-        .or_else(|| env::var("STDB_MODULE").ok())
-        .unwrap_or_else(|| "shell-relay-test".to_string());
+/// Resposta gerada aguardando a "digitação" simulada do bot.
+struct PendingAiReply {
+    text: String,
+    /// Pedida com `/ai`/`/ask` (ver `AiGeneratedReply::forced`).
+    forced: bool,
+    /// Instante a partir do qual a resposta pode ser publicada.
+    ready_at: Instant,
+}
 
-    let uri = env::args()
-        .nth(2)
-        .or_else(|| env::var("STDB_URI").ok())
-        .unwrap_or_else(|| "http://127.0.0.1:3000".to_string());
+/// Reconexão de uma conexão caída (do usuário ou de um bot), independente das outras.
+struct Reconnect<T> {
+    backoff: ReconnectBackoff,
+    /// Tentativa rodando em segundo plano (a conexão pode levar até o timeout).
+    ///
+    /// Descartar o receptor cancela a tentativa: o que ela abrir é desconectado.
+    attempt: Option<Receiver<Result<T, ConnectError>>>,
+}
 
-    let state = Arc::new(Mutex::new(AppState {
+/// Conexão do usuário aberta por uma reconexão, ainda sem callbacks nem thread:
+/// nada dela chega ao estado até `start_user_connection`.
+struct OpenedUserConnection {
+    conn: DbConnection,
+    token: Arc<Mutex<Option<String>>>,
+}
+
+/// Conexão do usuário humano com seus callbacks já registrados.
+struct UserConnection {
+    conn: DbConnection,
+    worker: JoinHandle<()>,
+    /// Token da conexão, para reconectar com a mesma identity.
+    token: Arc<Mutex<Option<String>>>,
+}
+
+pub fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let cli_args: Vec<String> = env::args().skip(1).collect();
+    config::init(Config::load(&cli_args)?);
+    let app_config = config::current();
+
+    let mut state = AppState {
         status: false,
+        bot_reply_mode: BotReplyMode::from_setting(&app_config.ai.bots_mode),
         ..Default::default()
-    }));
+    };
+    let own_color_error = match own_message_color(app_config.ui.own_message_color.as_deref()) {
+        Ok(color) => {
+            state.ui.own_message_color = color;
+            None
+        }
+        Err(raw) => {
+            state.ui.own_message_color = own_message_color(None).unwrap_or_default();
+            Some(format!(
+                "Unknown own message color {raw:?}; using a random accent instead"
+            ))
+        }
+    };
+    state.ui.idle_animation = app_config.ui.idle_animation;
+    state.ui.accessibility = app_config.ui.accessibility;
+    state.ui.theme = if app_config.ui.accessibility {
+        Theme::high_contrast()
+    } else {
+        Theme::by_name(&app_config.ui.theme)
+    };
+    state.ui.offline_grace = Duration::from_secs(app_config.ui.offline_grace_secs);
+    state.ui.raw_timestamps = app_config.debug.raw_timestamps;
+    state.ui.max_message_width = app_config.ui.max_message_width;
+    let filter_error = match app_config.ui.filter_file.as_deref().map(load_filter_words) {
+        Some(Ok(words)) => {
+            state.ui.filter_words = words;
+            None
+        }
+        Some(Err(err)) => Some(format!(
+            "Could not load word filter ({err}); showing messages unfiltered"
+        )),
+        None => None,
+    };
+    let state = Arc::new(Mutex::new(state));
+    for err in [own_color_error, filter_error].into_iter().flatten() {
+        add_local_system_message(&state, "System", err);
+    }
+
+    let event_log = match &app_config.debug.event_log {
+        Some(path) => Some(Arc::new(EventLog::open(path)?)),
+        None => None,
+    };
+    // Instancia múltiplos bots com nomes/profissões sorteados no startup.
+    let ai_enabled = app_config.ai.enabled;
+    let requested_bots = app_config.ai.requested_bot_count();
+    let bot_count = clamp_bot_count(requested_bots);
+    if ai_enabled && bot_count as i64 != requested_bots {
+        add_local_system_message(
+            &state,
+            "System",
+            format!(
+                "Bot count {requested_bots} is out of range (0-{}); using {bot_count}",
+                FANTASY_NAMES.len()
+            ),
+        );
+    }
+    // Arquivo de personas válido define os bots; com problema, volta ao sorteio.
+    // Com `--no-ai` nenhum bot conecta e os workers do Ollama nem sobem.
+    let personas = app_config.ai.personas.as_deref().filter(|_| ai_enabled);
+    let bot_profiles = match personas.map(load_personas) {
+        Some(Ok(mut personas)) => {
+            // Só avisa quando a quantidade foi pedida (arquivo, env ou `--bots`).
+            let ignored = app_config
+                .ai
+                .bot_count
+                .filter(|&requested| requested != personas.len() as i64);
+            if let Some(requested) = ignored {
+                add_local_system_message(
+                    &state,
+                    "System",
+                    format!(
+                        "--personas defines {} bot(s); ignoring bot count {requested}",
+                        personas.len()
+                    ),
+                );
+            }
+            let limit = clamp_bot_count(personas.len() as i64);
+            if personas.len() > limit {
+                add_local_system_message(
+                    &state,
+                    "System",
+                    format!(
+                        "Personas file has {} bots; using the first {limit}",
+                        personas.len()
+                    ),
+                );
+                personas.truncate(limit);
+            }
+            personas
+        }
+        Some(Err(err)) => {
+            add_local_system_message(
+                &state,
+                "System",
+                format!("Could not load personas ({err}); using random bots"),
+            );
+            generate_bot_profiles(bot_count)
+        }
+        None if !ai_enabled => Vec::new(),
+        None => generate_bot_profiles(bot_count),
+    };
+    // Memória dos bots da execução anterior (`--persist-memory`).
+    let memory_file = app_config.ai.memory_file.as_deref().filter(|_| ai_enabled);
+    if let Some(path) = memory_file {
+        match load_memory(path) {
+            Ok(memory) => update_state(&state, |s| s.ai_histories = memory),
+            Err(err) => add_local_system_message(
+                &state,
+                "System",
+                format!("Could not load bot memory ({err}); starting fresh"),
+            ),
+        }
+    }
+    if ai_enabled && let Err(err) = start_ai_workers() {
+        add_local_system_message(&state, "System", format!("AI workers unavailable: {err}"));
+    }
+    let mut session = connect_session(
+        app_config.connection.clone(),
+        bot_profiles,
+        &state,
+        event_log.clone(),
+    )?;
+    // Sem Ollama os bots ficam mudos; avisa cedo, sem segurar a abertura da TUI.
+    if ai_enabled && bot_count > 0 {
+        check_ollama_health(&state);
+    }
+
+    // Canal interno: threads de IA produzem texto e o loop principal envia via bots.
+    let (ai_reply_tx, ai_reply_rx) = mpsc::channel::<AiGeneratedReply>();
+
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    // Terminais com o protocolo de teclado estendido distinguem Shift+Enter de Enter.
+    let keyboard_enhanced = supports_keyboard_enhancement().unwrap_or(false);
+    if keyboard_enhanced {
+        execute!(
+            stdout,
+            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+        )?;
+    }
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let app_res = run_app(
+        &mut terminal,
+        &mut session,
+        &state,
+        &event_log,
+        &ai_reply_tx,
+        &ai_reply_rx,
+    );
+
+    if keyboard_enhanced {
+        execute!(terminal.backend_mut(), PopKeyboardEnhancementFlags)?;
+    }
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        DisableMouseCapture,
+        LeaveAlternateScreen
+    )?;
+    terminal.show_cursor()?;
+
+    session.shutdown();
+    shutdown_ai_workers();
+    if let Some(path) = memory_file {
+        let memory = state
+            .lock()
+            .map(|s| s.ai_histories.clone())
+            .unwrap_or_default();
+        if let Err(err) = save_memory(path, &memory) {
+            eprintln!("Could not save bot memory to {}: {err}", path.display());
+        }
+    }
+    if let Some(path) = &app_config.ui.export_json {
+        let (messages, users) = state
+            .lock()
+            .map(|s| (s.ui.messages.clone(), s.ui.users.clone()))
+            .unwrap_or_default();
+        if let Err(err) = export_chat_json(&messages, &users, path) {
+            eprintln!("Could not export chat to {}: {err}", path.display());
+        }
+    }
+
+    app_res
+}
+
+/// Conexões com um servidor: a do usuário e uma por bot.
+///
+/// O `/connect` troca a sessão inteira sem reiniciar o app.
+struct Session {
+    target: ConnectionConfig,
+    conn: DbConnection,
+    worker: JoinHandle<()>,
+    token: Arc<Mutex<Option<String>>>,
+    /// Reconexão do usuário em andamento desde que a conexão caiu.
+    reconnect: Option<Reconnect<OpenedUserConnection>>,
+    ai_bots: Vec<AiBotRuntime>,
+}
+
+impl Session {
+    /// Desconecta tudo e cancela as reconexões em andamento (do usuário e dos bots).
+    fn shutdown(self) {
+        drop(self.reconnect);
+        let _ = self.conn.disconnect();
+        let _ = self.worker.join();
+        for bot in &self.ai_bots {
+            let _ = bot.conn.disconnect();
+        }
+        for bot in self.ai_bots {
+            drop(bot.reconnect);
+            let _ = bot.worker.join();
+        }
+    }
+}
 
+/// Sessão aberta por um `/connect`, com o usuário ainda sem callbacks nem thread:
+/// a sessão atual segue intacta até `start_session`.
+struct OpenedSession {
+    target: ConnectionConfig,
+    user: OpenedUserConnection,
+    ai_bots: Vec<AiBotRuntime>,
+}
+
+impl OpenedSession {
+    /// Fecha uma sessão que não vai mais ser usada (`/connect` cancelado ou trocado).
+    fn discard(self) {
+        let _ = self.user.conn.disconnect();
+        for bot in self.ai_bots {
+            let _ = bot.conn.disconnect();
+            let _ = bot.worker.join();
+        }
+    }
+}
+
+/// Conecta o usuário e os bots em `target`; se um bot falhar, desfaz as conexões já abertas.
+fn connect_session(
+    target: ConnectionConfig,
+    bot_profiles: Vec<AiBotProfile>,
+    state: &SharedState,
+    event_log: Option<Arc<EventLog>>,
+) -> Result<Session, ConnectError> {
+    let opened = open_session(target, bot_profiles, state)?;
+    Ok(start_session(opened, state, event_log))
+}
+
+/// Parte bloqueante de `connect_session`; pode rodar fora da thread da UI.
+fn open_session(
+    target: ConnectionConfig,
+    bot_profiles: Vec<AiBotProfile>,
+    state: &SharedState,
+) -> Result<OpenedSession, ConnectError> {
     // Conexão principal do usuário humano.
-    let on_connect_state = Arc::clone(&state);
-    let on_disconnect_state = Arc::clone(&state);
+    let user = open_user_connection(&target, state, None)?;
+
+    let mut ai_bots: Vec<AiBotRuntime> = Vec::with_capacity(bot_profiles.len());
+    for profile in bot_profiles {
+        match connect_bot(profile, &target, None) {
+            Ok(bot) => ai_bots.push(bot),
+            Err(err) => {
+                OpenedSession {
+                    target,
+                    user,
+                    ai_bots,
+                }
+                .discard();
+                return Err(err);
+            }
+        }
+    }
+
+    Ok(OpenedSession {
+        target,
+        user,
+        ai_bots,
+    })
+}
+
+/// Registra os callbacks do usuário e sobe a thread da conexão.
+fn start_session(
+    opened: OpenedSession,
+    state: &SharedState,
+    event_log: Option<Arc<EventLog>>,
+) -> Session {
+    let OpenedSession {
+        target,
+        user,
+        ai_bots,
+    } = opened;
+    let user = start_user_connection(user, state, event_log);
+    Session {
+        target,
+        conn: user.conn,
+        worker: user.worker,
+        token: user.token,
+        reconnect: None,
+        ai_bots,
+    }
+}
 
-    let conn = DbConnection::builder()
-        .with_uri(uri.clone())
-        .with_module_name(module_name.clone())
-        .on_connect(move |ctx, identity, _token| {
+/// Abre a conexão do usuário; os callbacks do builder só rodam depois do
+/// `run_threaded` em `start_user_connection`.
+fn open_user_connection(
+    target: &ConnectionConfig,
+    state: &SharedState,
+    token: Option<String>,
+) -> Result<OpenedUserConnection, ConnectError> {
+    let on_connect_state = Arc::clone(state);
+    let on_disconnect_state = Arc::clone(state);
+    let token_slot = Arc::new(Mutex::new(token.clone()));
+    let on_connect_token = Arc::clone(&token_slot);
+
+    let builder = DbConnection::builder()
+        .with_uri(target.uri.clone())
+        .with_module_name(target.module.clone())
+        .with_token(token)
+        .on_connect(move |ctx, identity, token| {
+            if let Ok(mut slot) = on_connect_token.lock() {
+                *slot = Some(token.to_string());
+            }
             update_state(&on_connect_state, |s| {
                 s.my_identity = Some(identity.to_string());
                 s.status = true;
@@ -99,113 +467,472 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
             update_state(&on_disconnect_state, |s| {
                 s.status = false;
             });
+        });
+    let connect_timeout = Duration::from_secs(target.connect_timeout_secs);
+    let conn = connect_with_timeout(&target.uri, connect_timeout, move || builder.build())?;
+    Ok(OpenedUserConnection {
+        conn,
+        token: token_slot,
+    })
+}
+
+/// Registra os callbacks das tabelas e reducers e sobe a thread da conexão.
+fn start_user_connection(
+    opened: OpenedUserConnection,
+    state: &SharedState,
+    event_log: Option<Arc<EventLog>>,
+) -> UserConnection {
+    let OpenedUserConnection { conn, token } = opened;
+    register_table_callbacks(&conn, state, event_log);
+    register_reducer_callbacks(&conn, state);
+    let worker = conn.run_threaded();
+    UserConnection {
+        conn,
+        worker,
+        token,
+    }
+}
+
+/// Reconecta o usuário quando a conexão cai, com a mesma agenda de espera
+/// exponencial dos bots; a tentativa roda em segundo plano.
+///
+/// Ao reconectar, os callbacks são registrados de novo e a inscrição refaz o sync.
+fn supervise_user(session: &mut Session, state: &SharedState, event_log: &Option<Arc<EventLog>>) {
+    let now = Instant::now();
+    let finished = session
+        .reconnect
+        .as_ref()
+        .and_then(|r| r.attempt.as_ref())
+        .and_then(|attempt| attempt.try_recv().ok());
+    match finished {
+        Some(Ok(opened)) => {
+            let attempts = session.reconnect.take().map_or(0, |r| r.backoff.failures()) + 1;
+            let fresh = start_user_connection(opened, state, event_log.clone());
+            session.conn = fresh.conn;
+            let _ = std::mem::replace(&mut session.worker, fresh.worker).join();
+            session.token = fresh.token;
+            add_local_system_message(
+                state,
+                "System",
+                format!("Reconnected to the server after {attempts} attempt(s)"),
+            );
+            return;
+        }
+        Some(Err(err)) => {
+            if let Some(reconnect) = session.reconnect.as_mut() {
+                reconnect.attempt = None;
+                reconnect.backoff.record_failure(now);
+                add_local_system_message(
+                    state,
+                    "System",
+                    format!(
+                        "Could not reconnect ({err}); retrying in {}s",
+                        reconnect.backoff.remaining(now).as_secs()
+                    ),
+                );
+            }
+        }
+        None => {}
+    }
+
+    let action = reconnect_action(
+        session.worker.is_finished(),
+        session.reconnect.as_ref().map(|r| &r.backoff),
+        session
+            .reconnect
+            .as_ref()
+            .is_some_and(|r| r.attempt.is_some()),
+        now,
+    );
+    match action {
+        ReconnectAction::Wait => {}
+        ReconnectAction::Schedule => {
+            session.reconnect = Some(Reconnect {
+                backoff: ReconnectBackoff::new(now),
+                attempt: None,
+            });
+            add_local_system_message(
+                state,
+                "System",
+                format!(
+                    "Lost connection to {}; reconnecting in {}s",
+                    session.target.uri,
+                    RECONNECT_BASE_DELAY.as_secs()
+                ),
+            );
+        }
+        ReconnectAction::Attempt => {
+            let target = session.target.clone();
+            let token = session.token.lock().ok().and_then(|t| t.clone());
+            let state = Arc::clone(state);
+            let attempt = spawn_connect_attempt(
+                move || open_user_connection(&target, &state, token),
+                |stale: OpenedUserConnection| {
+                    let _ = stale.conn.disconnect();
+                },
+            );
+            if let Some(reconnect) = session.reconnect.as_mut() {
+                reconnect.attempt = Some(attempt);
+            }
+        }
+    }
+}
+
+/// Conecta um bot em `target`; ao conectar, ele se apresenta com `set_name`/`set_status`.
+///
+/// Com `token`, reconecta com a identity de antes.
+fn connect_bot(
+    profile: AiBotProfile,
+    target: &ConnectionConfig,
+    token: Option<String>,
+) -> Result<AiBotRuntime, ConnectError> {
+    let online = Arc::new(AtomicBool::new(false));
+    let identity = Arc::new(Mutex::new(None::<String>));
+    let token_slot = Arc::new(Mutex::new(token.clone()));
+
+    let on_connect_online = Arc::clone(&online);
+    let on_disconnect_online = Arc::clone(&online);
+    let on_connect_identity = Arc::clone(&identity);
+    let on_disconnect_identity = Arc::clone(&identity);
+    let on_connect_token = Arc::clone(&token_slot);
+    let bot_name = profile.name.clone();
+    let bot_status = profession_status(&profile.profession);
+
+    let builder = DbConnection::builder()
+        .with_uri(target.uri.clone())
+        .with_module_name(target.module.clone())
+        .with_token(token)
+        .on_connect(move |ctx, identity, token| {
+            if let Ok(mut slot) = on_connect_identity.lock() {
+                *slot = Some(identity.to_string());
+            }
+            if let Ok(mut slot) = on_connect_token.lock() {
+                *slot = Some(token.to_string());
+            }
+            let _ = ctx.reducers.set_name(bot_name.clone());
+            let _ = ctx.reducers.set_status(bot_status.to_string());
+            on_connect_online.store(true, Ordering::SeqCst);
         })
-        .build()?;
+        .on_disconnect(move |_ctx, _err| {
+            if let Ok(mut slot) = on_disconnect_identity.lock() {
+                *slot = None;
+            }
+            on_disconnect_online.store(false, Ordering::SeqCst);
+        });
+    let connect_timeout = Duration::from_secs(target.connect_timeout_secs);
+    let conn = connect_with_timeout(&target.uri, connect_timeout, move || builder.build())?;
 
-    // Instancia múltiplos bots com nomes/profissões sorteados no startup.
-    let bot_profiles = generate_bot_profiles(AI_BOT_COUNT);
-    let mut ai_bots = Vec::with_capacity(bot_profiles.len());
-    for profile in bot_profiles {
-        let online = Arc::new(AtomicBool::new(false));
-        let identity = Arc::new(Mutex::new(None::<String>));
-
-        let on_connect_online = Arc::clone(&online);
-        let on_disconnect_online = Arc::clone(&online);
-        let on_connect_identity = Arc::clone(&identity);
-        let on_disconnect_identity = Arc::clone(&identity);
-        let bot_name = profile.name.clone();
-
-        let conn_bot = DbConnection::builder()
-            .with_uri(uri.clone())
-            .with_module_name(module_name.clone())
-            .on_connect(move |ctx, identity, _token| {
-                if let Ok(mut slot) = on_connect_identity.lock() {
-                    *slot = Some(identity.to_string());
+    let worker = conn.run_threaded();
+    Ok(AiBotRuntime {
+        profile,
+        conn,
+        online,
+        identity,
+        token: token_slot,
+        worker,
+        reconnect: None,
+    })
+}
+
+/// Reconecta, um a um, os bots cuja conexão caiu (a thread da conexão terminou).
+///
+/// Cada bot tem a própria agenda com espera exponencial; as tentativas rodam em
+/// segundo plano e o resultado é aplicado num tick seguinte.
+fn supervise_bots(bots: &mut [AiBotRuntime], target: &ConnectionConfig, state: &SharedState) {
+    let now = Instant::now();
+    for bot in bots.iter_mut() {
+        let finished = bot
+            .reconnect
+            .as_ref()
+            .and_then(|r| r.attempt.as_ref())
+            .and_then(|attempt| attempt.try_recv().ok());
+        match finished {
+            Some(Ok(fresh)) => {
+                let attempts = bot.reconnect.as_ref().map_or(0, |r| r.backoff.failures()) + 1;
+                *bot = fresh;
+                add_local_system_message(
+                    state,
+                    "System",
+                    format!(
+                        "Bot {} reconnected after {attempts} attempt(s)",
+                        bot.profile.name
+                    ),
+                );
+                continue;
+            }
+            Some(Err(err)) => {
+                if let Some(reconnect) = bot.reconnect.as_mut() {
+                    reconnect.attempt = None;
+                    reconnect.backoff.record_failure(now);
+                    add_local_system_message(
+                        state,
+                        "System",
+                        format!(
+                            "Bot {} could not reconnect ({err}); retrying in {}s",
+                            bot.profile.name,
+                            reconnect.backoff.remaining(now).as_secs()
+                        ),
+                    );
                 }
-                let _ = ctx.reducers.set_name(bot_name.clone());
-                on_connect_online.store(true, Ordering::SeqCst);
-            })
-            .on_disconnect(move |_ctx, _err| {
-                if let Ok(mut slot) = on_disconnect_identity.lock() {
-                    *slot = None;
+            }
+            None => {}
+        }
+
+        let action = reconnect_action(
+            bot.worker.is_finished(),
+            bot.reconnect.as_ref().map(|r| &r.backoff),
+            bot.reconnect.as_ref().is_some_and(|r| r.attempt.is_some()),
+            now,
+        );
+        match action {
+            ReconnectAction::Wait => {}
+            ReconnectAction::Schedule => {
+                bot.reconnect = Some(Reconnect {
+                    backoff: ReconnectBackoff::new(now),
+                    attempt: None,
+                });
+                add_local_system_message(
+                    state,
+                    "System",
+                    format!("Bot {} lost its connection; reconnecting", bot.profile.name),
+                );
+            }
+            ReconnectAction::Attempt => {
+                let profile = bot.profile.clone();
+                let target = target.clone();
+                let token = bot.token.lock().ok().and_then(|t| t.clone());
+                let attempt = spawn_connect_attempt(
+                    move || connect_bot(profile, &target, token),
+                    |stale: AiBotRuntime| {
+                        let _ = stale.conn.disconnect();
+                    },
+                );
+                if let Some(reconnect) = bot.reconnect.as_mut() {
+                    reconnect.attempt = Some(attempt);
                 }
-                on_disconnect_online.store(false, Ordering::SeqCst);
-            })
-            .build()?;
-
-        let worker = conn_bot.run_threaded();
-        ai_bots.push(AiBotRuntime {
-            profile,
-            conn: conn_bot,
-            online,
-            identity,
-            worker,
-        });
+            }
+        }
     }
+}
 
-    register_table_callbacks(&conn, &state);
-    let worker = conn.run_threaded();
-
-    // Canal interno: threads de IA produzem texto e o loop principal envia via bots.
-    let (ai_reply_tx, ai_reply_rx) = mpsc::channel::<AiGeneratedReply>();
+/// Abre em segundo plano uma sessão em `target`, com as personas atuais dos bots.
+///
+/// Descartar o receptor cancela o `/connect`: o que ele abrir é desconectado.
+fn spawn_session_connect(
+    session: &Session,
+    target: ConnectionConfig,
+    state: &SharedState,
+) -> Receiver<Result<OpenedSession, ConnectError>> {
+    let profiles: Vec<AiBotProfile> = session
+        .ai_bots
+        .iter()
+        .map(|bot| bot.profile.clone())
+        .collect();
+    let state = Arc::clone(state);
+    spawn_connect_attempt(
+        move || open_session(target, profiles, &state),
+        OpenedSession::discard,
+    )
+}
 
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+/// Troca a sessão atual pela que o `/connect` abriu.
+///
+/// A sessão antiga só cai depois que a nova conecta; em caso de falha ela segue ativa.
+fn switch_session(
+    session: &mut Session,
+    opened: OpenedSession,
+    state: &SharedState,
+    event_log: &Option<Arc<EventLog>>,
+) {
+    let next = start_session(opened, state, event_log.clone());
+    std::mem::replace(session, next).shutdown();
 
-    let app_res = run_app(
-        &mut terminal,
-        &conn,
-        &ai_bots,
-        &state,
-        &ai_reply_tx,
-        &ai_reply_rx,
-    );
+    // Callbacks da sessão antiga já pararam: recomeça a UI só com o servidor novo.
+    clear_server_state(state);
+    sync_from_tables(&session.conn.db, state);
+    update_state(state, |s| {
+        s.my_identity = session.conn.try_identity().map(|id| id.to_string());
+        s.status = session.conn.is_active();
+    });
+}
 
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
+/// Cor das próprias mensagens: `ui.own_message_color` / `SHELLRELAY_OWN_COLOR`
+/// (nome/hex, ou `off`) ou, sem configuração, uma cor de destaque sorteada por sessão.
+///
+/// Valor que não é cor vira `Err` com o texto configurado.
+fn own_message_color(configured: Option<&str>) -> Result<Option<Color>, String> {
+    const ACCENTS: &[Color] = &[
+        Color::Cyan,
+        Color::Magenta,
+        Color::LightBlue,
+        Color::LightGreen,
+        Color::LightMagenta,
+    ];
 
-    let _ = conn.disconnect();
-    let _ = worker.join();
-    for bot in &ai_bots {
-        let _ = bot.conn.disconnect();
-    }
-    for bot in ai_bots {
-        let _ = bot.worker.join();
+    match configured.map(str::trim) {
+        Some(raw) if raw.eq_ignore_ascii_case("off") => Ok(None),
+        Some(raw) => raw.parse::<Color>().map(Some).map_err(|_| raw.to_string()),
+        None => Ok(ACCENTS.choose(&mut rng()).copied()),
     }
-
-    app_res
 }
 
 fn run_app(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
-    conn: &DbConnection,
-    ai_bots: &[AiBotRuntime],
+    session: &mut Session,
     state: &SharedState,
+    event_log: &Option<Arc<EventLog>>,
     ai_reply_tx: &Sender<AiGeneratedReply>,
     ai_reply_rx: &Receiver<AiGeneratedReply>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let mut pending_ai_replies: HashMap<String, VecDeque<String>> = ai_bots
+    let mut pending_ai_replies: HashMap<String, VecDeque<PendingAiReply>> = session
+        .ai_bots
         .iter()
         .map(|b| (b.profile.name.clone(), VecDeque::new()))
         .collect();
 
-    let mut initialized_seen = false;
-    let mut last_seen_message_id: u64 = 0;
+    let mut seen_messages = SeenMessages::default();
     let mut consecutive_ai_messages: usize = 0;
     let mut last_chat_activity = Instant::now();
     let mut last_proactive_attempt = Instant::now();
+    let mut open_question_turn = RoundRobin::default();
+    let tuning = config::current().ai.tuning.validated();
+    let mut forced_ai_prompts = ForcedPrompts::default();
+    // `/connect` em andamento; um novo `/connect` substitui (e cancela) o anterior.
+    let mut pending_connect: Option<(
+        ConnectionConfig,
+        Receiver<Result<OpenedSession, ConnectError>>,
+    )> = None;
 
     loop {
-        // Drena o canal sem bloquear para manter o loop responsivo.
-        loop {
+        let finished = pending_connect.as_ref().and_then(|(target, attempt)| {
+            let result = attempt.try_recv().ok()?;
+            Some((target.uri.clone(), target.module.clone(), result))
+        });
+        if let Some((uri, module, result)) = finished {
+            pending_connect = None;
+            match result {
+                Ok(opened) => {
+                    switch_session(session, opened, state, event_log);
+                    // Respostas geradas para o servidor antigo não seguem para o novo.
+                    while ai_reply_rx.try_recv().is_ok() {}
+                    for queue in pending_ai_replies.values_mut() {
+                        queue.clear();
+                    }
+                    seen_messages.reset();
+                    forced_ai_prompts.clear();
+                    consecutive_ai_messages = 0;
+                    add_local_system_message(
+                        state,
+                        "System",
+                        format!("Connected to {uri} ({module})"),
+                    );
+                }
+                Err(err) => add_local_system_message(
+                    state,
+                    "System",
+                    format!(
+                        "{err}; still on {} ({})",
+                        session.target.uri, session.target.module
+                    ),
+                ),
+            }
+        }
+        let mut connect_requested = None;
+        update_state(state, |s| connect_requested = s.ui.connect_requested.take());
+        if let Some((uri, module)) = connect_requested {
+            match session.target.retarget(&uri, &module) {
+                Ok(target) => {
+                    add_local_system_message(
+                        state,
+                        "System",
+                        format!("Connecting to {uri} ({module})..."),
+                    );
+                    let attempt = spawn_session_connect(session, target.clone(), state);
+                    pending_connect = Some((target, attempt));
+                }
+                Err(err) => add_local_system_message(state, "System", err.to_string()),
+            }
+        }
+
+        let ai_bots = &mut session.ai_bots;
+        let mut reroll_requested = false;
+        update_state(state, |s| {
+            reroll_requested = std::mem::take(&mut s.ui.reroll_bots_requested);
+        });
+        if reroll_requested {
+            reroll_bot_personas(ai_bots, state, &mut pending_ai_replies);
+        }
+        let mut preview_requested = None;
+        update_state(state, |s| {
+            preview_requested = s.ui.bot_preview_requested.take()
+        });
+        if let Some((bot_name, prompt)) = preview_requested {
+            match ai_bots
+                .iter()
+                .find(|bot| bot.profile.name.eq_ignore_ascii_case(&bot_name))
+            {
+                Some(bot) => request_bot_preview(state, bot.profile.clone(), prompt),
+                None => add_local_system_message(
+                    state,
+                    "System",
+                    format!("No bot named {bot_name} to preview"),
+                ),
+            }
+        }
+        let mut ai_reply_requested = None;
+        update_state(state, |s| {
+            ai_reply_requested = s.ui.ai_reply_requested.take()
+        });
+        if let Some((requested, text)) = ai_reply_requested {
+            // Sem os sorteios do despacho normal: algum bot online sempre responde.
+            let online: Vec<&AiBotRuntime> = ai_bots
+                .iter()
+                .filter(|bot| bot.online.load(Ordering::SeqCst))
+                .collect();
+            let names: Vec<&str> = online.iter().map(|bot| bot.profile.name.as_str()).collect();
+            let random = rng().random_range(0..names.len().max(1));
+            match forced_responder(&names, requested.as_deref(), random) {
+                Some((index, fell_back)) => {
+                    let bot = &online[index].profile;
+                    if fell_back {
+                        add_local_system_message(
+                            state,
+                            "System",
+                            format!(
+                                "{} is not online; {} will answer",
+                                requested.unwrap_or_default(),
+                                bot.name
+                            ),
+                        );
+                    }
+                    forced_ai_prompts.push(&text, Instant::now());
+                    request_bot_reply(state, bot.clone(), text, true, ai_reply_tx.clone());
+                }
+                None => add_local_system_message(state, "System", "No bot online to answer"),
+            }
+        }
+        supervise_bots(ai_bots, &session.target, state);
+        supervise_user(session, state, event_log);
+        let ai_bots: &[AiBotRuntime] = &session.ai_bots;
+        let conn = &session.conn;
+
+        // Drena o canal sem bloquear para manter o loop responsivo (sem bots, nada a drenar).
+        while !ai_bots.is_empty() {
             match ai_reply_rx.try_recv() {
                 Ok(reply) => {
+                    // Só começa a "digitar" depois da última resposta pendente de
+                    // qualquer bot: vários bots chamados juntos não postam ao mesmo tempo.
+                    let typing_from = pending_ai_replies
+                        .values()
+                        .filter_map(VecDeque::back)
+                        .map(|last| last.ready_at)
+                        .fold(Instant::now(), Instant::max);
                     if let Some(queue) = pending_ai_replies.get_mut(&reply.bot_name) {
-                        queue.push_back(reply.text);
+                        queue.push_back(PendingAiReply {
+                            ready_at: typing_from + typing_delay_for(&reply.text),
+                            text: reply.text,
+                            forced: reply.forced,
+                        });
                     }
                 }
                 Err(TryRecvError::Empty) => break,
@@ -213,7 +940,9 @@ fn run_app(
             }
         }
 
-        // Publica respostas pendentes assim que o bot correspondente estiver online.
+        // Publica respostas pendentes quando a digitação simulada termina e o bot está online.
+        // Respostas quase iguais a uma mensagem recente são descartadas: bots não ecoam o chat.
+        let mut recent_texts = recent_chat_texts(state, AI_ECHO_WINDOW);
         for bot in ai_bots {
             if !bot.online.load(Ordering::SeqCst) {
                 continue;
@@ -221,8 +950,25 @@ fn run_app(
             let Some(queue) = pending_ai_replies.get_mut(&bot.profile.name) else {
                 continue;
             };
-            while let Some(reply) = queue.front().cloned() {
-                if bot.conn.reducers.send_message(reply).is_ok() {
+            let now = Instant::now();
+            while let Some(reply) = queue.front().filter(|r| r.ready_at <= now) {
+                if is_echo(&reply.text, &recent_texts) {
+                    let Some(echo) = queue.pop_front() else {
+                        break;
+                    };
+                    forget_reply(state, &bot.profile.name, &echo.text);
+                    if echo.forced {
+                        add_local_system_message(
+                            state,
+                            "System",
+                            format!(
+                                "{} only repeated a recent message; reply dropped",
+                                bot.profile.name
+                            ),
+                        );
+                    }
+                } else if bot.conn.reducers.send_message(reply.text.clone()).is_ok() {
+                    recent_texts.push(reply.text.clone());
                     let _ = queue.pop_front();
                 } else {
                     break;
@@ -230,8 +976,18 @@ fn run_app(
             }
         }
 
-        let snapshot = snapshot_state(state);
         let bot_identities = current_bot_identity_set(ai_bots);
+        update_state(state, |s| {
+            s.ui.animation_tick = s.ui.animation_tick.wrapping_add(1);
+            s.ui.bot_identities.clone_from(&bot_identities);
+            s.ui.refresh_user_lookup();
+            // Acompanhando o fim da conversa, tudo que chegou já está à vista.
+            if s.ui.messages_scroll == ScrollPosition::Bottom {
+                s.ui.mark_messages_read();
+            }
+        });
+        expire_offline_users(state);
+        let snapshot = snapshot_state(state);
         let online_human_identities: HashSet<&str> = snapshot
             .ui
             .users
@@ -241,29 +997,28 @@ fn run_app(
             .collect();
         let online_human_count = online_human_identities.len();
 
-        let mut all_message_ids: Vec<u64> = snapshot
+        let server_message_ids = snapshot
             .ui
             .messages
             .iter()
             .filter(|m| m.id < SYSTEM_MESSAGE_ID_BASE)
-            .map(|m| m.id)
-            .collect();
-        all_message_ids.sort_unstable();
-        if !initialized_seen {
-            last_seen_message_id = all_message_ids.last().copied().unwrap_or(0);
-            initialized_seen = true;
-        } else {
+            .map(|m| m.id);
+        if let Some(new_ids) = seen_messages.take_new(server_message_ids) {
             let mut new_messages: Vec<_> = snapshot
                 .ui
                 .messages
                 .iter()
-                .filter(|m| m.id > last_seen_message_id && m.id < SYSTEM_MESSAGE_ID_BASE)
+                .filter(|m| new_ids.binary_search(&m.id).is_ok())
                 .cloned()
                 .collect();
             new_messages.sort_by_key(|m| m.id);
-
+            if !new_messages.is_empty() {
+                let count = new_messages.len() as u64;
+                update_state(state, |s| s.activity.record(unix_now_secs(), count));
+            }
+            // Passo cronológico: marca como vistas e conta a sequência de falas de bots.
+            let mut candidates = Vec::with_capacity(new_messages.len());
             for message in new_messages {
-                last_seen_message_id = last_seen_message_id.max(message.id);
                 if message.text.trim().is_empty() {
                     continue;
                 }
@@ -279,58 +1034,197 @@ fn run_app(
                 } else {
                     consecutive_ai_messages = 0;
                 }
+                candidates.push((message, sender_is_ai, consecutive_ai_messages));
+            }
 
-                let directed_bot = find_directed_bot(ai_bots, &message.sender, &message.text);
-                let maybe_bot = directed_bot.or_else(|| {
-                    choose_responder_bot(
-                        ai_bots,
-                        &bot_identities,
-                        &message.sender,
-                        sender_is_ai,
-                        consecutive_ai_messages,
-                        online_human_count,
-                    )
+            // Rajadas: só parte das mensagens do tick aciona bots; humanos primeiro se `human_first`.
+            let from_ai: Vec<bool> = candidates.iter().map(|(_, ai, _)| *ai).collect();
+            let order = dispatch_order(
+                &from_ai,
+                config::current().ai.max_dispatch_per_tick,
+                config::current().ai.human_first,
+            );
+            for index in order {
+                let &(ref message, sender_is_ai, consecutive_ai_messages) = &candidates[index];
+                let is_mine = snapshot.my_identity.as_deref() == Some(message.sender.as_str());
+                if is_mine && forced_ai_prompts.take_match(&message.text, Instant::now()) {
+                    continue;
+                }
+                let directed_bots = find_directed_bots(ai_bots, &message.sender, &message.text);
+                let directed_bot = directed_bots.first().copied();
+                // `@Bot /comando` tem resposta pronta e não passa pelo modelo.
+                let command_reply = directed_bot.filter(|_| !sender_is_ai).and_then(|bot| {
+                    bot_command_reply(&snapshot, &bot.profile, &message.text)
+                        .map(|text| (bot.profile.name.clone(), text))
                 });
-                if let Some(bot) = maybe_bot {
+                if let Some((bot_name, text)) = command_reply {
+                    let _ = ai_reply_tx.send(AiGeneratedReply {
+                        bot_name,
+                        text,
+                        forced: false,
+                    });
+                    continue;
+                }
+                let open_question = !sender_is_ai
+                    && config::current().ai.open_question_round_robin
+                    && is_open_question(&message.text);
+                let maybe_bot =
+                    choose_dispatch_target(snapshot.bot_reply_mode, directed_bot, || {
+                        if open_question {
+                            let online: Vec<&AiBotRuntime> = ai_bots
+                                .iter()
+                                .filter(|bot| bot.online.load(Ordering::SeqCst))
+                                .collect();
+                            return open_question_turn.pick(&online);
+                        }
+                        choose_responder_bot(
+                            ai_bots,
+                            &bot_identities,
+                            &message.sender,
+                            &message.text,
+                            consecutive_ai_messages,
+                            online_human_count,
+                            &tuning,
+                        )
+                    });
+                // Os outros bots citados na mesma mensagem também respondem.
+                let also_named = directed_bots
+                    .iter()
+                    .skip(1)
+                    .take(directed_reply_limit(consecutive_ai_messages) - 1);
+                for bot in maybe_bot.into_iter().chain(also_named.copied()) {
                     request_bot_reply(
                         state,
                         bot.profile.clone(),
                         message.text.clone(),
+                        false,
                         ai_reply_tx.clone(),
                     );
                 }
             }
 
-            maybe_start_proactive_ai_chat(
-                ai_bots,
-                state,
-                ai_reply_tx,
-                &pending_ai_replies,
-                online_human_count,
-                &mut last_chat_activity,
-                &mut last_proactive_attempt,
-            );
+            // Com respostas ainda na fila, os bots não puxam assunto novo.
+            if snapshot.bot_reply_mode.allows_proactive_chat()
+                && pending_ai_replies.values().all(VecDeque::is_empty)
+            {
+                maybe_start_proactive_ai_chat(
+                    ai_bots,
+                    state,
+                    ai_reply_tx,
+                    online_human_count,
+                    &tuning,
+                    &mut last_chat_activity,
+                    &mut last_proactive_attempt,
+                );
+            }
         }
 
+        let mut messages_max_scroll = snapshot.ui.messages_max_scroll;
+        let mut jump_scroll = None;
+        let mut chat_areas = None;
+        let activity = snapshot.activity.sparkline_data(unix_now_secs());
         terminal.draw(|f| match snapshot.ui.screen {
             UiScreen::MainMenu => render_menu_screen(f, &snapshot.ui, snapshot.status),
-            UiScreen::Chat => render_ui(f, &snapshot.ui, snapshot.status),
+            UiScreen::Chat => {
+                let rendered = render_ui(
+                    f,
+                    &snapshot.ui,
+                    snapshot.my_identity.as_deref(),
+                    snapshot.status,
+                    &activity,
+                );
+                messages_max_scroll = rendered.messages_max_scroll;
+                jump_scroll = rendered.jump_scroll;
+                chat_areas = Some((rendered.messages_area, rendered.users_area));
+            }
         })?;
+        if messages_max_scroll != snapshot.ui.messages_max_scroll
+            || snapshot.ui.loading_older_messages
+        {
+            update_state(state, |s| {
+                s.ui.messages_max_scroll = messages_max_scroll;
+                // A página antiga já foi desenhada: libera novo pedido de "Load older".
+                s.ui.loading_older_messages = false;
+            });
+        }
+        if snapshot.ui.scroll_to_message.is_some() {
+            update_state(state, |s| {
+                s.ui.scroll_to_message = None;
+                if let Some(position) = jump_scroll {
+                    s.ui.messages_scroll = position;
+                }
+            });
+        }
+
+        // Áreas dos painéis para o scroll do mouse saber onde está o cursor.
+        if let Some((messages_area, users_area)) = chat_areas
+            && (messages_area, users_area) != (snapshot.ui.messages_area, snapshot.ui.users_area)
+        {
+            update_state(state, |s| {
+                s.ui.messages_area = messages_area;
+                s.ui.users_area = users_area;
+            });
+        }
 
         if snapshot.ui.should_quit {
             break;
         }
 
-        if event::poll(Duration::from_millis(50))?
-            && let CEvent::Key(key) = event::read()?
-        {
-            handle_key_event(key, conn, state)?;
+        if event::poll(Duration::from_millis(50))? {
+            match event::read()? {
+                CEvent::Key(key) => handle_key_event(key, conn, state)?,
+                CEvent::Mouse(mouse) => handle_mouse_event(mouse, state),
+                _ => {}
+            }
         }
     }
 
     Ok(())
 }
 
+/// Sorteia novas personas para os bots em execução e reenvia `set_name`.
+///
+/// Nomes de humanos online são evitados; histórico e respostas pendentes da
+/// persona antiga são descartados para não vazar no novo personagem.
+fn reroll_bot_personas(
+    ai_bots: &mut [AiBotRuntime],
+    state: &SharedState,
+    pending_ai_replies: &mut HashMap<String, VecDeque<PendingAiReply>>,
+) {
+    let snapshot = snapshot_state(state);
+    let bot_identities = current_bot_identity_set(ai_bots);
+    let excluded: Vec<&str> = snapshot
+        .ui
+        .users
+        .iter()
+        .filter(|u| u.online && !bot_identities.contains(u.identity.as_str()))
+        .map(|u| u.name.as_str())
+        .collect();
+
+    let profiles = generate_bot_profiles_excluding(ai_bots.len(), &excluded);
+    for (bot, profile) in ai_bots.iter_mut().zip(profiles) {
+        let old = std::mem::replace(&mut bot.profile, profile);
+        pending_ai_replies.remove(&old.name);
+        pending_ai_replies.insert(bot.profile.name.clone(), VecDeque::new());
+        update_state(state, |s| {
+            s.ai_histories.remove(&old.name);
+            s.ai_moods.remove(&old.name);
+        });
+        let _ = bot.conn.reducers.set_name(bot.profile.name.clone());
+        let _ = bot
+            .conn
+            .reducers
+            .set_status(profession_status(&bot.profile.profession).to_string());
+    }
+
+    let cast = ai_bots
+        .iter()
+        .map(|bot| format!("{} ({})", bot.profile.name, bot.profile.profession))
+        .collect::<Vec<_>>()
+        .join(", ");
+    add_local_system_message(state, "System", format!("Bots re-rolled: {cast}"));
+}
+
 fn current_bot_identity_set(ai_bots: &[AiBotRuntime]) -> HashSet<String> {
     ai_bots
         .iter()
@@ -342,9 +1236,10 @@ fn choose_responder_bot<'a>(
     ai_bots: &'a [AiBotRuntime],
     bot_identities: &HashSet<String>,
     sender_identity: &str,
-    sender_is_ai: bool,
+    message_text: &str,
     consecutive_ai_messages: usize,
     online_human_count: usize,
+    tuning: &AiTuning,
 ) -> Option<&'a AiBotRuntime> {
     let mut rng = rng();
 
@@ -353,14 +1248,14 @@ fn choose_responder_bot<'a>(
         .filter(|bot| bot.online.load(Ordering::SeqCst))
         .collect();
 
-    if sender_is_ai {
+    if bot_identities.contains(sender_identity) {
         if consecutive_ai_messages >= MAX_AI_CHAIN_MESSAGES {
             return None;
         }
         let ai_reply_chance = if online_human_count > 0 {
-            AI_TO_AI_REPLY_CHANCE_WITH_HUMANS
+            tuning.ai_reply_chance_with_humans
         } else {
-            AI_TO_AI_REPLY_CHANCE_IDLE
+            tuning.ai_reply_chance_idle
         };
         if !rng.random_bool(ai_reply_chance) {
             return None;
@@ -372,42 +1267,52 @@ fn choose_responder_bot<'a>(
                 .and_then(|v| v.clone())
                 .is_none_or(|id| id != sender_identity)
         });
-    } else if sender_identity == "System" || bot_identities.contains(sender_identity) {
+    } else if sender_identity == "System" {
         return None;
     }
 
-    candidates.choose(&mut rng).copied()
+    // Quem tem a ver com o assunto responde mais; o piso mantém todos no sorteio.
+    candidates
+        .choose_weighted(&mut rng, |bot| {
+            profession_weight(&bot.profile.profession, message_text)
+        })
+        .ok()
+        .copied()
 }
 
 fn maybe_start_proactive_ai_chat(
     ai_bots: &[AiBotRuntime],
     state: &SharedState,
     ai_reply_tx: &Sender<AiGeneratedReply>,
-    pending_ai_replies: &HashMap<String, VecDeque<String>>,
     online_human_count: usize,
+    tuning: &AiTuning,
     last_chat_activity: &mut Instant,
     last_proactive_attempt: &mut Instant,
 ) {
     if ai_bots.len() < 2 {
         return;
     }
-    if pending_ai_replies.values().any(|q| !q.is_empty()) {
-        return;
+
+    if let Some(quiet) = QuietHours::parse(&config::current().ai.quiet_hours) {
+        let now = Local::now();
+        if quiet.contains(now.hour() * 60 + now.minute()) {
+            return;
+        }
     }
 
-    let cooldown = Duration::from_secs(AI_PROACTIVE_COOLDOWN_SECS);
+    let cooldown = Duration::from_secs(tuning.proactive_cooldown_secs);
     if last_proactive_attempt.elapsed() < cooldown {
         return;
     }
     *last_proactive_attempt = Instant::now();
 
-    let idle_window = Duration::from_secs(AI_PROACTIVE_IDLE_SECS);
+    let idle_window = Duration::from_secs(tuning.proactive_idle_secs);
     if last_chat_activity.elapsed() < idle_window {
         return;
     }
 
     let mut rng = rng();
-    if !rng.random_bool(AI_PROACTIVE_START_CHANCE) {
+    if !rng.random_bool(tuning.proactive_start_chance) {
         return;
     }
 
@@ -424,7 +1329,13 @@ fn maybe_start_proactive_ai_chat(
     let target = online_bots[1];
     let opening = proactive_opening_prompt(&target.profile.name, online_human_count > 0);
 
-    request_bot_reply(state, starter.profile.clone(), opening, ai_reply_tx.clone());
+    request_bot_reply(
+        state,
+        starter.profile.clone(),
+        opening,
+        false,
+        ai_reply_tx.clone(),
+    );
     *last_chat_activity = Instant::now();
 }
 
@@ -457,14 +1368,30 @@ fn proactive_opening_prompt(target_name: &str, has_humans_online: bool) -> Strin
     )
 }
 
-fn find_directed_bot<'a>(
+/// Textos das últimas `limit` mensagens do servidor, da mais recente para a mais antiga.
+fn recent_chat_texts(state: &SharedState, limit: usize) -> Vec<String> {
+    let Ok(guard) = state.lock() else {
+        return Vec::new();
+    };
+    guard
+        .ui
+        .messages
+        .iter()
+        .rev()
+        .filter(|m| m.id < SYSTEM_MESSAGE_ID_BASE && !m.text.trim().is_empty())
+        .take(limit)
+        .map(|m| m.text.clone())
+        .collect()
+}
+
+/// Bots online citados pelo nome na mensagem (menos quem a enviou), na ordem
+/// em que aparecem no texto.
+fn find_directed_bots<'a>(
     ai_bots: &'a [AiBotRuntime],
     sender_identity: &str,
     message_text: &str,
-) -> Option<&'a AiBotRuntime> {
-    let lowered_message = message_text.to_lowercase();
-
-    ai_bots
+) -> Vec<&'a AiBotRuntime> {
+    let candidates: Vec<&AiBotRuntime> = ai_bots
         .iter()
         .filter(|bot| bot.online.load(Ordering::SeqCst))
         .filter(|bot| {
@@ -474,5 +1401,13 @@ fn find_directed_bot<'a>(
                 .and_then(|v| v.clone())
                 .is_none_or(|id| id != sender_identity)
         })
-        .find(|bot| lowered_message.contains(&bot.profile.name.to_lowercase()))
+        .collect();
+    let names: Vec<&str> = candidates
+        .iter()
+        .map(|bot| bot.profile.name.as_str())
+        .collect();
+    mentioned_names(&names, message_text)
+        .into_iter()
+        .map(|index| candidates[index])
+        .collect()
 }
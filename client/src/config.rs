@@ -0,0 +1,721 @@
+// Configuração unificada do client.
+//
+// Ordem de precedência (maior vence): CLI > variáveis de ambiente > arquivos > padrão.
+// O arquivo do usuário fica em `~/.config/shellrelay/config.toml` (ou
+// `$SHELLRELAY_CONFIG`); um `./shellrelay.toml` no diretório atual vai por cima,
+// chave a chave, sem poder trocar caminhos de arquivo nem endpoints da IA.
+// Os arquivos podem ser parciais: seções/campos ausentes usam os valores padrão.
+
+use std::{
+    env, fmt, fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+use crate::ai::{
+    AI_BOT_COUNT, AI_HISTORY_TOKEN_BUDGET, AI_MAX_DISPATCH_PER_TICK, AiTuning, DEFAULT_HOST,
+    DEFAULT_MODEL, DEFAULT_NUM_PREDICT, DEFAULT_OPENAI_URL, DEFAULT_PORT, DEFAULT_REPEAT_PENALTY,
+    DEFAULT_RETRIES, DEFAULT_TEMPERATURE, DEFAULT_TIMEOUT_SECS, DEFAULT_TOP_P, MAX_RETRIES,
+};
+use crate::connection::{ConnectError, DEFAULT_CONNECT_TIMEOUT_SECS, validate_target};
+
+static CURRENT: OnceLock<Config> = OnceLock::new();
+
+/// Configuração completa carregada no startup.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Config {
+    pub connection: ConnectionConfig,
+    pub ai: AiConfig,
+    pub ui: UiConfig,
+    pub keybindings: KeybindingsConfig,
+    pub debug: DebugConfig,
+}
+
+/// Acesso ao SpacetimeDB.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ConnectionConfig {
+    pub module: String,
+    pub uri: String,
+    pub connect_timeout_secs: u64,
+}
+
+impl Default for ConnectionConfig {
+    fn default() -> Self {
+        Self {
+            module: "shell-relay-test".to_string(),
+            uri: "http://127.0.0.1:3000".to_string(),
+            connect_timeout_secs: DEFAULT_CONNECT_TIMEOUT_SECS,
+        }
+    }
+}
+
+impl ConnectionConfig {
+    /// Mesma configuração apontando para outro servidor (`/connect`); o prazo é mantido.
+    pub fn retarget(&self, uri: &str, module: &str) -> Result<Self, ConnectError> {
+        validate_target(uri, module)?;
+        Ok(Self {
+            module: module.to_string(),
+            uri: uri.to_string(),
+            ..self.clone()
+        })
+    }
+}
+
+/// Backend de IA (Ollama) e comportamento dos bots.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AiConfig {
+    /// `false` (`--no-ai`): chat só de humanos, sem bots nem Ollama.
+    pub enabled: bool,
+    /// `ollama` (padrão) ou `openai` (servidor compatível: LM Studio, vLLM...).
+    pub backend: String,
+    /// Base da API no backend `openai` (termina em `/v1`).
+    pub openai_url: String,
+    /// Chave enviada como `Bearer` no backend `openai` (vazio = sem autenticação).
+    pub openai_api_key: String,
+    pub model: String,
+    /// Modelos distribuídos em rodízio entre os bots (vazio = todos usam `model`).
+    pub models: Vec<String>,
+    pub host: String,
+    pub port: u16,
+    /// Espera máxima por uma resposta do Ollama, em segundos.
+    pub timeout_secs: u64,
+    /// Tentativas por resposta quando a conexão com o Ollama falha (`1..=MAX_RETRIES`).
+    pub retries: u32,
+    /// Temperatura base; o humor de cada bot oscila em volta dela (limitada a `0.0..=2.0`).
+    pub temperature: f32,
+    /// Amostragem nucleus (`0.0..=1.0`).
+    pub top_p: f32,
+    /// Penalidade de repetição (não negativa).
+    pub repeat_penalty: f32,
+    /// Máximo de tokens gerados por resposta (mínimo 1).
+    pub num_predict: i32,
+    /// Quantidade de bots pedida (`None` = `AI_BOT_COUNT`); limitada a
+    /// `0..=FANTASY_NAMES.len()` no startup.
+    pub bot_count: Option<i64>,
+    /// Arquivo TOML de personas; quando válido, define os bots no lugar do sorteio.
+    pub personas: Option<PathBuf>,
+    /// `ambient` (padrão) ou `spoken-to`.
+    pub bots_mode: String,
+    /// Texto de lore/conhecimento compartilhado injetado no prompt de todos os bots.
+    pub lore: String,
+    /// Prompt base de todos os bots no lugar do embutido (vazio = embutido, em português).
+    pub system_prompt: String,
+    /// Arquivo lido no startup para `system_prompt`; tem prioridade sobre o texto.
+    pub system_prompt_file: Option<PathBuf>,
+    /// Arquivo JSON com a memória dos bots entre execuções (`--persist-memory FILE`).
+    pub memory_file: Option<PathBuf>,
+    /// Mostra no chat (só localmente) o texto do bot enquanto ele ainda está sendo gerado.
+    pub show_partial_replies: bool,
+    /// Perguntas abertas (`?` sem `@menção`) sempre recebem uma resposta, em rodízio.
+    pub open_question_round_robin: bool,
+    /// Máximo de mensagens novas que acionam bots por tick (as mais recentes; `0` = sem limite).
+    pub max_dispatch_per_tick: usize,
+    /// Num mesmo tick, mensagens de humanos acionam bots antes (e no lugar) das de outros bots.
+    pub human_first: bool,
+    /// Orçamento em tokens estimados (~4 chars cada) do histórico de cada bot (`0` = sem limite).
+    pub history_token_budget: usize,
+    /// Horário local sem conversa espontânea entre bots, `"HH:MM-HH:MM"` (vazio desativa).
+    pub quiet_hours: String,
+    /// Chances e intervalos da conversa entre bots (`[ai.tuning]`).
+    pub tuning: AiTuning,
+}
+
+impl AiConfig {
+    /// Quantidade de bots pedida, com o padrão quando ninguém definiu.
+    pub fn requested_bot_count(&self) -> i64 {
+        self.bot_count.unwrap_or(AI_BOT_COUNT as i64)
+    }
+}
+
+impl Default for AiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            backend: "ollama".to_string(),
+            openai_url: DEFAULT_OPENAI_URL.to_string(),
+            openai_api_key: String::new(),
+            model: DEFAULT_MODEL.to_string(),
+            models: Vec::new(),
+            host: DEFAULT_HOST.to_string(),
+            port: DEFAULT_PORT,
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+            retries: DEFAULT_RETRIES,
+            temperature: DEFAULT_TEMPERATURE,
+            top_p: DEFAULT_TOP_P,
+            repeat_penalty: DEFAULT_REPEAT_PENALTY,
+            num_predict: DEFAULT_NUM_PREDICT,
+            bot_count: None,
+            personas: None,
+            bots_mode: "ambient".to_string(),
+            lore: String::new(),
+            system_prompt: String::new(),
+            system_prompt_file: None,
+            memory_file: None,
+            show_partial_replies: false,
+            open_question_round_robin: true,
+            max_dispatch_per_tick: AI_MAX_DISPATCH_PER_TICK,
+            human_first: true,
+            history_token_budget: AI_HISTORY_TOKEN_BUDGET,
+            quiet_hours: String::new(),
+            tuning: AiTuning::default(),
+        }
+    }
+}
+
+/// Preferências visuais.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct UiConfig {
+    /// Cor das próprias mensagens (nome/hex, `off`); ausente = sorteada por sessão.
+    pub own_message_color: Option<String>,
+    pub idle_animation: bool,
+    /// Tema de cores: `default`, `amber`, `mono` ou `high-contrast`.
+    pub theme: String,
+    /// Glifos só em ASCII e alto contraste (leitores de tela, terminais limitados).
+    pub accessibility: bool,
+    /// Segundos antes de exibir como offline quem acabou de cair e de avisar quem entrou (`0` desativa).
+    pub offline_grace_secs: u64,
+    /// Colunas máximas do texto das mensagens, centralizado em terminais largos (`0` = toda a largura).
+    pub max_message_width: u16,
+    /// Separador entre os itens do rodapé de instruções.
+    pub footer_separator: String,
+    /// Par que envolve as teclas do rodapé: primeiro caractere abre, o resto fecha (`""` = sem).
+    pub footer_brackets: String,
+    /// Traço das laterais do rodapé; vazio = o do modo de glifos.
+    pub footer_rule: String,
+    /// Lista de palavras mascaradas com `*` na tela (`--filter-file FILE`, uma por linha).
+    pub filter_file: Option<PathBuf>,
+    /// Chat salvo em JSON na saída do app (`--export-json FILE`).
+    pub export_json: Option<PathBuf>,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            own_message_color: None,
+            idle_animation: true,
+            theme: "default".to_string(),
+            offline_grace_secs: 5,
+            accessibility: false,
+            max_message_width: 0,
+            footer_separator: "  ".to_string(),
+            footer_brackets: "<>".to_string(),
+            footer_rule: String::new(),
+            filter_file: None,
+            export_json: None,
+        }
+    }
+}
+
+/// Teclas configuráveis (nomes no formato `F5`, `Ctrl+r`, `Esc`...).
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct KeybindingsConfig {
+    pub reroll_bots: String,
+    pub menu: String,
+    /// Cita a mensagem mais recente no input (repetir volta para as anteriores).
+    pub quote: String,
+    /// Abre a ajuda do chat (`?` com o input vazio também abre; `??` digita um `?`).
+    pub help: String,
+    /// Alterna a hora das mensagens entre absoluta e relativa ("2m ago").
+    pub timestamps: String,
+    /// Mostra/oculta os avisos do sistema (entradas, saídas, erros) no chat.
+    pub system_messages: String,
+    /// Abre a busca nas mensagens do chat.
+    pub search: String,
+    /// Com busca ativa, foca a ocorrência anterior (mais antiga).
+    pub search_next: String,
+    /// Com busca ativa, volta para a ocorrência mais recente.
+    pub search_previous: String,
+    /// Seleciona uma mensagem própria para apagar (repetir abre a confirmação).
+    pub delete_message: String,
+    /// Copia a mensagem visível mais recente (ou a focada na busca).
+    pub copy: String,
+    /// Exporta o chat para uma transcrição no diretório atual.
+    pub export: String,
+    /// Exporta o chat em JSON (id, `sent_at` cru, nome de quem enviou).
+    pub export_json: String,
+}
+
+impl Default for KeybindingsConfig {
+    fn default() -> Self {
+        Self {
+            reroll_bots: "F5".to_string(),
+            menu: "F1".to_string(),
+            quote: "Ctrl+q".to_string(),
+            help: "F2".to_string(),
+            timestamps: "Ctrl+t".to_string(),
+            system_messages: "F3".to_string(),
+            search: "Ctrl+f".to_string(),
+            search_next: "Ctrl+n".to_string(),
+            search_previous: "Ctrl+p".to_string(),
+            delete_message: "Ctrl+d".to_string(),
+            copy: "Ctrl+y".to_string(),
+            export: "Ctrl+e".to_string(),
+            export_json: "Alt+e".to_string(),
+        }
+    }
+}
+
+/// Ferramentas de diagnóstico.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct DebugConfig {
+    /// Arquivo do log de eventos de sync (`None` desativa).
+    pub event_log: Option<PathBuf>,
+    /// Mostra o `sent_at` das mensagens exatamente como veio do servidor.
+    pub raw_timestamps: bool,
+}
+
+/// Arquivo usado por `--debug` quando nenhum caminho foi configurado.
+pub const DEFAULT_EVENT_LOG: &str = "shellrelay-events.log";
+
+/// Falha ao ler ou interpretar o arquivo de configuração.
+#[derive(Debug)]
+pub struct ConfigError {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid config {}: {}", self.path.display(), self.reason)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// Carrega padrão + arquivo + env + CLI (argumentos posicionais `<module> <uri>`).
+    pub fn load(cli_args: &[String]) -> Result<Self, ConfigError> {
+        let sources = config_sources(
+            |key| env::var(key).ok(),
+            Path::new(LOCAL_CONFIG_FILE).is_file(),
+        );
+        let mut config = Self::from_files(&sources)?;
+        config.apply_env(|key| env::var(key).ok());
+        config.apply_cli(cli_args);
+        config.load_system_prompt_file()?;
+        Ok(config)
+    }
+
+    /// Lê `ai.system_prompt_file` (se houver) para `ai.system_prompt`.
+    fn load_system_prompt_file(&mut self) -> Result<(), ConfigError> {
+        let Some(path) = &self.ai.system_prompt_file else {
+            return Ok(());
+        };
+        self.ai.system_prompt = fs::read_to_string(path).map_err(|err| ConfigError {
+            path: path.clone(),
+            reason: err.to_string(),
+        })?;
+        Ok(())
+    }
+
+    /// Lê os arquivos que existirem, do mais fraco ao mais forte; nenhum
+    /// arquivo equivale à configuração padrão.
+    pub fn from_files(sources: &[ConfigSource]) -> Result<Self, ConfigError> {
+        let mut layers = Vec::with_capacity(sources.len());
+        for source in sources {
+            let error = |reason: String| ConfigError {
+                path: source.path.clone(),
+                reason,
+            };
+            match fs::read_to_string(&source.path) {
+                Ok(raw) => layers.push(parse_layer(&raw, source.trusted).map_err(error)?),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(error(err.to_string())),
+            }
+        }
+        // Cada arquivo já foi validado sozinho; a junção só combina campos válidos.
+        Ok(Self::from_layers(layers).unwrap_or_default())
+    }
+
+    pub fn from_toml_str(raw: &str) -> Result<Self, String> {
+        Self::from_layers([parse_layer(raw, true)?])
+    }
+
+    /// Junta as tabelas (cada uma sobrescreve as anteriores, chave a chave) e interpreta.
+    pub fn from_layers(layers: impl IntoIterator<Item = toml::Table>) -> Result<Self, String> {
+        let mut merged = toml::Table::new();
+        for layer in layers {
+            merge_tables(&mut merged, layer);
+        }
+        let mut config: Self = toml::Value::Table(merged)
+            .try_into()
+            .map_err(|e: toml::de::Error| e.to_string())?;
+        config.ai.retries = config.ai.retries.min(MAX_RETRIES);
+        Ok(config)
+    }
+
+    /// Sobrescreve campos com variáveis de ambiente (lookup injetável para testes).
+    pub fn apply_env(&mut self, lookup: impl Fn(&str) -> Option<String>) {
+        if let Some(v) = lookup("STDB_MODULE") {
+            self.connection.module = v;
+        }
+        if let Some(v) = lookup("STDB_URI") {
+            self.connection.uri = v;
+        }
+        if let Some(secs) = lookup("STDB_CONNECT_TIMEOUT_SECS")
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+        {
+            self.connection.connect_timeout_secs = secs;
+        }
+        if let Some(v) = lookup("OLLAMA_MODEL") {
+            self.ai.model = v;
+        }
+        if let Some(v) = lookup("OLLAMA_MODELS") {
+            self.ai.models = v
+                .split(',')
+                .map(str::trim)
+                .filter(|model| !model.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+        if let Some(v) = lookup("LLM_BACKEND") {
+            self.ai.backend = v;
+        }
+        if let Some(v) = lookup("OPENAI_BASE_URL") {
+            self.ai.openai_url = v;
+        }
+        if let Some(v) = lookup("OPENAI_API_KEY") {
+            self.ai.openai_api_key = v;
+        }
+        if let Some(v) = lookup("OLLAMA_HOST") {
+            self.ai.host = v;
+        }
+        if let Some(port) = lookup("OLLAMA_PORT").and_then(|p| p.parse::<u16>().ok()) {
+            self.ai.port = port;
+        }
+        if let Some(secs) = lookup("OLLAMA_TIMEOUT_SECS")
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+        {
+            self.ai.timeout_secs = secs;
+        }
+        if let Some(retries) = lookup("OLLAMA_RETRIES").and_then(|v| v.trim().parse::<u32>().ok()) {
+            self.ai.retries = retries.min(MAX_RETRIES);
+        }
+        let sampling = |key: &str| {
+            lookup(key)
+                .and_then(|v| v.trim().parse::<f32>().ok())
+                .filter(|v| v.is_finite())
+        };
+        if let Some(temperature) = sampling("OLLAMA_TEMPERATURE") {
+            self.ai.temperature = temperature;
+        }
+        if let Some(top_p) = sampling("OLLAMA_TOP_P") {
+            self.ai.top_p = top_p;
+        }
+        if let Some(penalty) = sampling("OLLAMA_REPEAT_PENALTY") {
+            self.ai.repeat_penalty = penalty;
+        }
+        if let Some(tokens) =
+            lookup("OLLAMA_NUM_PREDICT").and_then(|v| v.trim().parse::<i32>().ok())
+        {
+            self.ai.num_predict = tokens;
+        }
+        if let Some(count) = lookup("SHELLRELAY_BOTS").and_then(|v| v.trim().parse::<i64>().ok()) {
+            self.ai.bot_count = Some(count);
+        }
+        if let Some(v) = lookup("SHELLRELAY_NO_AI") {
+            self.ai.enabled = !matches!(
+                v.trim().to_ascii_lowercase().as_str(),
+                "1" | "on" | "true" | "yes"
+            );
+        }
+        if let Some(v) = lookup("SHELLRELAY_PARTIAL_REPLIES") {
+            self.ai.show_partial_replies = matches!(
+                v.trim().to_ascii_lowercase().as_str(),
+                "1" | "on" | "true" | "yes"
+            );
+        }
+        if let Some(v) = lookup("OLLAMA_SYSTEM_PROMPT") {
+            self.ai.system_prompt = v;
+        }
+        if let Some(v) = lookup("SHELLRELAY_PERSONAS") {
+            self.ai.personas = Some(PathBuf::from(v));
+        }
+        if let Some(v) = lookup("SHELLRELAY_BOTS_MODE") {
+            self.ai.bots_mode = v;
+        }
+        if let Some(tokens) =
+            lookup("SHELLRELAY_HISTORY_TOKENS").and_then(|v| v.trim().parse::<usize>().ok())
+        {
+            self.ai.history_token_budget = tokens;
+        }
+        if let Some(v) = lookup("SHELLRELAY_QUIET_HOURS") {
+            self.ai.quiet_hours = v;
+        }
+        let chance = |key: &str| lookup(key).and_then(|v| v.trim().parse::<f64>().ok());
+        let secs = |key: &str| lookup(key).and_then(|v| v.trim().parse::<u64>().ok());
+        let tuning = &mut self.ai.tuning;
+        if let Some(v) = chance("SHELLRELAY_PROACTIVE_CHANCE") {
+            tuning.proactive_start_chance = v;
+        }
+        if let Some(v) = secs("SHELLRELAY_PROACTIVE_COOLDOWN_SECS") {
+            tuning.proactive_cooldown_secs = v;
+        }
+        if let Some(v) = secs("SHELLRELAY_PROACTIVE_IDLE_SECS") {
+            tuning.proactive_idle_secs = v;
+        }
+        if let Some(v) = chance("SHELLRELAY_AI_REPLY_CHANCE_IDLE") {
+            tuning.ai_reply_chance_idle = v;
+        }
+        if let Some(v) = chance("SHELLRELAY_AI_REPLY_CHANCE_WITH_HUMANS") {
+            tuning.ai_reply_chance_with_humans = v;
+        }
+        *tuning = tuning.validated();
+        if let Some(v) = lookup("SHELLRELAY_OWN_COLOR") {
+            self.ui.own_message_color = Some(v);
+        }
+        if let Some(v) = lookup("SHELLRELAY_EVENT_LOG") {
+            self.debug.event_log = Some(PathBuf::from(v));
+        }
+        if let Some(v) = lookup("SHELLRELAY_THEME") {
+            self.ui.theme = v;
+        }
+        if let Some(secs) =
+            lookup("SHELLRELAY_OFFLINE_GRACE_SECS").and_then(|v| v.trim().parse::<u64>().ok())
+        {
+            self.ui.offline_grace_secs = secs;
+        }
+        if let Some(width) =
+            lookup("SHELLRELAY_MAX_MESSAGE_WIDTH").and_then(|v| v.trim().parse::<u16>().ok())
+        {
+            self.ui.max_message_width = width;
+        }
+        if let Some(v) = lookup("SHELLRELAY_ACCESSIBILITY") {
+            self.ui.accessibility = matches!(
+                v.trim().to_ascii_lowercase().as_str(),
+                "1" | "on" | "true" | "yes"
+            );
+        }
+        if let Some(v) = lookup("SHELLRELAY_IDLE_ANIMATION") {
+            self.ui.idle_animation = !v.trim().eq_ignore_ascii_case("off");
+        }
+    }
+
+    /// Argumentos: `client [--debug] [--no-ai] [--bots N] [--personas FILE]
+    /// [--system-prompt-file FILE] [--persist-memory FILE] [--filter-file FILE]
+    /// [--export-json FILE] <module> <uri>`.
+    pub fn apply_cli(&mut self, args: &[String]) {
+        let mut positional = Vec::new();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            // `--flag valor` ou `--flag=valor`; o valor não conta como posicional.
+            let Some((flag, inline)) = split_value_flag(arg) else {
+                if !arg.starts_with("--") {
+                    positional.push(arg);
+                }
+                continue;
+            };
+            let Some(value) = inline.or_else(|| iter.next().map(String::as_str)) else {
+                continue;
+            };
+            match flag {
+                "--bots" => {
+                    if let Ok(count) = value.trim().parse::<i64>() {
+                        self.ai.bot_count = Some(count);
+                    }
+                }
+                "--personas" => self.ai.personas = Some(PathBuf::from(value)),
+                "--persist-memory" => self.ai.memory_file = Some(PathBuf::from(value)),
+                "--filter-file" => self.ui.filter_file = Some(PathBuf::from(value)),
+                "--export-json" => self.ui.export_json = Some(PathBuf::from(value)),
+                "--system-prompt-file" => {
+                    self.ai.system_prompt_file = Some(PathBuf::from(value));
+                }
+                other => unreachable!("{other} is in VALUE_FLAGS but has no handler"),
+            }
+        }
+        let mut positional = positional.into_iter();
+        if let Some(module) = positional.next() {
+            self.connection.module = module.clone();
+        }
+        if let Some(uri) = positional.next() {
+            self.connection.uri = uri.clone();
+        }
+        if args.iter().any(|arg| arg == "--no-ai") {
+            self.ai.enabled = false;
+        }
+        if args.iter().any(|arg| arg == "--debug") {
+            self.debug.raw_timestamps = true;
+            if self.debug.event_log.is_none() {
+                self.debug.event_log = Some(PathBuf::from(DEFAULT_EVENT_LOG));
+            }
+        }
+    }
+}
+
+/// Flags de linha de comando que recebem valor.
+const VALUE_FLAGS: [&str; 6] = [
+    "--bots",
+    "--personas",
+    "--system-prompt-file",
+    "--persist-memory",
+    "--filter-file",
+    "--export-json",
+];
+
+/// Separa `--flag=valor` em `(flag, Some(valor))`; `--flag` sozinho vira `(flag, None)`.
+fn split_value_flag(arg: &str) -> Option<(&'static str, Option<&str>)> {
+    VALUE_FLAGS
+        .iter()
+        .find_map(|&flag| match arg.strip_prefix(flag)? {
+            "" => Some((flag, None)),
+            rest => rest.strip_prefix('=').map(|value| (flag, Some(value))),
+        })
+}
+
+/// Interpreta uma tecla no formato `F5`, `Ctrl+r`, `Alt+Enter`, `Esc`...
+pub fn parse_key_binding(raw: &str) -> Option<(KeyCode, KeyModifiers)> {
+    let mut parts: Vec<&str> = raw.split('+').map(str::trim).collect();
+    let key = parts.pop()?;
+    let mut modifiers = KeyModifiers::NONE;
+    for part in parts {
+        modifiers |= match part.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return None,
+        };
+    }
+
+    let lower = key.to_ascii_lowercase();
+    let code = match lower.as_str() {
+        "esc" | "escape" => KeyCode::Esc,
+        "enter" => KeyCode::Enter,
+        "tab" => KeyCode::Tab,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" | "pgup" => KeyCode::PageUp,
+        "pagedown" | "pgdn" => KeyCode::PageDown,
+        _ if lower.len() > 1 && lower.starts_with('f') => KeyCode::F(lower[1..].parse().ok()?),
+        _ => {
+            let mut chars = key.chars();
+            let c = chars.next()?;
+            if chars.next().is_some() {
+                return None;
+            }
+            KeyCode::Char(c)
+        }
+    };
+    Some((code, modifiers))
+}
+
+/// Verdadeiro quando `key` corresponde à tecla configurada em `binding`.
+///
+/// Os modificadores precisam ser os mesmos (`Ctrl+e` não casa com `Ctrl+Alt+e`);
+/// só o SHIFT é ignorado em caracteres, onde ele já vem no próprio caractere.
+pub fn key_matches(binding: &str, key: &KeyEvent) -> bool {
+    parse_key_binding(binding).is_some_and(|(code, modifiers)| {
+        let compared = match code {
+            KeyCode::Char(_) => !KeyModifiers::SHIFT,
+            _ => KeyModifiers::all(),
+        };
+        key.code == code && key.modifiers & compared == modifiers & compared
+    })
+}
+
+/// Registra a configuração carregada para acesso global.
+pub fn init(config: Config) {
+    let _ = CURRENT.set(config);
+}
+
+/// Configuração atual (padrão enquanto `init` não for chamado, ex.: em testes).
+pub fn current() -> &'static Config {
+    CURRENT.get_or_init(Config::default)
+}
+
+/// Arquivo do projeto, procurado no diretório atual.
+pub const LOCAL_CONFIG_FILE: &str = "shellrelay.toml";
+
+/// Chaves que o `./shellrelay.toml` não pode definir: o arquivo pode vir de um
+/// checkout qualquer, e estas leem/sobrescrevem arquivos ou mandam o chat para
+/// outro servidor. Valem só na config do usuário, no env e na CLI.
+pub const LOCAL_CONFIG_IGNORED_KEYS: &[(&str, &str)] = &[
+    ("ai", "host"),
+    ("ai", "port"),
+    ("ai", "openai_url"),
+    ("ai", "personas"),
+    ("ai", "system_prompt_file"),
+    ("ai", "memory_file"),
+    ("ui", "filter_file"),
+    ("ui", "export_json"),
+    ("debug", "event_log"),
+];
+
+/// Arquivo de configuração lido no startup.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigSource {
+    pub path: PathBuf,
+    /// `false` no `./shellrelay.toml`: as chaves de `LOCAL_CONFIG_IGNORED_KEYS` são descartadas.
+    pub trusted: bool,
+}
+
+/// Arquivos lidos, do mais fraco ao mais forte: a config do usuário
+/// (`SHELLRELAY_CONFIG` ou `~/.config/shellrelay/config.toml`) e, por cima,
+/// o `./shellrelay.toml`, se existir.
+pub fn config_sources(
+    lookup: impl Fn(&str) -> Option<String>,
+    local_file_exists: bool,
+) -> Vec<ConfigSource> {
+    let user = lookup("SHELLRELAY_CONFIG").map(PathBuf::from).or_else(|| {
+        let base = lookup("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| lookup("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(base.join("shellrelay").join("config.toml"))
+    });
+    let local = local_file_exists.then(|| PathBuf::from(LOCAL_CONFIG_FILE));
+    let trusted = user.map(|path| ConfigSource {
+        path,
+        trusted: true,
+    });
+    let untrusted = local.map(|path| ConfigSource {
+        path,
+        trusted: false,
+    });
+    trusted.into_iter().chain(untrusted).collect()
+}
+
+/// Interpreta um arquivo de configuração e confere os tipos dele sozinho (o erro
+/// aponta o arquivo certo); sem confiança, tira as chaves de `LOCAL_CONFIG_IGNORED_KEYS`.
+pub fn parse_layer(raw: &str, trusted: bool) -> Result<toml::Table, String> {
+    let mut table: toml::Table = raw.parse().map_err(|e: toml::de::Error| e.to_string())?;
+    toml::Value::Table(table.clone())
+        .try_into::<Config>()
+        .map_err(|e| e.to_string())?;
+    if !trusted {
+        for (section, key) in LOCAL_CONFIG_IGNORED_KEYS {
+            if let Some(toml::Value::Table(section)) = table.get_mut(*section) {
+                section.remove(*key);
+            }
+        }
+    }
+    Ok(table)
+}
+
+/// Copia `overlay` sobre `base`; subtabelas são juntadas campo a campo.
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => {
+                merge_tables(base, overlay);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/config_tests.rs"]
+mod tests;
@@ -0,0 +1,199 @@
+use std::{
+    error::Error,
+    fmt,
+    sync::mpsc::{self, Receiver, SendError},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Tempo padrão de espera pela conexão com o SpacetimeDB.
+pub const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+
+/// Falhas ao estabelecer conexão com o backend.
+#[derive(Debug)]
+pub enum ConnectError {
+    /// O servidor não respondeu dentro do prazo configurado.
+    Timeout { uri: String, timeout: Duration },
+    /// O builder retornou erro antes do prazo.
+    Failed { uri: String, reason: String },
+    /// Endereço sem esquema suportado ou sem host.
+    InvalidUri { uri: String },
+    /// Nome de banco fora do formato aceito pelo SpacetimeDB.
+    InvalidModule { module: String },
+}
+
+impl fmt::Display for ConnectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timeout { uri, timeout } => write!(
+                f,
+                "could not connect to {uri} within {}s",
+                timeout.as_secs()
+            ),
+            Self::Failed { uri, reason } => write!(f, "could not connect to {uri}: {reason}"),
+            Self::InvalidUri { uri } => write!(
+                f,
+                "invalid server uri {uri} (expected http://, https://, ws:// or wss://)"
+            ),
+            Self::InvalidModule { module } => write!(
+                f,
+                "invalid module name {module} (use lowercase letters, digits, - and _)"
+            ),
+        }
+    }
+}
+
+impl Error for ConnectError {}
+
+/// Espera antes da primeira tentativa de reconexão; dobra a cada falha.
+pub const RECONNECT_BASE_DELAY: Duration = Duration::from_secs(2);
+/// Teto da espera entre tentativas de reconexão.
+pub const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// Agenda de tentativas de reconexão com espera exponencial.
+#[derive(Clone, Debug)]
+pub struct ReconnectBackoff {
+    failures: u32,
+    next_attempt: Instant,
+}
+
+impl ReconnectBackoff {
+    /// Queda vista em `now`: a primeira tentativa sai após `RECONNECT_BASE_DELAY`.
+    pub fn new(now: Instant) -> Self {
+        Self {
+            failures: 0,
+            next_attempt: now + RECONNECT_BASE_DELAY,
+        }
+    }
+
+    /// Tentativas que já falharam.
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    /// Espera até a próxima tentativa, a partir de `now`.
+    pub fn remaining(&self, now: Instant) -> Duration {
+        self.next_attempt.saturating_duration_since(now)
+    }
+
+    /// Registra uma tentativa falha e adia a próxima.
+    pub fn record_failure(&mut self, now: Instant) {
+        self.failures = self.failures.saturating_add(1);
+        self.next_attempt = now + reconnect_delay(self.failures);
+    }
+}
+
+/// Espera após `failures` tentativas falhas: 2s, 4s, 8s... até `RECONNECT_MAX_DELAY`.
+pub fn reconnect_delay(failures: u32) -> Duration {
+    RECONNECT_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(failures))
+        .min(RECONNECT_MAX_DELAY)
+}
+
+/// O que fazer com uma conexão supervisionada neste tick.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReconnectAction {
+    /// Conectada, ou esperando a tentativa em andamento/o fim da espera.
+    Wait,
+    /// Acabou de cair: começa a agenda de reconexão.
+    Schedule,
+    /// Hora de uma nova tentativa.
+    Attempt,
+}
+
+/// Decide o passo da reconexão de uma conexão (`dropped`) com sua agenda atual.
+pub fn reconnect_action(
+    dropped: bool,
+    backoff: Option<&ReconnectBackoff>,
+    attempt_running: bool,
+    now: Instant,
+) -> ReconnectAction {
+    match backoff {
+        _ if !dropped || attempt_running => ReconnectAction::Wait,
+        None => ReconnectAction::Schedule,
+        Some(backoff) if backoff.remaining(now).is_zero() => ReconnectAction::Attempt,
+        Some(_) => ReconnectAction::Wait,
+    }
+}
+
+/// Esquemas aceitos no endereço do servidor.
+const URI_SCHEMES: &[&str] = &["http://", "https://", "ws://", "wss://"];
+
+/// Confere endereço e nome do banco antes de tentar conectar.
+pub fn validate_target(uri: &str, module: &str) -> Result<(), ConnectError> {
+    let host = URI_SCHEMES
+        .iter()
+        .find_map(|scheme| uri.strip_prefix(scheme))
+        .map(|rest| rest.split('/').next().unwrap_or(""));
+    if !host.is_some_and(|host| !host.is_empty() && !host.contains(char::is_whitespace)) {
+        return Err(ConnectError::InvalidUri {
+            uri: uri.to_string(),
+        });
+    }
+    let valid_module = !module.is_empty()
+        && module
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if !valid_module {
+        return Err(ConnectError::InvalidModule {
+            module: module.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Executa `connect` em uma thread auxiliar e desiste após `timeout`.
+///
+/// Se o prazo estourar, a thread continua em segundo plano e o resultado
+/// tardio é descartado.
+pub fn connect_with_timeout<T, E, F>(
+    uri: &str,
+    timeout: Duration,
+    connect: F,
+) -> Result<T, ConnectError>
+where
+    T: Send + 'static,
+    E: fmt::Display + Send + 'static,
+    F: FnOnce() -> Result<T, E> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = tx.send(connect());
+    });
+
+    match rx.recv_timeout(timeout) {
+        Ok(Ok(conn)) => Ok(conn),
+        Ok(Err(err)) => Err(ConnectError::Failed {
+            uri: uri.to_string(),
+            reason: err.to_string(),
+        }),
+        Err(_) => Err(ConnectError::Timeout {
+            uri: uri.to_string(),
+            timeout,
+        }),
+    }
+}
+
+/// Roda uma tentativa de reconexão numa thread; o resultado chega pelo canal devolvido.
+///
+/// O canal não guarda o resultado: a thread espera alguém recebê-lo. Se o
+/// receptor for descartado antes (sessão trocada, app encerrando), a conexão
+/// aberta vai para `discard` em vez de continuar viva em segundo plano.
+pub fn spawn_connect_attempt<T, C, D>(connect: C, discard: D) -> Receiver<Result<T, ConnectError>>
+where
+    T: Send + 'static,
+    C: FnOnce() -> Result<T, ConnectError> + Send + 'static,
+    D: FnOnce(T) + Send + 'static,
+{
+    let (tx, rx) = mpsc::sync_channel(0);
+    thread::spawn(move || {
+        if let Err(SendError(Ok(stale))) = tx.send(connect()) {
+            discard(stale);
+        }
+    });
+    rx
+}
+
+#[cfg(test)]
+#[path = "tests/connection_tests.rs"]
+mod tests;
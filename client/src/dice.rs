@@ -0,0 +1,93 @@
+// Rolagem de dados do comando `/roll NdM` (tema RPG dos bots).
+//
+// A rolagem é feita localmente e o resultado vai para o chat como mensagem comum.
+
+use std::fmt;
+
+use rand::{Rng, RngExt};
+
+/// Limite de dados por rolagem (evita mensagens gigantes).
+pub const MAX_DICE: u32 = 20;
+/// Maior dado aceito (`d1000`).
+pub const MAX_SIDES: u32 = 1000;
+
+/// Notação `NdM` já validada: `count` dados de `sides` faces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiceSpec {
+    pub count: u32,
+    pub sides: u32,
+}
+
+/// Motivo de uma notação de dados recusada.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DiceError {
+    Invalid(String),
+    TooManyDice,
+    BadSides,
+}
+
+impl fmt::Display for DiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(spec) => write!(f, "invalid dice '{spec}', use NdM (e.g. 2d6)"),
+            Self::TooManyDice => write!(f, "roll between 1 and {MAX_DICE} dice"),
+            Self::BadSides => write!(f, "dice need between 2 and {MAX_SIDES} sides"),
+        }
+    }
+}
+
+impl fmt::Display for DiceSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}d{}", self.count, self.sides)
+    }
+}
+
+/// Interpreta `NdM` (`2d6`, `d20`, `3D8`); a quantidade omitida vale 1.
+pub fn parse_dice(raw: &str) -> Result<DiceSpec, DiceError> {
+    let spec = raw.trim();
+    let invalid = || DiceError::Invalid(spec.to_string());
+    let (count, sides) = spec
+        .to_ascii_lowercase()
+        .split_once('d')
+        .map(|(c, s)| (c.to_string(), s.to_string()))
+        .ok_or_else(invalid)?;
+    let is_number = |text: &str| !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit());
+    if !(count.is_empty() || is_number(&count)) || !is_number(&sides) {
+        return Err(invalid());
+    }
+
+    let count = if count.is_empty() {
+        1
+    } else {
+        count.parse::<u32>().map_err(|_| DiceError::TooManyDice)?
+    };
+    let sides = sides.parse::<u32>().map_err(|_| DiceError::BadSides)?;
+    if count == 0 || count > MAX_DICE {
+        return Err(DiceError::TooManyDice);
+    }
+    if !(2..=MAX_SIDES).contains(&sides) {
+        return Err(DiceError::BadSides);
+    }
+    Ok(DiceSpec { count, sides })
+}
+
+/// Rola os dados; recebe o gerador explicitamente para permitir seed fixa em testes.
+pub fn roll_dice<R: Rng + ?Sized>(spec: DiceSpec, rng: &mut R) -> Vec<u32> {
+    (0..spec.count)
+        .map(|_| rng.random_range(1..=spec.sides))
+        .collect()
+}
+
+/// Mensagem enviada ao chat: `🎲 Rafael rolled 2d6: 4+3 = 7`.
+pub fn format_roll(name: &str, spec: DiceSpec, rolls: &[u32]) -> String {
+    let total: u32 = rolls.iter().sum();
+    if rolls.len() == 1 {
+        return format!("🎲 {name} rolled {spec}: {total}");
+    }
+    let parts: Vec<String> = rolls.iter().map(u32::to_string).collect();
+    format!("🎲 {name} rolled {spec}: {} = {total}", parts.join("+"))
+}
+
+#[cfg(test)]
+#[path = "tests/dice_tests.rs"]
+mod tests;
@@ -0,0 +1,72 @@
+// Log de eventos de sincronização para depurar bugs de sync.
+//
+// Cada callback de tabela vira uma linha com horário, tabela, operação, linha
+// afetada e o tamanho do estado resultante. Ativado por `--debug`,
+// `SHELLRELAY_EVENT_LOG` ou `[debug] event_log` na config.
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::state::AppState;
+
+/// Destino das linhas do log (arquivo em uso real, buffer em testes).
+pub struct EventLog {
+    sink: Mutex<Box<dyn Write + Send>>,
+}
+
+impl EventLog {
+    /// Abre (ou cria) o arquivo em modo append.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file: File = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::with_sink(file))
+    }
+
+    pub fn with_sink(sink: impl Write + Send + 'static) -> Self {
+        Self {
+            sink: Mutex::new(Box::new(sink)),
+        }
+    }
+
+    /// Registra um callback de tabela; falhas de escrita são ignoradas para não derrubar a UI.
+    pub fn record(&self, table: &str, op: &str, row: &str, state: &AppState) {
+        let line = format_entry(now_micros(), table, op, row, state);
+        if let Ok(mut sink) = self.sink.lock() {
+            let _ = writeln!(sink, "{line}");
+            let _ = sink.flush();
+        }
+    }
+}
+
+/// Linha separada por tabs: `micros  tabela  operação  linha  tamanhos`.
+pub(crate) fn format_entry(
+    micros: u128,
+    table: &str,
+    op: &str,
+    row: &str,
+    state: &AppState,
+) -> String {
+    format!(
+        "{micros}\t{table}\t{op}\t{row}\tmessages={} users={} direct={} system={} pending={}",
+        state.ui.messages.len(),
+        state.ui.users.len(),
+        state.ui.direct_messages.len(),
+        state.ui.system_messages.len(),
+        state.ui.pending_messages.len(),
+    )
+}
+
+fn now_micros() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_micros())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+#[path = "tests/event_log_tests.rs"]
+mod tests;
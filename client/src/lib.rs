@@ -1,4 +1,9 @@
+pub mod activity;
 pub mod ai;
+pub mod config;
+pub mod connection;
+pub mod dice;
+pub mod event_log;
 pub mod module_bindings;
 pub mod state;
 pub mod sync;
@@ -4,6 +4,8 @@
 #![allow(unused, clippy::all)]
 use spacetimedb_sdk::__codegen::{self as __sdk, __lib, __sats, __ws};
 
+use super::message_kind_type::MessageKind;
+
 #[derive(__lib::ser::Serialize, __lib::de::Deserialize, Clone, PartialEq, Debug)]
 #[sats(crate = __lib)]
 pub struct Message {
@@ -11,6 +13,8 @@ pub struct Message {
     pub sender: __sdk::Identity,
     pub text: String,
     pub sent_at: __sdk::Timestamp,
+    pub kind: MessageKind,
+    pub edited_at: Option<__sdk::Timestamp>,
 }
 
 impl __sdk::InModule for Message {
@@ -25,6 +29,8 @@ pub struct MessageCols {
     pub sender: __sdk::__query_builder::Col<Message, __sdk::Identity>,
     pub text: __sdk::__query_builder::Col<Message, String>,
     pub sent_at: __sdk::__query_builder::Col<Message, __sdk::Timestamp>,
+    pub kind: __sdk::__query_builder::Col<Message, MessageKind>,
+    pub edited_at: __sdk::__query_builder::Col<Message, Option<__sdk::Timestamp>>,
 }
 
 impl __sdk::__query_builder::HasCols for Message {
@@ -35,6 +41,8 @@ impl __sdk::__query_builder::HasCols for Message {
             sender: __sdk::__query_builder::Col::new(table_name, "sender"),
             text: __sdk::__query_builder::Col::new(table_name, "text"),
             sent_at: __sdk::__query_builder::Col::new(table_name, "sent_at"),
+            kind: __sdk::__query_builder::Col::new(table_name, "kind"),
+            edited_at: __sdk::__query_builder::Col::new(table_name, "edited_at"),
         }
     }
 }
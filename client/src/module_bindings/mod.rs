@@ -6,25 +6,63 @@
 #![allow(unused, clippy::all)]
 use spacetimedb_sdk::__codegen::{self as __sdk, __lib, __sats, __ws};
 
+pub mod announce_reducer;
+pub mod delete_account_reducer;
+pub mod delete_message_reducer;
+pub mod direct_message_table;
+pub mod direct_message_type;
+pub mod edit_message_reducer;
 pub mod identity_connected_reducer;
 pub mod identity_disconnected_reducer;
+pub mod message_kind_type;
 pub mod message_table;
 pub mod message_type;
+pub mod reaction_table;
+pub mod reaction_type;
+pub mod send_direct_reducer;
 pub mod send_message_reducer;
 pub mod set_name_reducer;
+pub mod set_status_reducer;
+pub mod set_title_reducer;
+pub mod set_typing_reducer;
+pub mod toggle_reaction_reducer;
+pub mod typing_table;
+pub mod typing_type;
 pub mod user_table;
 pub mod user_type;
 
+pub use announce_reducer::{AnnounceCallbackId, announce, set_flags_for_announce};
+pub use delete_account_reducer::{
+    DeleteAccountCallbackId, delete_account, set_flags_for_delete_account,
+};
+pub use delete_message_reducer::{
+    DeleteMessageCallbackId, delete_message, set_flags_for_delete_message,
+};
+pub use direct_message_table::*;
+pub use direct_message_type::DirectMessage;
+pub use edit_message_reducer::{EditMessageCallbackId, edit_message, set_flags_for_edit_message};
 pub use identity_connected_reducer::{
     IdentityConnectedCallbackId, identity_connected, set_flags_for_identity_connected,
 };
 pub use identity_disconnected_reducer::{
     IdentityDisconnectedCallbackId, identity_disconnected, set_flags_for_identity_disconnected,
 };
+pub use message_kind_type::MessageKind;
 pub use message_table::*;
 pub use message_type::Message;
+pub use reaction_table::*;
+pub use reaction_type::Reaction;
+pub use send_direct_reducer::{SendDirectCallbackId, send_direct, set_flags_for_send_direct};
 pub use send_message_reducer::{SendMessageCallbackId, send_message, set_flags_for_send_message};
 pub use set_name_reducer::{SetNameCallbackId, set_flags_for_set_name, set_name};
+pub use set_status_reducer::{SetStatusCallbackId, set_flags_for_set_status, set_status};
+pub use set_title_reducer::{SetTitleCallbackId, set_flags_for_set_title, set_title};
+pub use set_typing_reducer::{SetTypingCallbackId, set_flags_for_set_typing, set_typing};
+pub use toggle_reaction_reducer::{
+    ToggleReactionCallbackId, set_flags_for_toggle_reaction, toggle_reaction,
+};
+pub use typing_table::*;
+pub use typing_type::Typing;
 pub use user_table::*;
 pub use user_type::User;
 
@@ -36,10 +74,43 @@ pub use user_type::User;
 /// to indicate which reducer caused the event.
 
 pub enum Reducer {
+    SetTitle {
+        target: __sdk::Identity,
+        title: String,
+    },
+    SetStatus {
+        text: String,
+    },
+    SetTyping {
+        is_typing: bool,
+    },
+    ToggleReaction {
+        message_id: u64,
+        emoji: String,
+    },
+    SendDirect {
+        recipient: __sdk::Identity,
+        text: String,
+    },
+    EditMessage {
+        id: u64,
+        new_text: String,
+    },
+    DeleteMessage {
+        id: u64,
+    },
+    DeleteAccount,
+    Announce {
+        text: String,
+    },
     IdentityConnected,
     IdentityDisconnected,
-    SendMessage { text: String },
-    SetName { new_name: String },
+    SendMessage {
+        text: String,
+    },
+    SetName {
+        new_name: String,
+    },
 }
 
 impl __sdk::InModule for Reducer {
@@ -49,6 +120,15 @@ impl __sdk::InModule for Reducer {
 impl __sdk::Reducer for Reducer {
     fn reducer_name(&self) -> &'static str {
         match self {
+            Reducer::SetTitle { .. } => "set_title",
+            Reducer::SetStatus { .. } => "set_status",
+            Reducer::SetTyping { .. } => "set_typing",
+            Reducer::ToggleReaction { .. } => "toggle_reaction",
+            Reducer::SendDirect { .. } => "send_direct",
+            Reducer::EditMessage { .. } => "edit_message",
+            Reducer::DeleteMessage { .. } => "delete_message",
+            Reducer::DeleteAccount => "delete_account",
+            Reducer::Announce { .. } => "announce",
             Reducer::IdentityConnected => "identity_connected",
             Reducer::IdentityDisconnected => "identity_disconnected",
             Reducer::SendMessage { .. } => "send_message",
@@ -61,6 +141,58 @@ impl TryFrom<__ws::ReducerCallInfo<__ws::BsatnFormat>> for Reducer {
     type Error = __sdk::Error;
     fn try_from(value: __ws::ReducerCallInfo<__ws::BsatnFormat>) -> __sdk::Result<Self> {
         match &value.reducer_name[..] {
+            "set_title" => Ok(
+                __sdk::parse_reducer_args::<set_title_reducer::SetTitleArgs>(
+                    "set_title",
+                    &value.args,
+                )?
+                .into(),
+            ),
+            "set_status" => Ok(
+                __sdk::parse_reducer_args::<set_status_reducer::SetStatusArgs>(
+                    "set_status",
+                    &value.args,
+                )?
+                .into(),
+            ),
+            "set_typing" => Ok(
+                __sdk::parse_reducer_args::<set_typing_reducer::SetTypingArgs>(
+                    "set_typing",
+                    &value.args,
+                )?
+                .into(),
+            ),
+            "toggle_reaction" => Ok(__sdk::parse_reducer_args::<
+                toggle_reaction_reducer::ToggleReactionArgs,
+            >("toggle_reaction", &value.args)?
+            .into()),
+            "send_direct" => Ok(
+                __sdk::parse_reducer_args::<send_direct_reducer::SendDirectArgs>(
+                    "send_direct",
+                    &value.args,
+                )?
+                .into(),
+            ),
+            "edit_message" => Ok(
+                __sdk::parse_reducer_args::<edit_message_reducer::EditMessageArgs>(
+                    "edit_message",
+                    &value.args,
+                )?
+                .into(),
+            ),
+            "delete_message" => Ok(__sdk::parse_reducer_args::<
+                delete_message_reducer::DeleteMessageArgs,
+            >("delete_message", &value.args)?
+            .into()),
+            "delete_account" => Ok(__sdk::parse_reducer_args::<
+                delete_account_reducer::DeleteAccountArgs,
+            >("delete_account", &value.args)?
+            .into()),
+            "announce" => Ok(__sdk::parse_reducer_args::<announce_reducer::AnnounceArgs>(
+                "announce",
+                &value.args,
+            )?
+            .into()),
             "identity_connected" => Ok(__sdk::parse_reducer_args::<
                 identity_connected_reducer::IdentityConnectedArgs,
             >("identity_connected", &value.args)?
@@ -95,7 +227,10 @@ impl TryFrom<__ws::ReducerCallInfo<__ws::BsatnFormat>> for Reducer {
 #[allow(non_snake_case)]
 #[doc(hidden)]
 pub struct DbUpdate {
+    direct_message: __sdk::TableUpdate<DirectMessage>,
     message: __sdk::TableUpdate<Message>,
+    reaction: __sdk::TableUpdate<Reaction>,
+    typing: __sdk::TableUpdate<Typing>,
     user: __sdk::TableUpdate<User>,
 }
 
@@ -105,9 +240,18 @@ impl TryFrom<__ws::DatabaseUpdate<__ws::BsatnFormat>> for DbUpdate {
         let mut db_update = DbUpdate::default();
         for table_update in raw.tables {
             match &table_update.table_name[..] {
+                "direct_message" => db_update
+                    .direct_message
+                    .append(direct_message_table::parse_table_update(table_update)?),
                 "message" => db_update
                     .message
                     .append(message_table::parse_table_update(table_update)?),
+                "reaction" => db_update
+                    .reaction
+                    .append(reaction_table::parse_table_update(table_update)?),
+                "typing" => db_update
+                    .typing
+                    .append(typing_table::parse_table_update(table_update)?),
                 "user" => db_update
                     .user
                     .append(user_table::parse_table_update(table_update)?),
@@ -137,9 +281,18 @@ impl __sdk::DbUpdate for DbUpdate {
     ) -> AppliedDiff<'_> {
         let mut diff = AppliedDiff::default();
 
+        diff.direct_message = cache
+            .apply_diff_to_table::<DirectMessage>("direct_message", &self.direct_message)
+            .with_updates_by_pk(|row| &row.id);
         diff.message = cache
             .apply_diff_to_table::<Message>("message", &self.message)
             .with_updates_by_pk(|row| &row.id);
+        diff.reaction = cache
+            .apply_diff_to_table::<Reaction>("reaction", &self.reaction)
+            .with_updates_by_pk(|row| &row.id);
+        diff.typing = cache
+            .apply_diff_to_table::<Typing>("typing", &self.typing)
+            .with_updates_by_pk(|row| &row.identity);
         diff.user = cache
             .apply_diff_to_table::<User>("user", &self.user)
             .with_updates_by_pk(|row| &row.identity);
@@ -152,7 +305,10 @@ impl __sdk::DbUpdate for DbUpdate {
 #[allow(non_snake_case)]
 #[doc(hidden)]
 pub struct AppliedDiff<'r> {
+    direct_message: __sdk::TableAppliedDiff<'r, DirectMessage>,
     message: __sdk::TableAppliedDiff<'r, Message>,
+    reaction: __sdk::TableAppliedDiff<'r, Reaction>,
+    typing: __sdk::TableAppliedDiff<'r, Typing>,
     user: __sdk::TableAppliedDiff<'r, User>,
     __unused: std::marker::PhantomData<&'r ()>,
 }
@@ -167,7 +323,14 @@ impl<'r> __sdk::AppliedDiff<'r> for AppliedDiff<'r> {
         event: &EventContext,
         callbacks: &mut __sdk::DbCallbacks<RemoteModule>,
     ) {
+        callbacks.invoke_table_row_callbacks::<DirectMessage>(
+            "direct_message",
+            &self.direct_message,
+            event,
+        );
         callbacks.invoke_table_row_callbacks::<Message>("message", &self.message, event);
+        callbacks.invoke_table_row_callbacks::<Reaction>("reaction", &self.reaction, event);
+        callbacks.invoke_table_row_callbacks::<Typing>("typing", &self.typing, event);
         callbacks.invoke_table_row_callbacks::<User>("user", &self.user, event);
     }
 }
@@ -889,7 +1052,10 @@ impl __sdk::SpacetimeModule for RemoteModule {
     type QueryBuilder = __sdk::QueryBuilder;
 
     fn register_tables(client_cache: &mut __sdk::ClientCache<Self>) {
+        direct_message_table::register_table(client_cache);
         message_table::register_table(client_cache);
+        reaction_table::register_table(client_cache);
+        typing_table::register_table(client_cache);
         user_table::register_table(client_cache);
     }
 }
@@ -10,6 +10,9 @@ pub struct User {
     pub identity: __sdk::Identity,
     pub name: String,
     pub online: bool,
+    pub last_sent: __sdk::Timestamp,
+    pub status: String,
+    pub title: String,
 }
 
 impl __sdk::InModule for User {
@@ -23,6 +26,9 @@ pub struct UserCols {
     pub identity: __sdk::__query_builder::Col<User, __sdk::Identity>,
     pub name: __sdk::__query_builder::Col<User, String>,
     pub online: __sdk::__query_builder::Col<User, bool>,
+    pub last_sent: __sdk::__query_builder::Col<User, __sdk::Timestamp>,
+    pub status: __sdk::__query_builder::Col<User, String>,
+    pub title: __sdk::__query_builder::Col<User, String>,
 }
 
 impl __sdk::__query_builder::HasCols for User {
@@ -32,6 +38,9 @@ impl __sdk::__query_builder::HasCols for User {
             identity: __sdk::__query_builder::Col::new(table_name, "identity"),
             name: __sdk::__query_builder::Col::new(table_name, "name"),
             online: __sdk::__query_builder::Col::new(table_name, "online"),
+            last_sent: __sdk::__query_builder::Col::new(table_name, "last_sent"),
+            status: __sdk::__query_builder::Col::new(table_name, "status"),
+            title: __sdk::__query_builder::Col::new(table_name, "title"),
         }
     }
 }
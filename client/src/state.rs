@@ -3,17 +3,22 @@ use std::{
     sync::{Arc, Mutex},
 };
 
-use crate::ui::ui_state::UiState;
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+use crate::ui::ui_state::{UiMessage, UiUser};
+use crate::{activity::ActivityHistory, ai::BotReplyMode, ui::ui_state::UiState};
 
 /// Papel de cada item salvo no histórico local da IA.
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum AiRole {
     User,
     Assistant,
 }
 
 /// Entrada de histórico enviada ao modelo para manter continuidade.
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AiHistoryEntry {
     pub role: AiRole,
     pub content: String,
@@ -32,6 +37,12 @@ pub struct AppState {
     pub status: bool,
     /// Histórico curto de contexto por bot de IA.
     pub ai_histories: HashMap<String, Vec<AiHistoryEntry>>,
+    /// Quando os bots podem responder (ambiente ou só quando chamados).
+    pub bot_reply_mode: BotReplyMode,
+    /// Temperatura atual ("humor") de cada bot, em passeio aleatório limitado.
+    pub ai_moods: HashMap<String, f32>,
+    /// Mensagens por segundo no último minuto (sparkline do cabeçalho).
+    pub activity: ActivityHistory,
 }
 
 /// Tipo utilitário para compartilhar `AppState` entre threads.
@@ -48,3 +59,53 @@ pub fn update_state(state: &SharedState, f: impl FnOnce(&mut AppState)) {
         f(&mut s);
     }
 }
+
+/// Monta `AppState` com usuários e mensagens semeados para testes.
+#[cfg(test)]
+#[derive(Default)]
+pub(crate) struct AppStateBuilder {
+    state: AppState,
+}
+
+#[cfg(test)]
+impl AppStateBuilder {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Identity da conexão principal (`my_identity`).
+    pub(crate) fn with_identity(mut self, identity: &str) -> Self {
+        self.state.my_identity = Some(identity.to_string());
+        self
+    }
+
+    pub(crate) fn with_user(mut self, identity: &str, name: &str, online: bool) -> Self {
+        self.state.ui.users.push(UiUser {
+            identity: identity.to_string(),
+            name: name.to_string(),
+            online,
+            ..Default::default()
+        });
+        self.state.ui.users_version += 1;
+        self
+    }
+
+    /// Mensagem simples; para anúncios/pendentes use `with_ui_message`.
+    pub(crate) fn with_message(self, id: u64, sender: &str, text: &str) -> Self {
+        self.with_ui_message(UiMessage {
+            id,
+            sender: sender.to_string(),
+            text: text.to_string(),
+            ..Default::default()
+        })
+    }
+
+    pub(crate) fn with_ui_message(mut self, message: UiMessage) -> Self {
+        self.state.ui.messages.push(message);
+        self
+    }
+
+    pub(crate) fn build(self) -> AppState {
+        self.state
+    }
+}
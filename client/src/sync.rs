@@ -1,17 +1,78 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Instant,
+};
 
-use spacetimedb_sdk::{Table, TableWithPrimaryKey};
+use spacetimedb_sdk::{Status, Table, TableWithPrimaryKey, Timestamp};
 
 use crate::{
-    module_bindings::{DbConnection, MessageTableAccess, RemoteTables, UserTableAccess},
+    event_log::EventLog,
+    module_bindings::{
+        DbConnection, DirectMessageTableAccess, MessageKind, MessageTableAccess,
+        ReactionTableAccess, ReducerEventContext, RemoteTables, TypingTableAccess, UserTableAccess,
+        announce as AnnounceReducerExt, delete_account as DeleteAccountReducerExt,
+        delete_message as DeleteMessageReducerExt, edit_message as EditMessageReducerExt,
+        send_direct as SendDirectReducerExt, send_message as SendMessageReducerExt,
+        set_name as SetNameReducerExt, set_status as SetStatusReducerExt,
+        set_title as SetTitleReducerExt, toggle_reaction as ToggleReactionReducerExt,
+    },
     state::{AppState, SharedState, update_state},
-    ui::ui_state::{UiMessage, UiUser},
+    ui::ui_state::{
+        PendingMessage, UiDirectMessage, UiMessage, UiPopup, UiScreen, UiTyping, UiUser, sort_users,
+    },
 };
 
 // IDs de mensagens locais começam em uma faixa alta para nunca colidir
 // com os IDs autoincrementais vindos do backend.
 pub(crate) const SYSTEM_MESSAGE_ID_BASE: u64 = 1_000_000_000_000_000_000;
 pub(crate) const MAX_SYSTEM_MESSAGES: usize = 200;
+/// Acima desta quantidade de eventos de presença em um único sync, as linhas
+/// individuais viram um resumo (ex.: reconexão em massa).
+pub(crate) const PRESENCE_BURST_THRESHOLD: usize = 8;
+/// Janela (em microssegundos) para casar o eco otimista com a linha do servidor.
+pub(crate) const OPTIMISTIC_ECHO_WINDOW_MICROS: i64 = 30_000_000;
+
+/// Linha confirmada pelo servidor, no formato usado para reconciliar ecos.
+pub(crate) struct ConfirmedMessage<'a> {
+    pub(crate) id: u64,
+    pub(crate) sender: &'a str,
+    pub(crate) text: &'a str,
+    pub(crate) sent_at_micros: i64,
+}
+
+/// Ids das mensagens do servidor que o loop do app já processou.
+///
+/// Os ids não chegam necessariamente em ordem nem sem lacunas (auto-inc, retenção,
+/// linhas atrasadas), então guardar só o maior id visto perderia mensagens de id menor.
+#[derive(Default)]
+pub(crate) struct SeenMessages {
+    ids: HashSet<u64>,
+    initialized: bool,
+}
+
+impl SeenMessages {
+    /// Compara `current` com o que já foi visto e retorna os ids inéditos em ordem
+    /// crescente; a primeira chamada (ou após `reset`) só registra o histórico e retorna `None`.
+    ///
+    /// Ids que sumiram do servidor saem do conjunto, que acompanha o tamanho do chat.
+    pub(crate) fn take_new(&mut self, current: impl IntoIterator<Item = u64>) -> Option<Vec<u64>> {
+        let current: HashSet<u64> = current.into_iter().collect();
+        let new_ids = self.initialized.then(|| {
+            let mut new_ids: Vec<u64> = current.difference(&self.ids).copied().collect();
+            new_ids.sort_unstable();
+            new_ids
+        });
+        self.ids = current;
+        self.initialized = true;
+        new_ids
+    }
+
+    /// Esquece tudo (troca de servidor): a próxima chamada volta a ser o histórico.
+    pub(crate) fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
 
 /// Adiciona mensagem local (ex.: avisos do sistema/erros) e recompõe a lista renderizada.
 pub fn add_local_system_message(
@@ -19,26 +80,172 @@ pub fn add_local_system_message(
     sender: impl Into<String>,
     text: impl Into<String>,
 ) {
+    push_local_system_message(state, sender, text);
+}
+
+/// Como `add_local_system_message`, retornando o id para editar ou remover a mensagem depois.
+pub fn push_local_system_message(
+    state: &SharedState,
+    sender: impl Into<String>,
+    text: impl Into<String>,
+) -> u64 {
     let sender = sender.into();
     let text = text.into();
+    let mut id = 0;
     update_state(state, |s| {
-        let id = SYSTEM_MESSAGE_ID_BASE.saturating_add(s.ui.next_system_message_id);
+        id = SYSTEM_MESSAGE_ID_BASE.saturating_add(s.ui.next_system_message_id);
         s.ui.next_system_message_id = s.ui.next_system_message_id.saturating_add(1);
         s.ui.system_messages.push(UiMessage {
             id,
             sender,
             text,
             sent_at: String::new(),
+            ..Default::default()
         });
         if s.ui.system_messages.len() > MAX_SYSTEM_MESSAGES {
             let to_drop = s.ui.system_messages.len() - MAX_SYSTEM_MESSAGES;
             s.ui.system_messages.drain(0..to_drop);
         }
 
+        rebuild_messages_with_system(s);
+    });
+    id
+}
+
+/// Troca o texto de uma mensagem local; no-op se ela já saiu do buffer.
+pub fn update_local_system_message(state: &SharedState, id: u64, text: impl Into<String>) {
+    let text = text.into();
+    update_state(state, |s| {
+        if let Some(message) = s.ui.system_messages.iter_mut().find(|m| m.id == id) {
+            message.text = text;
+            rebuild_messages_with_system(s);
+        }
+    });
+}
+
+/// Remove uma mensagem local (ex.: a prévia parcial de um bot).
+pub fn remove_local_system_message(state: &SharedState, id: u64) {
+    update_state(state, |s| {
+        s.ui.system_messages.retain(|m| m.id != id);
+        rebuild_messages_with_system(s);
+    });
+}
+
+/// Apaga os avisos locais do sistema (`/clear`); mensagens do servidor ficam.
+pub fn clear_local_system_messages(state: &SharedState) {
+    update_state(state, |s| {
+        s.ui.system_messages.clear();
+        rebuild_messages_with_system(s);
+    });
+}
+
+/// Esquece os dados do servidor anterior ao trocar de conexão (`/connect`).
+///
+/// Avisos locais ficam, para o progresso da troca continuar visível.
+pub fn clear_server_state(state: &SharedState) {
+    update_state(state, |s| {
+        s.status = false;
+        s.ai_histories.clear();
+        s.activity = Default::default();
+        s.ui.messages.clear();
+        s.ui.pending_messages.clear();
+        s.ui.messages_scroll = Default::default();
+        s.ui.messages_extra_pages = 0;
+        s.ui.last_read_message_id = 0;
+        s.ui.set_users(Vec::new());
+        s.ui.users_scroll = 0;
+        s.ui.pending_presence.clear();
+        s.ui.users_presence_initialized = false;
+        s.ui.typing.clear();
+        s.ui.typing_sent_at = None;
+        s.ui.direct_messages.clear();
+        s.ui.quoted_message_id = None;
+        s.ui.delete_selection = None;
+        rebuild_messages_with_system(s);
+    });
+}
+
+/// Exibe imediatamente a mensagem enviada (eco otimista) até o servidor confirmar.
+pub fn add_pending_message(
+    state: &SharedState,
+    sender: impl Into<String>,
+    text: impl Into<String>,
+) {
+    let sender = sender.into();
+    let text = text.into();
+    let sent_at_micros = Timestamp::now().to_micros_since_unix_epoch();
+    update_state(state, |s| {
+        let local_id = SYSTEM_MESSAGE_ID_BASE.saturating_add(s.ui.next_system_message_id);
+        s.ui.next_system_message_id = s.ui.next_system_message_id.saturating_add(1);
+        let after_id =
+            s.ui.messages
+                .iter()
+                .filter(|m| m.id < SYSTEM_MESSAGE_ID_BASE)
+                .map(|m| m.id)
+                .max()
+                .unwrap_or(0);
+        s.ui.pending_messages.push(PendingMessage {
+            local_id,
+            sender,
+            text,
+            sent_at_micros,
+            after_id,
+        });
+
         rebuild_messages_with_system(s);
     });
 }
 
+/// Descarta o eco otimista de uma mensagem rejeitada pelo servidor.
+fn drop_pending_message(state: &SharedState, text: &str) {
+    update_state(state, |s| {
+        if let Some(idx) =
+            s.ui.pending_messages
+                .iter()
+                .position(|p| p.text.trim() == text.trim())
+        {
+            s.ui.pending_messages.remove(idx);
+            rebuild_messages_with_system(s);
+        }
+    });
+}
+
+/// Indica se a linha do servidor é a confirmação do eco otimista.
+///
+/// Exige mesmo remetente, mesmo texto (após trim), ID mais novo que o
+/// conhecido no envio e horário dentro da janela de tolerância.
+pub(crate) fn pending_matches(pending: &PendingMessage, row: &ConfirmedMessage<'_>) -> bool {
+    row.id > pending.after_id
+        && pending.sender == row.sender
+        && pending.text.trim() == row.text.trim()
+        && (row.sent_at_micros - pending.sent_at_micros).abs() <= OPTIMISTIC_ECHO_WINDOW_MICROS
+}
+
+/// Remove ecos confirmados (cada linha confirma no máximo um eco) e ecos expirados.
+pub(crate) fn reconcile_pending_messages(
+    pending: &mut Vec<PendingMessage>,
+    confirmed: &[ConfirmedMessage<'_>],
+    now_micros: i64,
+) {
+    for row in confirmed {
+        if let Some(idx) = pending.iter().position(|p| pending_matches(p, row)) {
+            pending.remove(idx);
+        }
+    }
+    pending.retain(|p| now_micros - p.sent_at_micros <= OPTIMISTIC_ECHO_WINDOW_MICROS * 2);
+}
+
+fn pending_as_ui_message(pending: &PendingMessage) -> UiMessage {
+    UiMessage {
+        id: pending.local_id,
+        sender: pending.sender.clone(),
+        text: pending.text.clone(),
+        sent_at: String::new(),
+        is_pending: true,
+        ..Default::default()
+    }
+}
+
 /// Recria `ui.messages` a partir das mensagens remotas + locais preservando ordem.
 fn rebuild_messages_with_system(state: &mut AppState) {
     let mut non_system_messages: Vec<UiMessage> = state
@@ -49,154 +256,462 @@ fn rebuild_messages_with_system(state: &mut AppState) {
         .cloned()
         .collect();
     non_system_messages.extend(state.ui.system_messages.iter().cloned());
+    non_system_messages.extend(state.ui.pending_messages.iter().map(pending_as_ui_message));
     non_system_messages.sort_by_key(|m| m.id);
     state.ui.messages = non_system_messages;
 }
 
 /// Registra callbacks de tabela para manter a UI sincronizada em tempo real.
-pub fn register_table_callbacks(conn: &DbConnection, state: &SharedState) {
-    let s = Arc::clone(state);
-    let _ = conn.db.message().on_insert(move |ctx, _row| {
+///
+/// Com `event_log`, cada callback também é gravado com o estado resultante.
+pub fn register_table_callbacks(
+    conn: &DbConnection,
+    state: &SharedState,
+    event_log: Option<Arc<EventLog>>,
+) {
+    let (s, log) = (Arc::clone(state), event_log.clone());
+    let _ = conn.db.message().on_insert(move |ctx, row| {
         sync_from_tables(&ctx.db, &s);
+        record_table_event(&log, "message", "insert", &format!("id={}", row.id), &s);
     });
 
-    let s = Arc::clone(state);
-    let _ = conn.db.message().on_delete(move |ctx, _row| {
+    let (s, log) = (Arc::clone(state), event_log.clone());
+    let _ = conn.db.message().on_delete(move |ctx, row| {
         sync_from_tables(&ctx.db, &s);
+        record_table_event(&log, "message", "delete", &format!("id={}", row.id), &s);
     });
 
-    let s = Arc::clone(state);
-    let _ = conn.db.message().on_update(move |ctx, _old, _new| {
+    let (s, log) = (Arc::clone(state), event_log.clone());
+    let _ = conn.db.message().on_update(move |ctx, _old, new| {
         sync_from_tables(&ctx.db, &s);
+        record_table_event(&log, "message", "update", &format!("id={}", new.id), &s);
     });
 
-    let s = Arc::clone(state);
-    let _ = conn.db.user().on_insert(move |ctx, _row| {
+    let (s, log) = (Arc::clone(state), event_log.clone());
+    let _ = conn.db.direct_message().on_insert(move |ctx, row| {
         sync_from_tables(&ctx.db, &s);
+        record_table_event(
+            &log,
+            "direct_message",
+            "insert",
+            &format!("id={}", row.id),
+            &s,
+        );
     });
 
-    let s = Arc::clone(state);
-    let _ = conn.db.user().on_delete(move |ctx, _row| {
+    let (s, log) = (Arc::clone(state), event_log.clone());
+    let _ = conn.db.direct_message().on_delete(move |ctx, row| {
         sync_from_tables(&ctx.db, &s);
+        record_table_event(
+            &log,
+            "direct_message",
+            "delete",
+            &format!("id={}", row.id),
+            &s,
+        );
     });
 
-    let s = Arc::clone(state);
-    let _ = conn.db.user().on_update(move |ctx, _old, _new| {
+    let (s, log) = (Arc::clone(state), event_log.clone());
+    let _ = conn.db.reaction().on_insert(move |ctx, row| {
         sync_from_tables(&ctx.db, &s);
+        record_table_event(&log, "reaction", "insert", &format!("id={}", row.id), &s);
+    });
+
+    let (s, log) = (Arc::clone(state), event_log.clone());
+    let _ = conn.db.reaction().on_delete(move |ctx, row| {
+        sync_from_tables(&ctx.db, &s);
+        record_table_event(&log, "reaction", "delete", &format!("id={}", row.id), &s);
+    });
+
+    let (s, log) = (Arc::clone(state), event_log.clone());
+    let _ = conn.db.typing().on_insert(move |ctx, row| {
+        sync_from_tables(&ctx.db, &s);
+        record_table_event(&log, "typing", "insert", &row.identity.to_string(), &s);
+    });
+
+    let (s, log) = (Arc::clone(state), event_log.clone());
+    let _ = conn.db.typing().on_delete(move |ctx, row| {
+        sync_from_tables(&ctx.db, &s);
+        record_table_event(&log, "typing", "delete", &row.identity.to_string(), &s);
+    });
+
+    let (s, log) = (Arc::clone(state), event_log.clone());
+    let _ = conn.db.typing().on_update(move |ctx, _old, new| {
+        sync_from_tables(&ctx.db, &s);
+        record_table_event(&log, "typing", "update", &new.identity.to_string(), &s);
+    });
+
+    let (s, log) = (Arc::clone(state), event_log.clone());
+    let _ = conn.db.user().on_insert(move |ctx, row| {
+        sync_from_tables(&ctx.db, &s);
+        record_table_event(&log, "user", "insert", &row.identity.to_string(), &s);
+    });
+
+    let (s, log) = (Arc::clone(state), event_log.clone());
+    let _ = conn.db.user().on_delete(move |ctx, row| {
+        sync_from_tables(&ctx.db, &s);
+        record_table_event(&log, "user", "delete", &row.identity.to_string(), &s);
+    });
+
+    let (s, log) = (Arc::clone(state), event_log);
+    let _ = conn.db.user().on_update(move |ctx, _old, new| {
+        sync_from_tables(&ctx.db, &s);
+        record_table_event(&log, "user", "update", &new.identity.to_string(), &s);
     });
 }
 
+fn record_table_event(
+    log: &Option<Arc<EventLog>>,
+    table: &str,
+    op: &str,
+    row: &str,
+    state: &SharedState,
+) {
+    let Some(log) = log else {
+        return;
+    };
+    if let Ok(s) = state.lock() {
+        log.record(table, op, row, &s);
+    }
+}
+
+/// Registra callbacks de reducers para avisar o usuário quando o servidor
+/// rejeita uma chamada feita por esta conexão (mensagem vazia, sem nome etc.).
+pub fn register_reducer_callbacks(conn: &DbConnection, state: &SharedState) {
+    let s = Arc::clone(state);
+    let _ = conn.reducers.on_send_message(move |ctx, text| {
+        if !report_reducer_failure(ctx, "send_message", &s) {
+            return;
+        }
+        drop_pending_message(&s, text);
+        // O texto recusado volta para o input em vez de se perder.
+        if let Status::Failed(reason) = &ctx.event.status {
+            let reason = reason.to_string();
+            update_state(&s, |st| st.ui.restore_rejected_send(text, &reason));
+        }
+    });
+
+    let s = Arc::clone(state);
+    let _ = conn.reducers.on_set_name(move |ctx, _name| {
+        if !report_reducer_failure(ctx, "set_name", &s) {
+            return;
+        }
+        // Nome recusado (ex.: já em uso): volta ao popup para escolher outro.
+        if let Status::Failed(reason) = &ctx.event.status {
+            let reason = reason.to_string();
+            update_state(&s, |st| {
+                st.ui.screen = UiScreen::MainMenu;
+                st.ui.popup = Some(UiPopup::ChooseName);
+                st.ui.name_error = Some(reason);
+            });
+        }
+    });
+
+    let s = Arc::clone(state);
+    let _ = conn.reducers.on_edit_message(move |ctx, _id, _new_text| {
+        let _ = report_reducer_failure(ctx, "edit_message", &s);
+    });
+
+    let s = Arc::clone(state);
+    let _ = conn.reducers.on_delete_message(move |ctx, _id| {
+        let _ = report_reducer_failure(ctx, "delete_message", &s);
+    });
+
+    let s = Arc::clone(state);
+    let _ = conn.reducers.on_send_direct(move |ctx, _recipient, _text| {
+        let _ = report_reducer_failure(ctx, "send_direct", &s);
+    });
+
+    let s = Arc::clone(state);
+    let _ = conn
+        .reducers
+        .on_toggle_reaction(move |ctx, _message_id, _emoji| {
+            let _ = report_reducer_failure(ctx, "toggle_reaction", &s);
+        });
+
+    let s = Arc::clone(state);
+    let _ = conn.reducers.on_set_status(move |ctx, _text| {
+        let _ = report_reducer_failure(ctx, "set_status", &s);
+    });
+
+    let s = Arc::clone(state);
+    let _ = conn.reducers.on_set_title(move |ctx, _target, _title| {
+        let _ = report_reducer_failure(ctx, "set_title", &s);
+    });
+
+    let s = Arc::clone(state);
+    let _ = conn.reducers.on_announce(move |ctx, _text| {
+        let _ = report_reducer_failure(ctx, "announce", &s);
+    });
+
+    // Conta apagada: encerra o app, que desconecta ao sair do loop.
+    let s = Arc::clone(state);
+    let _ = conn.reducers.on_delete_account(move |ctx| {
+        if report_reducer_failure(ctx, "delete_account", &s) {
+            return;
+        }
+        if matches!(ctx.event.status, Status::Committed) && is_my_call(ctx, &s) {
+            update_state(&s, |st| st.ui.should_quit = true);
+        }
+    });
+}
+
+/// Retorna `true` quando a chamada desta conexão falhou e o aviso foi exibido.
+fn report_reducer_failure(ctx: &ReducerEventContext, reducer: &str, state: &SharedState) -> bool {
+    let Status::Failed(reason) = &ctx.event.status else {
+        return false;
+    };
+    let is_mine = is_my_call(ctx, state);
+    if is_mine {
+        add_local_system_message(state, "System", reducer_rejection_notice(reducer, reason));
+    }
+    is_mine
+}
+
+/// Verdadeiro quando a chamada do reducer partiu desta conexão.
+fn is_my_call(ctx: &ReducerEventContext, state: &SharedState) -> bool {
+    let caller = ctx.event.caller_identity.to_string();
+    state
+        .lock()
+        .ok()
+        .and_then(|s| s.my_identity.clone())
+        .is_some_and(|me| me == caller)
+}
+
+/// Texto exibido no chat quando um reducer falha no servidor.
+pub(crate) fn reducer_rejection_notice(reducer: &str, reason: &str) -> String {
+    let subject = match reducer {
+        "send_message" => "message",
+        "set_name" => "name",
+        "announce" => "announcement",
+        "delete_account" => "account deletion",
+        "edit_message" => "edit",
+        "delete_message" => "message deletion",
+        "send_direct" => "direct message",
+        "toggle_reaction" => "reaction",
+        "set_status" => "status",
+        "set_title" => "title",
+        other => other,
+    };
+    format!("{subject} rejected: {reason}")
+}
+
+/// Mantém só as mensagens privadas em que `me` participa, em ordem de envio.
+pub(crate) fn visible_direct_messages(
+    mut all: Vec<UiDirectMessage>,
+    me: Option<&str>,
+) -> Vec<UiDirectMessage> {
+    let Some(me) = me else {
+        return Vec::new();
+    };
+    all.retain(|dm| dm.sender == me || dm.recipient == me);
+    all.sort_by_key(|dm| dm.id);
+    all
+}
+
+/// Agrupa reações `(message_id, emoji)` em contagens por mensagem,
+/// mantendo os emojis na ordem em que apareceram pela primeira vez.
+pub(crate) fn aggregate_reactions(
+    mut rows: Vec<(u64, u64, String)>,
+) -> HashMap<u64, Vec<(String, usize)>> {
+    rows.sort_by_key(|(id, _, _)| *id);
+    let mut by_message: HashMap<u64, Vec<(String, usize)>> = HashMap::new();
+    for (_, message_id, emoji) in rows {
+        let counts = by_message.entry(message_id).or_default();
+        match counts.iter_mut().find(|(e, _)| *e == emoji) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((emoji, 1)),
+        }
+    }
+    by_message
+}
+
+/// Linhas de presença de um sync: uma por usuário em volumes normais,
+/// ou um único resumo quando passa de `PRESENCE_BURST_THRESHOLD`.
+pub(crate) fn presence_messages(connected: &[String], disconnected: &[String]) -> Vec<String> {
+    if connected.len() + disconnected.len() <= PRESENCE_BURST_THRESHOLD {
+        return connected
+            .iter()
+            .map(|name| format!("{name} connected"))
+            .chain(
+                disconnected
+                    .iter()
+                    .map(|name| format!("{name} disconnected")),
+            )
+            .collect();
+    }
+
+    let summary: Vec<String> = [
+        (connected.len(), "connected"),
+        (disconnected.len(), "disconnected"),
+    ]
+    .into_iter()
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, verb)| {
+        let noun = if count == 1 { "user" } else { "users" };
+        format!("{count} {noun} {verb}")
+    })
+    .collect();
+    vec![summary.join(", ")]
+}
+
 /// Reconcilia estado local com as tabelas materializadas do SpacetimeDB.
 pub fn sync_from_tables(db: &RemoteTables, state: &SharedState) {
     // Snapshot atual de mensagens remotas.
-    let mut messages: Vec<UiMessage> = db
-        .message()
+    let rows: Vec<_> = db.message().iter().collect();
+    let mut reactions = aggregate_reactions(
+        db.reaction()
+            .iter()
+            .map(|r| (r.id, r.message_id, r.emoji))
+            .collect(),
+    );
+    let mut messages: Vec<UiMessage> = rows
         .iter()
         .map(|m| UiMessage {
             id: m.id,
             sender: m.sender.to_string(),
-            text: m.text,
+            text: m.text.clone(),
             sent_at: m.sent_at.to_string(),
+            is_announcement: m.kind == MessageKind::Announcement,
+            is_pending: false,
+            is_edited: m.edited_at.is_some(),
+            reactions: reactions.remove(&m.id).unwrap_or_default(),
         })
         .collect();
     messages.sort_by_key(|m| m.id);
+    let row_senders: Vec<String> = rows.iter().map(|m| m.sender.to_string()).collect();
+    let confirmed: Vec<ConfirmedMessage<'_>> = rows
+        .iter()
+        .zip(row_senders.iter())
+        .map(|(row, sender)| ConfirmedMessage {
+            id: row.id,
+            sender: sender.as_str(),
+            text: row.text.as_str(),
+            sent_at_micros: row.sent_at.to_micros_since_unix_epoch(),
+        })
+        .collect();
+    let now_micros = Timestamp::now().to_micros_since_unix_epoch();
+
+    let direct_rows: Vec<UiDirectMessage> = db
+        .direct_message()
+        .iter()
+        .map(|dm| UiDirectMessage {
+            id: dm.id,
+            sender: dm.sender.to_string(),
+            recipient: dm.recipient.to_string(),
+            text: dm.text,
+            sent_at: dm.sent_at.to_string(),
+        })
+        .collect();
+
+    let typing: Vec<UiTyping> = db
+        .typing()
+        .iter()
+        .map(|t| UiTyping {
+            identity: t.identity.to_string(),
+            started_at_micros: t.started_at.to_micros_since_unix_epoch(),
+        })
+        .collect();
 
     let mut users: Vec<UiUser> = db
         .user()
         .iter()
         .map(|u| UiUser {
+            name: resolved_user_name(&u.name, &u.identity.to_string()),
             identity: u.identity.to_string(),
-            name: u.name,
             online: u.online,
+            status: u.status,
+            title: u.title,
         })
         .collect();
-    users.sort_by(|a, b| {
-        b.online
-            .cmp(&a.online)
-            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
-    });
 
     update_state(state, |s| {
-        // Detecta transições de presença comparando estado anterior x atual.
-        let previous_users = s.ui.users.clone();
-        let mut presence_events: Vec<String> = Vec::new();
-
-        if s.ui.users_presence_initialized {
-            let previous_online: HashMap<&str, bool> = previous_users
-                .iter()
-                .map(|u| (u.identity.as_str(), u.online))
-                .collect();
-            let current_online: HashMap<&str, bool> = users
-                .iter()
-                .map(|u| (u.identity.as_str(), u.online))
-                .collect();
-
-            for user in &users {
-                let was_online = previous_online
-                    .get(user.identity.as_str())
-                    .copied()
-                    .unwrap_or(false);
-                if user.online && !was_online {
-                    presence_events.push(format!("{} connected", display_user_name(user)));
-                }
-            }
-
-            for user in &previous_users {
-                let is_online = current_online
-                    .get(user.identity.as_str())
-                    .copied()
-                    .unwrap_or(false);
-                if user.online && !is_online {
-                    presence_events.push(format!("{} disconnected", display_user_name(user)));
-                }
-            }
-        }
+        // Quedas curtas seguem online durante a carência (evita piscar na lista)
+        // e entradas só viram aviso depois dela; quem pisca não gera aviso.
+        let (connected, disconnected) = s.ui.apply_offline_grace(&mut users, Instant::now());
+        sort_users(&mut users);
         s.ui.users_presence_initialized = true;
 
         // Converte eventos de presença em mensagens locais do "System".
-        for text in presence_events {
-            let id = SYSTEM_MESSAGE_ID_BASE.saturating_add(s.ui.next_system_message_id);
-            s.ui.next_system_message_id = s.ui.next_system_message_id.saturating_add(1);
-            s.ui.system_messages.push(UiMessage {
-                id,
-                sender: "System".to_string(),
-                text,
-                sent_at: String::new(),
-            });
-        }
-        if s.ui.system_messages.len() > MAX_SYSTEM_MESSAGES {
-            let to_drop = s.ui.system_messages.len() - MAX_SYSTEM_MESSAGES;
-            s.ui.system_messages.drain(0..to_drop);
-        }
+        push_presence_messages(s, &connected, &disconnected);
+
+        // Ecos otimistas confirmados pelo servidor são substituídos pela linha real.
+        reconcile_pending_messages(&mut s.ui.pending_messages, &confirmed, now_micros);
 
         messages.extend(s.ui.system_messages.iter().cloned());
+        messages.extend(s.ui.pending_messages.iter().map(pending_as_ui_message));
         messages.sort_by_key(|m| m.id);
 
         s.ui.messages = messages;
-        s.ui.users = users;
-        s.ui.users_scroll = s.ui.users_scroll.min(s.ui.users.len().saturating_sub(1));
+        s.ui.set_users(users);
+        s.ui.typing = typing;
+        s.ui.direct_messages = visible_direct_messages(direct_rows, s.my_identity.as_deref());
+        let visible_users = s.ui.visible_users().len();
+        s.ui.users_scroll = s.ui.users_scroll.min(visible_users.saturating_sub(1));
     });
 }
 
-/// Exibe nome amigável com fallback para identity curta.
-fn display_user_name(user: &UiUser) -> String {
-    if !user.name.trim().is_empty() {
-        return user.name.clone();
+/// Registra as linhas de presença como mensagens locais do "System".
+fn push_presence_messages(state: &mut AppState, connected: &[UiUser], disconnected: &[UiUser]) {
+    let connected: Vec<String> = connected.iter().map(display_user_name).collect();
+    let disconnected: Vec<String> = disconnected.iter().map(display_user_name).collect();
+    for text in presence_messages(&connected, &disconnected) {
+        let id = SYSTEM_MESSAGE_ID_BASE.saturating_add(state.ui.next_system_message_id);
+        state.ui.next_system_message_id = state.ui.next_system_message_id.saturating_add(1);
+        state.ui.system_messages.push(UiMessage {
+            id,
+            sender: "System".to_string(),
+            text,
+            sent_at: String::new(),
+            ..Default::default()
+        });
     }
+    if state.ui.system_messages.len() > MAX_SYSTEM_MESSAGES {
+        let to_drop = state.ui.system_messages.len() - MAX_SYSTEM_MESSAGES;
+        state.ui.system_messages.drain(0..to_drop);
+    }
+}
+
+/// Chamado a cada tick: quem esgotou a carência passa a aparecer offline e
+/// entradas que passaram dela viram aviso.
+pub fn expire_offline_users(state: &SharedState) {
+    update_state(state, |s| {
+        let (connected, went_offline) = s.ui.expire_offline_grace(Instant::now());
+        if connected.is_empty() && went_offline.is_empty() {
+            return;
+        }
+        push_presence_messages(s, &connected, &went_offline);
+        rebuild_messages_with_system(s);
+        let visible_users = s.ui.visible_users().len();
+        s.ui.users_scroll = s.ui.users_scroll.min(visible_users.saturating_sub(1));
+    });
+}
 
-    short_identity(&user.identity)
+/// Exibe nome amigável com fallback para o apelido anônimo da identity.
+fn display_user_name(user: &UiUser) -> String {
+    resolved_user_name(&user.name, &user.identity)
 }
 
-/// Identity abreviada para layout compacto em terminal.
-fn short_identity(identity: &str) -> String {
-    const MAX: usize = 18;
-    if identity.len() <= MAX {
-        return identity.to_string();
+/// Nome que o servidor dá a quem ainda não passou pelo `set_name`.
+const SERVER_DEFAULT_NAME: &str = "Anônimo";
+
+/// Nome vazio ou o padrão do servidor vira o apelido da identity, para que
+/// anônimos diferentes não apareçam todos com o mesmo nome.
+fn resolved_user_name(name: &str, identity: &str) -> String {
+    match name.trim() {
+        "" | SERVER_DEFAULT_NAME => anonymous_name(identity),
+        _ => name.to_string(),
     }
+}
 
-    let head = &identity[..10.min(identity.len())];
-    let tail = &identity[identity.len().saturating_sub(6)..];
-    format!("{}..{}", head, tail)
+/// Apelido estável de um anônimo ("Anon-3f9a"): hash FNV-1a da identity.
+fn anonymous_name(identity: &str) -> String {
+    let hash = identity
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!("Anon-{:04x}", hash & 0xffff)
 }
 
 #[cfg(test)]
@@ -0,0 +1,38 @@
+use super::{ACTIVITY_WINDOW_SECS, ActivityHistory};
+
+#[test]
+fn empty_history_yields_a_full_window_of_zeros() {
+    let history = ActivityHistory::default();
+    assert_eq!(history.sparkline_data(1_000), vec![0; ACTIVITY_WINDOW_SECS]);
+    // Relógio próximo de zero não pode dar underflow.
+    assert_eq!(history.sparkline_data(3), vec![0; ACTIVITY_WINDOW_SECS]);
+}
+
+#[test]
+fn record_accumulates_per_second_in_time_order() {
+    let mut history = ActivityHistory::default();
+    history.record(1_000, 2);
+    history.record(1_000, 1);
+    history.record(1_002, 4);
+
+    let data = history.sparkline_data(1_002);
+    assert_eq!(data.len(), ACTIVITY_WINDOW_SECS);
+    assert_eq!(&data[ACTIVITY_WINDOW_SECS - 3..], &[3, 0, 4]);
+    assert_eq!(data.iter().sum::<u64>(), 7);
+}
+
+#[test]
+fn old_seconds_leave_the_window_and_do_not_wrap_around() {
+    let mut history = ActivityHistory::default();
+    history.record(1_000, 5);
+
+    // Ainda visível 59s depois, fora da janela a partir de 60s.
+    assert_eq!(history.sparkline_data(1_059)[0], 5);
+    assert_eq!(history.sparkline_data(1_060).iter().sum::<u64>(), 0);
+
+    // Registrar exatamente uma volta depois não soma ao valor antigo.
+    history.record(1_060, 1);
+    let data = history.sparkline_data(1_060);
+    assert_eq!(data.iter().sum::<u64>(), 1);
+    assert_eq!(data[ACTIVITY_WINDOW_SECS - 1], 1);
+}
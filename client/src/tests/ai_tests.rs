@@ -1,9 +1,88 @@
-use crate::ui::ui_state::{UiMessage, UiUser};
+use std::{sync::Arc, time::Instant};
 
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::state::AppStateBuilder;
+use crate::ui::ui_state::{MAX_NAME_LEN, UiDirectMessage, UiMessage, UiUser};
+
+use super::backend::{
+    BackendKind, CompletionRequest, Sampling, StreamLine, openai_request_body, parse_stream_line,
+};
+use super::bots::bot_models;
+use super::config::{
+    AI_FORCED_PROMPT_TTL, AI_MAX_TYPING_DELAY, AI_TYPING_DELAY_PER_CHAR, clamp_bot_count,
+};
+use super::context::{Lang, guess_language};
+use super::dispatch::{
+    BotReplyMode, ForcedPrompts, RoundRobin, capped_dispatch_start, choose_dispatch_target,
+    directed_reply_limit, dispatch_order, forced_responder, is_open_question, mentioned_names,
+};
+use super::hook::{NoopReplyHook, ReplyHook, post_process_reply};
+use super::memory::{BotMemory, parse_memory};
+use super::mood::{
+    MOOD_DEFAULT_TEMPERATURE, MOOD_MAX_TEMPERATURE, MOOD_MIN_TEMPERATURE, next_mood,
+};
+use super::personas::parse_personas;
+use super::pool::WorkerPool;
+use super::schedule::QuietHours;
+
+use super::commands::{BOT_COMMANDS, find_bot_command};
+use super::mood::mood_label;
+use super::text::word_similarity;
 use super::{
-    AppState, MAX_REPLY_CHARS, build_context_system_prompt, build_prompt_context, normalize_reply,
-    short_identity, truncate_for_context,
+    AiBotProfile, AppState, BASE_SYSTEM_PROMPT, BASE_SYSTEM_PROMPT_EN, DEFAULT_MODEL,
+    MAX_HISTORY_ENTRIES, MAX_LORE_TOKENS, MAX_REPLY_CHARS, PREVIEW_DEFAULT_PROMPT,
+    base_system_prompt, bot_command_reply, build_bot_preview, build_chat_messages,
+    build_context_system_prompt, build_lore_system_prompt, build_prompt_context, format_preview,
+    is_echo, normalize_reply, ollama_unavailable_notice, partial_reply_text, remove_last_reply,
+    retry_delay, sampling_options, short_identity, timeout_error_message, trim_history,
+    truncate_for_context, typing_delay_for,
 };
+use crate::state::{AiHistoryEntry, AiRole};
+
+fn history_of(sizes: &[usize]) -> Vec<AiHistoryEntry> {
+    sizes
+        .iter()
+        .enumerate()
+        .map(|(i, size)| AiHistoryEntry {
+            role: if i % 2 == 0 {
+                AiRole::User
+            } else {
+                AiRole::Assistant
+            },
+            content: "x".repeat(*size),
+        })
+        .collect()
+}
+
+#[test]
+fn trim_history_drops_oldest_entries_until_budget_fits() {
+    // Tokens estimados: 50, 3, 25, 2, 10 (4 chars por token, arredondando para cima).
+    let mut history = history_of(&[200, 12, 100, 8, 40]);
+    trim_history(&mut history, 40);
+    let sizes: Vec<usize> = history.iter().map(|e| e.content.len()).collect();
+    assert_eq!(sizes, vec![12, 100, 8, 40]);
+
+    let mut history = history_of(&[200, 12, 100, 8, 40]);
+    trim_history(&mut history, 36);
+    let sizes: Vec<usize> = history.iter().map(|e| e.content.len()).collect();
+    assert_eq!(sizes, vec![8, 40]);
+}
+
+#[test]
+fn trim_history_keeps_latest_entry_even_over_budget() {
+    let mut history = history_of(&[4, 4, 400]);
+    trim_history(&mut history, 10);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].content.len(), 400);
+}
+
+#[test]
+fn trim_history_without_budget_only_caps_entry_count() {
+    let mut history = history_of(&[400; MAX_HISTORY_ENTRIES + 3]);
+    trim_history(&mut history, 0);
+    assert_eq!(history.len(), MAX_HISTORY_ENTRIES);
+}
 
 #[test]
 fn short_identity_returns_original_when_small_and_truncates_when_large() {
@@ -21,6 +100,38 @@ fn truncate_for_context_normalizes_newlines_and_limits_size() {
     assert_eq!(truncate_for_context("abcdefghij", 5), "abcde...");
 }
 
+#[test]
+fn build_prompt_context_never_includes_direct_messages() {
+    let mut state = AppState {
+        my_identity: Some("id_rafael".to_string()),
+        ..Default::default()
+    };
+    state.ui.users = vec![UiUser {
+        identity: "id_rafael".to_string(),
+        name: "Rafael".to_string(),
+        online: true,
+        ..Default::default()
+    }];
+    state.ui.messages = vec![UiMessage {
+        id: 1,
+        sender: "id_rafael".to_string(),
+        text: "bom dia".to_string(),
+        ..Default::default()
+    }];
+    state.ui.direct_messages = vec![UiDirectMessage {
+        id: 1,
+        sender: "id_rafael".to_string(),
+        recipient: "id_lia".to_string(),
+        text: "segredo entre nos".to_string(),
+        ..Default::default()
+    }];
+
+    let ctx = build_prompt_context(&state);
+    assert!(ctx.recent_messages.iter().any(|m| m.contains("bom dia")));
+    assert!(!ctx.recent_messages.iter().any(|m| m.contains("segredo")));
+    assert!(!build_context_system_prompt(&ctx).contains("segredo"));
+}
+
 #[test]
 fn build_prompt_context_uses_requester_name_online_users_and_recent_messages() {
     let mut state = AppState {
@@ -32,16 +143,19 @@ fn build_prompt_context_uses_requester_name_online_users_and_recent_messages() {
             identity: "id_rafael".to_string(),
             name: "Rafael".to_string(),
             online: true,
+            ..Default::default()
         },
         UiUser {
             identity: "id_ai".to_string(),
             name: "Ai".to_string(),
             online: true,
+            ..Default::default()
         },
         UiUser {
             identity: "id_offline".to_string(),
             name: "Offline".to_string(),
             online: false,
+            ..Default::default()
         },
     ];
     state.ui.messages = vec![
@@ -50,12 +164,14 @@ fn build_prompt_context_uses_requester_name_online_users_and_recent_messages() {
             sender: "id_rafael".to_string(),
             text: "Oi".to_string(),
             sent_at: "2026-02-12T13:44:00Z".to_string(),
+            ..Default::default()
         },
         UiMessage {
             id: 2,
             sender: "id_ai".to_string(),
             text: "Ola".to_string(),
             sent_at: "2026-02-12T13:45:00Z".to_string(),
+            ..Default::default()
         },
     ];
 
@@ -72,41 +188,14 @@ fn build_prompt_context_uses_requester_name_online_users_and_recent_messages() {
 
 #[test]
 fn build_prompt_context_ignores_recent_messages_from_offline_users() {
-    let mut state = AppState {
-        my_identity: Some("id_rafael".to_string()),
-        ..Default::default()
-    };
-    state.ui.users = vec![
-        UiUser {
-            identity: "id_rafael".to_string(),
-            name: "Rafael".to_string(),
-            online: true,
-        },
-        UiUser {
-            identity: "id_online".to_string(),
-            name: "Lia".to_string(),
-            online: true,
-        },
-        UiUser {
-            identity: "id_offline".to_string(),
-            name: "Teste".to_string(),
-            online: false,
-        },
-    ];
-    state.ui.messages = vec![
-        UiMessage {
-            id: 1,
-            sender: "id_offline".to_string(),
-            text: "msg antiga".to_string(),
-            sent_at: String::new(),
-        },
-        UiMessage {
-            id: 2,
-            sender: "id_online".to_string(),
-            text: "msg atual".to_string(),
-            sent_at: String::new(),
-        },
-    ];
+    let state = AppStateBuilder::new()
+        .with_identity("id_rafael")
+        .with_user("id_rafael", "Rafael", true)
+        .with_user("id_online", "Lia", true)
+        .with_user("id_offline", "Teste", false)
+        .with_message(1, "id_offline", "msg antiga")
+        .with_message(2, "id_online", "msg atual")
+        .build();
 
     let ctx = build_prompt_context(&state);
     assert_eq!(ctx.recent_messages.len(), 1);
@@ -115,21 +204,11 @@ fn build_prompt_context_ignores_recent_messages_from_offline_users() {
 
 #[test]
 fn context_system_prompt_contains_core_fields() {
-    let mut state = AppState {
-        my_identity: Some("id_rafael".to_string()),
-        ..Default::default()
-    };
-    state.ui.users.push(UiUser {
-        identity: "id_rafael".to_string(),
-        name: "Rafael".to_string(),
-        online: true,
-    });
-    state.ui.messages.push(UiMessage {
-        id: 1,
-        sender: "id_rafael".to_string(),
-        text: "Teste".to_string(),
-        sent_at: String::new(),
-    });
+    let state = AppStateBuilder::new()
+        .with_identity("id_rafael")
+        .with_user("id_rafael", "Rafael", true)
+        .with_message(1, "id_rafael", "Teste")
+        .build();
 
     let ctx = build_prompt_context(&state);
     let prompt = build_context_system_prompt(&ctx);
@@ -152,3 +231,855 @@ fn normalize_reply_truncates_very_long_output() {
     let normalized = normalize_reply(&long);
     assert!(normalized.chars().count() <= MAX_REPLY_CHARS + 3);
 }
+
+#[test]
+fn context_system_prompt_puts_host_announcements_first() {
+    let state = AppStateBuilder::new()
+        .with_identity("id_rafael")
+        .with_ui_message(UiMessage {
+            id: 1,
+            sender: "id_host".to_string(),
+            text: "Evento na taverna hoje".to_string(),
+            is_announcement: true,
+            ..Default::default()
+        })
+        .build();
+
+    let ctx = build_prompt_context(&state);
+    assert_eq!(
+        ctx.announcements,
+        vec!["Evento na taverna hoje".to_string()]
+    );
+    let prompt = build_context_system_prompt(&ctx);
+    assert!(prompt.starts_with("Avisos do host"));
+    assert!(prompt.contains("Evento na taverna hoje"));
+}
+
+#[test]
+fn next_mood_random_walk_stays_within_bounds() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut mood = MOOD_DEFAULT_TEMPERATURE;
+    for _ in 0..10_000 {
+        mood = next_mood(mood, &mut rng);
+        assert!((MOOD_MIN_TEMPERATURE..=MOOD_MAX_TEMPERATURE).contains(&mood));
+    }
+}
+
+#[test]
+fn next_mood_is_reproducible_with_same_seed_and_recovers_from_nan() {
+    let a = next_mood(0.9, &mut StdRng::seed_from_u64(42));
+    let b = next_mood(0.9, &mut StdRng::seed_from_u64(42));
+    assert_eq!(a, b);
+
+    let recovered = next_mood(f32::NAN, &mut StdRng::seed_from_u64(1));
+    assert!((MOOD_MIN_TEMPERATURE..=MOOD_MAX_TEMPERATURE).contains(&recovered));
+}
+
+#[test]
+fn spoken_to_only_mode_dispatches_only_directed_mentions() {
+    let mode = BotReplyMode::SpokenToOnly;
+    assert_eq!(
+        choose_dispatch_target(mode, Some("Kael"), || Some("Lyria")),
+        Some("Kael")
+    );
+    assert_eq!(
+        choose_dispatch_target(mode, None::<&str>, || panic!("ambient must not run")),
+        None
+    );
+    assert!(!mode.allows_proactive_chat());
+}
+
+#[test]
+fn ambient_mode_prefers_directed_and_falls_back_to_random_pick() {
+    let mode = BotReplyMode::Ambient;
+    assert_eq!(
+        choose_dispatch_target(mode, Some("Kael"), || Some("Lyria")),
+        Some("Kael")
+    );
+    assert_eq!(
+        choose_dispatch_target(mode, None, || Some("Lyria")),
+        Some("Lyria")
+    );
+    assert!(mode.allows_proactive_chat());
+}
+
+#[test]
+fn lore_prompt_is_included_only_when_configured() {
+    let prompt = build_lore_system_prompt("A taverna fecha ao nascer do sol.")
+        .expect("configured lore must produce a prompt");
+    assert!(prompt.contains("A taverna fecha ao nascer do sol."));
+    assert_eq!(build_lore_system_prompt(""), None);
+    assert_eq!(build_lore_system_prompt("  \n "), None);
+}
+
+#[test]
+fn lore_prompt_is_truncated_to_token_budget() {
+    let long = "x".repeat(MAX_LORE_TOKENS * 10);
+    let prompt = build_lore_system_prompt(&long).expect("lore");
+    assert!(prompt.chars().filter(|c| *c == 'x').count() <= MAX_LORE_TOKENS * 4);
+}
+
+#[test]
+fn open_question_requires_question_mark_and_no_mention() {
+    assert!(is_open_question("alguem viu o ferreiro? "));
+    assert!(!is_open_question("alguem viu o ferreiro"));
+    assert!(!is_open_question("@Kael voce viu o ferreiro?"));
+}
+
+#[test]
+fn open_questions_rotate_through_bots_one_at_a_time() {
+    let bots = ["Kael", "Lyria", "Thorn"];
+    let mut turn = RoundRobin::default();
+    let mode = BotReplyMode::Ambient;
+
+    let responders: Vec<_> = (0..4)
+        .map(|_| choose_dispatch_target(mode, None, || turn.pick(&bots)))
+        .collect();
+    assert_eq!(
+        responders,
+        vec![Some("Kael"), Some("Lyria"), Some("Thorn"), Some("Kael")]
+    );
+    assert_eq!(turn.pick::<&str>(&[]), None);
+}
+
+#[test]
+fn dispatch_cap_keeps_only_newest_messages_of_a_tick() {
+    // 10 mensagens novas, limite 3: só os índices 7, 8 e 9 acionam bots.
+    let start = capped_dispatch_start(10, 3);
+    let dispatched: Vec<usize> = (0..10).filter(|i| *i >= start).collect();
+    assert_eq!(dispatched, vec![7, 8, 9]);
+
+    // Abaixo do limite, nada é descartado.
+    assert_eq!(capped_dispatch_start(2, 3), 0);
+    // Limite 0 desativa o corte.
+    assert_eq!(capped_dispatch_start(10, 0), 0);
+}
+
+#[test]
+fn bot_preview_uses_current_mood_without_touching_history() {
+    let mut state = AppStateBuilder::new().build();
+    state.ai_moods.insert("Aelric".to_string(), 0.97);
+    let bot = AiBotProfile {
+        name: "Aelric".to_string(),
+        profession: "Mago".to_string(),
+        style: String::new(),
+        model: DEFAULT_MODEL.to_string(),
+    };
+
+    let preview = build_bot_preview(&state, &bot, "  Qual feitiço você prefere? ");
+    assert_eq!(preview.temperature, 0.97);
+    assert_eq!(preview.history.len(), 1);
+    assert_eq!(preview.history[0].content, "Qual feitiço você prefere?");
+    assert!(state.ai_histories.is_empty());
+    assert_eq!(state.ai_moods["Aelric"], 0.97);
+
+    let other = AiBotProfile {
+        name: "Lyria".to_string(),
+        profession: "Bardo".to_string(),
+        style: String::new(),
+        model: DEFAULT_MODEL.to_string(),
+    };
+    let preview = build_bot_preview(&state, &other, "");
+    assert_eq!(preview.temperature, MOOD_DEFAULT_TEMPERATURE);
+    assert_eq!(preview.history[0].content, PREVIEW_DEFAULT_PROMPT);
+    assert!(!state.ai_moods.contains_key("Lyria"));
+}
+
+#[test]
+fn preview_sample_is_labelled_with_bot_and_temperature() {
+    assert_eq!(
+        format_preview("Aelric", 0.853, "Bola de fogo, sempre."),
+        "Preview Aelric (temp 0.85): Bola de fogo, sempre."
+    );
+}
+
+#[test]
+fn parse_personas_reads_profiles_and_fills_missing_models() {
+    let text = r#"
+        [[persona]]
+        name = " Deckard "
+        profession = "Detetive"
+        style = "Fala pouco, desconfiado."
+        model = "llama3"
+
+        [[persona]]
+        name = "Rachael"
+        profession = "Replicante"
+    "#;
+    let personas =
+        parse_personas(text, &["qwen2".to_string()], DEFAULT_MODEL).expect("valid personas");
+    assert_eq!(personas.len(), 2);
+    assert_eq!(personas[0].name, "Deckard");
+    assert_eq!(personas[0].style, "Fala pouco, desconfiado.");
+    assert_eq!(personas[0].model, "llama3");
+    assert_eq!(personas[1].profession, "Replicante");
+    assert_eq!(personas[1].style, "");
+    assert_eq!(personas[1].model, "qwen2");
+}
+
+#[test]
+fn parse_personas_rejects_malformed_files() {
+    let parse = |text: &str| parse_personas(text, &[], DEFAULT_MODEL);
+    assert!(parse("").is_err());
+    assert!(parse("[[persona]]\nname = \"Sem profissao\"").is_err());
+    assert!(parse("[[persona]]\nname = \"\"\nprofession = \"Bardo\"").is_err());
+    assert!(
+        parse("[[persona]]\nname = \"Ana\"\nprofession = \"Bardo\"\n[[persona]]\nname = \"ana\"\nprofession = \"Mago\"")
+            .is_err()
+    );
+    assert!(parse("persona = 3").is_err());
+    let long_name = "n".repeat(MAX_NAME_LEN + 1);
+    assert!(
+        parse(&format!(
+            "[[persona]]\nname = \"{long_name}\"\nprofession = \"Bardo\""
+        ))
+        .is_err()
+    );
+    let longest = "é".repeat(MAX_NAME_LEN);
+    assert!(
+        parse(&format!(
+            "[[persona]]\nname = \"{longest}\"\nprofession = \"Bardo\""
+        ))
+        .is_ok()
+    );
+}
+
+#[test]
+fn worker_pool_runs_queued_jobs_in_order_with_one_worker() {
+    let pool = WorkerPool::new(1).expect("pool");
+    let (done_tx, done_rx) = std::sync::mpsc::channel();
+    for index in 0..5 {
+        let done_tx = done_tx.clone();
+        pool.submit(move |_runtime| done_tx.send(index).expect("send"))
+            .expect("submit");
+    }
+    let order: Vec<i32> = (0..5)
+        .map(|_| {
+            done_rx
+                .recv_timeout(std::time::Duration::from_secs(5))
+                .expect("job finished")
+        })
+        .collect();
+    assert_eq!(order, vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn worker_pool_caps_concurrency_at_the_worker_count() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let pool = WorkerPool::new(2).expect("pool");
+    let running = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let (done_tx, done_rx) = std::sync::mpsc::channel();
+    for _ in 0..8 {
+        let (running, peak, done_tx) = (Arc::clone(&running), Arc::clone(&peak), done_tx.clone());
+        pool.submit(move |runtime| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            // O runtime do worker atende o trabalho async, como nos pedidos ao Ollama.
+            runtime.block_on(async {
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            });
+            running.fetch_sub(1, Ordering::SeqCst);
+            done_tx.send(()).expect("send");
+        })
+        .expect("submit");
+    }
+    for _ in 0..8 {
+        done_rx
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .expect("job finished");
+    }
+    assert!(peak.load(Ordering::SeqCst) <= 2);
+    assert_eq!(running.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn worker_runtime_is_reused_across_sequential_requests() {
+    let pool = WorkerPool::new(1).expect("pool");
+    let (done_tx, done_rx) = std::sync::mpsc::channel();
+    for index in 0..3u64 {
+        let done_tx = done_tx.clone();
+        pool.submit(move |runtime| {
+            let doubled = runtime.block_on(async {
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+                index * 2
+            });
+            done_tx
+                .send((doubled, std::thread::current().id()))
+                .expect("send");
+        })
+        .expect("submit");
+    }
+    let results: Vec<_> = (0..3)
+        .map(|_| {
+            done_rx
+                .recv_timeout(std::time::Duration::from_secs(5))
+                .expect("job finished")
+        })
+        .collect();
+    assert_eq!(
+        results.iter().map(|(value, _)| *value).collect::<Vec<_>>(),
+        vec![0, 2, 4]
+    );
+    assert!(results.iter().all(|(_, thread)| *thread == results[0].1));
+}
+
+#[test]
+fn worker_pool_shutdown_stops_accepting_and_drops_queued_jobs() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let pool = WorkerPool::new(1).expect("pool");
+    let ran = Arc::new(AtomicUsize::new(0));
+    let (started_tx, started_rx) = std::sync::mpsc::channel();
+    let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+    pool.submit(move |_runtime| {
+        started_tx.send(()).expect("send");
+        let _ = release_rx.recv();
+    })
+    .expect("submit");
+    started_rx
+        .recv_timeout(std::time::Duration::from_secs(5))
+        .expect("first job started");
+    let queued = Arc::clone(&ran);
+    pool.submit(move |_runtime| {
+        queued.fetch_add(1, Ordering::SeqCst);
+    })
+    .expect("submit");
+
+    // Em andamento além do prazo: o worker fica para trás.
+    assert!(!pool.shutdown(std::time::Duration::from_millis(20)));
+    assert!(pool.submit(|_runtime| {}).is_err());
+
+    drop(release_tx);
+    assert!(pool.shutdown(std::time::Duration::from_secs(5)));
+    assert_eq!(ran.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn ollama_unavailable_notice_explains_how_to_fix_it() {
+    let notice = ollama_unavailable_notice("http://127.0.0.1", 11434, "connection refused");
+    assert!(notice.contains("http://127.0.0.1:11434"));
+    assert!(notice.contains("connection refused"));
+    assert!(notice.contains("ollama serve"));
+    for var in ["OLLAMA_HOST", "OLLAMA_PORT", "OLLAMA_MODEL"] {
+        assert!(notice.contains(var), "{var}");
+    }
+}
+
+#[test]
+fn retry_delay_doubles_from_the_base_delay() {
+    use std::time::Duration;
+    assert_eq!(retry_delay(1), Duration::from_millis(250));
+    assert_eq!(retry_delay(2), Duration::from_millis(500));
+    assert_eq!(retry_delay(3), Duration::from_millis(1000));
+    // Muitas falhas não estouram nem prendem o worker: a espera para no teto.
+    assert_eq!(retry_delay(5), Duration::from_secs(4));
+    assert_eq!(retry_delay(6), Duration::from_secs(5));
+    assert_eq!(retry_delay(200), Duration::from_secs(5));
+}
+
+#[test]
+fn timeout_error_message_names_the_wait() {
+    assert_eq!(
+        timeout_error_message(std::time::Duration::from_secs(30)),
+        "sem resposta do Ollama em 30s"
+    );
+}
+
+#[test]
+fn quiet_hours_parse_accepts_clock_ranges_only() {
+    assert!(QuietHours::parse("23:00-07:00").is_some());
+    assert!(QuietHours::parse(" 9:30 - 12:00 ").is_some());
+    assert_eq!(QuietHours::parse(""), None);
+    assert_eq!(QuietHours::parse("23:00"), None);
+    assert_eq!(QuietHours::parse("24:00-07:00"), None);
+    assert_eq!(QuietHours::parse("10:60-11:00"), None);
+    assert_eq!(QuietHours::parse("08:00-08:00"), None);
+}
+
+#[test]
+fn quiet_hours_cover_same_day_and_overnight_windows() {
+    let at = |h: u32, m: u32| h * 60 + m;
+
+    let lunch = QuietHours::parse("12:00-13:30").expect("valid");
+    assert!(lunch.contains(at(12, 0)));
+    assert!(lunch.contains(at(13, 29)));
+    assert!(!lunch.contains(at(13, 30)));
+    assert!(!lunch.contains(at(11, 59)));
+
+    let night = QuietHours::parse("23:00-07:00").expect("valid");
+    assert!(night.contains(at(23, 0)));
+    assert!(night.contains(at(0, 0)));
+    assert!(night.contains(at(6, 59)));
+    assert!(!night.contains(at(7, 0)));
+    assert!(!night.contains(at(22, 59)));
+    assert!(!night.contains(at(12, 0)));
+}
+
+fn kael() -> AiBotProfile {
+    AiBotProfile {
+        name: "Kael".to_string(),
+        profession: "Mago".to_string(),
+        style: String::new(),
+        model: "mistral:7b".to_string(),
+    }
+}
+
+#[test]
+fn bot_command_reply_is_canned_and_uses_the_current_mood() {
+    let mut state = AppState::default();
+    state.ai_moods.insert("Kael".to_string(), 0.62);
+
+    // Resposta montada na hora, sem histórico nem chamada ao modelo.
+    let reply = bot_command_reply(&state, &kael(), "@Kael /mood").expect("command reply");
+    assert!(reply.contains("Kael"));
+    assert!(reply.contains("Mago"));
+    assert!(reply.contains(mood_label(0.62)));
+    assert!(reply.contains("0.62"));
+    assert!(state.ai_histories.is_empty());
+
+    let default = bot_command_reply(&AppState::default(), &kael(), "@kael, /MOOD?").unwrap();
+    assert!(default.contains(&format!("{MOOD_DEFAULT_TEMPERATURE:.2}")));
+}
+
+#[test]
+fn find_bot_command_needs_a_known_command_right_after_the_mention() {
+    assert_eq!(
+        find_bot_command("Kael", "ei @Kael /whoami").map(|c| c.name),
+        Some("whoami")
+    );
+    assert!(find_bot_command("Kael", "@Kael /voar").is_none());
+    assert!(find_bot_command("Kael", "@Lia /mood").is_none());
+    assert!(find_bot_command("Kael", "Kael /mood").is_none());
+    assert!(find_bot_command("Kael", "@Kael qual seu /mood").is_none());
+    assert!(bot_command_reply(&AppState::default(), &kael(), "@Kael tudo bem?").is_none());
+}
+
+#[test]
+fn commands_listing_names_every_bot_command() {
+    let reply = bot_command_reply(&AppState::default(), &kael(), "@Kael /commands").unwrap();
+    for command in BOT_COMMANDS {
+        assert!(reply.contains(&format!("/{}", command.name)));
+    }
+}
+
+#[test]
+fn dispatch_order_prefers_a_human_over_a_concurrent_bot_message() {
+    // Tick com uma fala de humano seguida de uma de bot e limite de 1.
+    assert_eq!(dispatch_order(&[false, true], 1, true), vec![0]);
+    // Sem a preferência, vale só a mais recente.
+    assert_eq!(dispatch_order(&[false, true], 1, false), vec![1]);
+}
+
+#[test]
+fn dispatch_order_puts_humans_first_and_fills_the_rest_with_recent_bots() {
+    let from_ai = [true, false, true, true, false];
+    assert_eq!(dispatch_order(&from_ai, 3, true), vec![1, 4, 3]);
+    assert_eq!(dispatch_order(&from_ai, 0, true), vec![1, 4, 0, 2, 3]);
+    // Mais humanos que o limite: ficam os mais novos.
+    assert_eq!(
+        dispatch_order(&[false, false, false, true], 2, true),
+        vec![1, 2]
+    );
+    assert_eq!(dispatch_order(&from_ai, 3, false), vec![2, 3, 4]);
+    assert!(dispatch_order(&[], 3, true).is_empty());
+}
+
+#[test]
+fn clamp_bot_count_stays_within_the_name_pool() {
+    assert_eq!(clamp_bot_count(3), 3);
+    assert_eq!(clamp_bot_count(0), 0);
+    assert_eq!(clamp_bot_count(-4), 0);
+    assert_eq!(clamp_bot_count(1_000), super::FANTASY_NAMES.len());
+    assert_eq!(clamp_bot_count(i64::MAX), super::FANTASY_NAMES.len());
+}
+
+/// Gancho de teste: grita todas as respostas.
+struct UppercaseHook;
+
+impl ReplyHook for UppercaseHook {
+    fn process(&self, _bot: &AiBotProfile, reply: String) -> String {
+        reply.to_uppercase()
+    }
+}
+
+#[test]
+fn reply_hook_is_applied_to_the_normalized_reply() {
+    let reply = normalize_reply("  oi, tudo bem?  ");
+    assert_eq!(
+        post_process_reply(&UppercaseHook, &kael(), reply.clone()),
+        Some("OI, TUDO BEM?".to_string())
+    );
+    assert_eq!(
+        post_process_reply(&NoopReplyHook, &kael(), reply.clone()),
+        Some(reply)
+    );
+}
+
+#[test]
+fn reply_hook_can_sign_or_drop_replies() {
+    let sign = |bot: &AiBotProfile, reply: String| format!("{reply} -- {}", bot.name);
+    assert_eq!(
+        post_process_reply(&sign, &kael(), "ok".to_string()),
+        Some("ok -- Kael".to_string())
+    );
+    let drop_all = |_: &AiBotProfile, _: String| String::new();
+    assert_eq!(
+        post_process_reply(&drop_all, &kael(), "ok".to_string()),
+        None
+    );
+}
+
+#[test]
+fn bot_models_are_assigned_round_robin() {
+    let models = vec![
+        "mistral:7b".to_string(),
+        " llama3 ".to_string(),
+        String::new(),
+    ];
+    assert_eq!(
+        bot_models(5, &models, "ignored"),
+        vec!["mistral:7b", "llama3", "mistral:7b", "llama3", "mistral:7b"]
+    );
+
+    // Distribuição equilibrada: nenhum modelo fica com mais de um bot de diferença.
+    let assigned = bot_models(7, &models, "ignored");
+    let mistral = assigned.iter().filter(|m| *m == "mistral:7b").count();
+    assert_eq!((mistral, assigned.len() - mistral), (4, 3));
+}
+
+#[test]
+fn bot_models_fall_back_to_the_single_model() {
+    assert_eq!(bot_models(2, &[], "llama3"), vec!["llama3", "llama3"]);
+    assert_eq!(bot_models(1, &[" ".to_string()], ""), vec![DEFAULT_MODEL]);
+    assert!(bot_models(0, &[], "llama3").is_empty());
+}
+
+#[test]
+fn system_prompt_override_replaces_only_the_base_prompt() {
+    let bot = AiBotProfile {
+        name: "Aelric".to_string(),
+        profession: "Mago".to_string(),
+        style: String::new(),
+        model: DEFAULT_MODEL.to_string(),
+    };
+    let ctx = super::context::AiPromptContext {
+        requester_name: "Rafael".to_string(),
+        ..Default::default()
+    };
+    let history = vec![AiHistoryEntry {
+        role: AiRole::User,
+        content: "hello there".to_string(),
+    }];
+
+    let override_prompt = "You are a regular user of an English chat room.";
+    let messages = build_chat_messages(
+        base_system_prompt(override_prompt, Lang::English),
+        "",
+        &bot,
+        &ctx,
+        history.clone(),
+    );
+    let contents: Vec<&str> = messages.iter().map(|m| m.content.as_str()).collect();
+    assert_eq!(contents[0], override_prompt);
+    assert!(!contents.contains(&BASE_SYSTEM_PROMPT));
+    assert!(contents.iter().any(|c| c.contains("Aelric")));
+    assert!(contents.iter().any(|c| c.contains("Usuario que te chamou")));
+    assert_eq!(contents.last(), Some(&"hello there"));
+
+    let messages = build_chat_messages(
+        base_system_prompt("  ", Lang::Portuguese),
+        "",
+        &bot,
+        &ctx,
+        history,
+    );
+    assert_eq!(messages[0].content, BASE_SYSTEM_PROMPT);
+}
+
+#[test]
+fn guess_language_detects_english_and_defaults_to_portuguese() {
+    let english: Vec<String> = [
+        "[10:01] Ana: hey, what are you all doing tonight?",
+        "[10:02] Bruno: I'm just reading, it's raining here",
+        "Aelric: that sounds nice, the tavern is full",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    assert_eq!(guess_language(&english), Lang::English);
+
+    let portuguese: Vec<String> = [
+        "[10:01] Ana: e ai, o que voces vao fazer hoje?",
+        "[10:02] Bruno: to lendo, esta chovendo muito aqui",
+        "Aelric: isso parece bom, a taverna esta cheia",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    assert_eq!(guess_language(&portuguese), Lang::Portuguese);
+
+    // Pouca evidência ou mistura equilibrada: fica no português.
+    assert_eq!(guess_language(&[]), Lang::Portuguese);
+    assert_eq!(guess_language(&["Ana: ok".to_string()]), Lang::Portuguese);
+    assert_eq!(
+        guess_language(&[
+            "Ana: the game is on, mas eu nao vi o jogo e tem muito barulho".to_string()
+        ]),
+        Lang::Portuguese
+    );
+}
+
+#[test]
+fn base_prompt_follows_the_detected_language_unless_configured() {
+    assert_eq!(base_system_prompt("", Lang::English), BASE_SYSTEM_PROMPT_EN);
+    assert_eq!(base_system_prompt("", Lang::Portuguese), BASE_SYSTEM_PROMPT);
+    assert_eq!(
+        base_system_prompt("Seja pirata.", Lang::English),
+        "Seja pirata."
+    );
+}
+
+#[test]
+fn partial_reply_text_compacts_whitespace_and_marks_it_unfinished() {
+    assert_eq!(partial_reply_text("  Ola,\n tudo   bem"), "Ola, tudo bem…");
+}
+
+#[test]
+fn typing_delay_grows_with_reply_length_up_to_the_cap() {
+    assert_eq!(typing_delay_for(""), std::time::Duration::ZERO);
+    assert_eq!(typing_delay_for("  oi  "), AI_TYPING_DELAY_PER_CHAR * 2);
+    assert!(typing_delay_for("bom dia a todos") > typing_delay_for("oi"));
+    assert_eq!(typing_delay_for(&"a".repeat(5_000)), AI_MAX_TYPING_DELAY);
+}
+
+#[test]
+fn forced_responder_prefers_the_named_bot_and_falls_back_when_offline() {
+    let online = ["Aelric", "Lyria"];
+    assert_eq!(
+        forced_responder(&online, Some("lyria"), 0),
+        Some((1, false))
+    );
+    assert_eq!(forced_responder(&online, Some("Thorn"), 3), Some((1, true)));
+    assert_eq!(forced_responder(&online, None, 2), Some((0, false)));
+    assert_eq!(forced_responder(&[], Some("Aelric"), 0), None);
+}
+
+#[test]
+fn mentioned_names_finds_every_named_bot_in_message_order() {
+    let online = ["Branna", "Aelric", "Lyria"];
+    assert_eq!(
+        mentioned_names(&online, "Aelric e Branna, o que acham?"),
+        vec![1, 0]
+    );
+    assert_eq!(mentioned_names(&online, "oi LYRIA"), vec![2]);
+    assert!(mentioned_names(&online, "alguem ai?").is_empty());
+    assert!(mentioned_names(&["", "Aelric"], "nada").is_empty());
+}
+
+#[test]
+fn directed_reply_limit_follows_the_remaining_chain() {
+    use super::MAX_AI_CHAIN_MESSAGES;
+    assert_eq!(directed_reply_limit(0), MAX_AI_CHAIN_MESSAGES);
+    assert_eq!(directed_reply_limit(MAX_AI_CHAIN_MESSAGES - 2), 2);
+    // O primeiro citado responde mesmo com a cadeia esgotada.
+    assert_eq!(directed_reply_limit(MAX_AI_CHAIN_MESSAGES + 3), 1);
+}
+
+#[test]
+fn word_similarity_ignores_case_punctuation_and_order() {
+    assert_eq!(
+        word_similarity("A taverna está cheia!", "a TAVERNA, está cheia"),
+        1.0
+    );
+    assert_eq!(word_similarity("chuva forte", "sol quente"), 0.0);
+    assert_eq!(word_similarity("", "..."), 0.0);
+    let partial = word_similarity("a taverna esta cheia hoje", "a taverna esta vazia");
+    assert!(partial > 0.4 && partial < 0.7, "{partial}");
+}
+
+#[test]
+fn echo_guard_flags_near_repeats_only() {
+    let recent = [
+        "Alguem viu o ferreiro hoje?",
+        "A chuva nao para no reino, que saco.",
+    ];
+    assert!(is_echo("a chuva nao para no reino que saco", &recent));
+    assert!(is_echo(
+        "A chuva nao para no reino, que saco mesmo!",
+        &recent
+    ));
+    assert!(!is_echo(
+        "Vi o ferreiro cedo, tava fechando a forja.",
+        &recent
+    ));
+    assert!(!is_echo("qualquer coisa", &[] as &[&str]));
+    // Respostas curtas repetidas são conversa normal.
+    assert!(!is_echo("sim", &["sim"]));
+    assert!(!is_echo("haha, verdade", &["Haha verdade!"]));
+}
+
+#[test]
+fn dropped_reply_is_removed_from_the_bot_history() {
+    let entry = |role: AiRole, content: &str| AiHistoryEntry {
+        role,
+        content: content.to_string(),
+    };
+    let mut history = vec![
+        entry(AiRole::User, "bom dia"),
+        entry(AiRole::Assistant, "bom dia"),
+        entry(AiRole::User, "a chuva nao para"),
+        entry(AiRole::Assistant, "a chuva nao para"),
+    ];
+    assert!(remove_last_reply(&mut history, "a chuva nao para"));
+    assert_eq!(
+        history,
+        vec![
+            entry(AiRole::User, "bom dia"),
+            entry(AiRole::Assistant, "bom dia"),
+            entry(AiRole::User, "a chuva nao para"),
+        ]
+    );
+    // A fala do usuário com o mesmo texto fica.
+    assert!(!remove_last_reply(&mut history, "a chuva nao para"));
+}
+
+#[test]
+fn bot_memory_round_trips_through_json_and_is_trimmed_on_load() {
+    let entry = |role: AiRole, content: &str| AiHistoryEntry {
+        role,
+        content: content.to_string(),
+    };
+    let mut memory = BotMemory::new();
+    memory.insert(
+        "Aelric".to_string(),
+        vec![
+            entry(AiRole::User, "Oi, Aelric!"),
+            entry(AiRole::Assistant, "Saudações, viajante."),
+        ],
+    );
+    let text = serde_json::to_string(&memory).expect("serialize memory");
+    assert!(text.contains("\"role\":\"assistant\""));
+    assert_eq!(parse_memory(&text, 0), Ok(memory));
+
+    let mut long = BotMemory::new();
+    long.insert(
+        "Lyria".to_string(),
+        (0..MAX_HISTORY_ENTRIES + 5)
+            .map(|i| entry(AiRole::User, &format!("msg {i}")))
+            .collect(),
+    );
+    long.insert("Vazio".to_string(), Vec::new());
+    let text = serde_json::to_string(&long).expect("serialize memory");
+    let loaded = parse_memory(&text, 0).expect("parse memory");
+    let lyria = &loaded["Lyria"];
+    assert_eq!(lyria.len(), MAX_HISTORY_ENTRIES);
+    assert_eq!(lyria.last().map(|e| e.content.as_str()), Some("msg 16"));
+    assert!(!loaded.contains_key("Vazio"));
+    assert!(parse_memory("not json", 0).is_err());
+}
+
+#[test]
+fn model_options_use_config_values_clamped_and_shifted_by_mood() {
+    let options = |ai: &crate::config::AiConfig, mood: f32| {
+        let options = ollama_rs::models::ModelOptions::from(sampling_options(ai, mood));
+        serde_json::to_value(options).expect("serialize options")
+    };
+    let defaults = options(
+        &crate::config::AiConfig::default(),
+        MOOD_DEFAULT_TEMPERATURE,
+    );
+    assert_eq!(defaults["num_predict"], 70);
+    assert!((defaults["temperature"].as_f64().unwrap() - 0.85).abs() < 1e-6);
+    assert!((defaults["top_p"].as_f64().unwrap() - 0.95).abs() < 1e-6);
+    assert!((defaults["repeat_penalty"].as_f64().unwrap() - 1.35).abs() < 1e-6);
+
+    let wild = crate::config::AiConfig {
+        temperature: 5.0,
+        top_p: 1.5,
+        repeat_penalty: -1.0,
+        num_predict: 0,
+        ..Default::default()
+    };
+    let clamped = options(&wild, MOOD_DEFAULT_TEMPERATURE);
+    assert_eq!(clamped["temperature"], 2.0);
+    assert_eq!(clamped["top_p"], 1.0);
+    assert_eq!(clamped["repeat_penalty"], 0.0);
+    assert_eq!(clamped["num_predict"], 1);
+
+    let cold = crate::config::AiConfig {
+        temperature: 0.3,
+        ..Default::default()
+    };
+    let shifted = options(&cold, MOOD_DEFAULT_TEMPERATURE + 0.1);
+    assert!((shifted["temperature"].as_f64().unwrap() - 0.4).abs() < 1e-6);
+    let frozen = crate::config::AiConfig {
+        temperature: 0.1,
+        ..Default::default()
+    };
+    assert_eq!(options(&frozen, MOOD_MIN_TEMPERATURE)["temperature"], 0.0);
+}
+
+#[test]
+fn backend_is_selected_from_the_setting() {
+    assert_eq!(BackendKind::from_setting("openai"), BackendKind::OpenAi);
+    assert_eq!(BackendKind::from_setting(" OpenAI "), BackendKind::OpenAi);
+    assert_eq!(BackendKind::from_setting("ollama"), BackendKind::Ollama);
+    assert_eq!(BackendKind::from_setting("gpt"), BackendKind::Ollama);
+}
+
+#[test]
+fn openai_request_body_carries_messages_and_sampling() {
+    let request = CompletionRequest {
+        model: "qwen2.5-7b-instruct".to_string(),
+        messages: vec![
+            ollama_rs::generation::chat::ChatMessage::system("seja breve".to_string()),
+            ollama_rs::generation::chat::ChatMessage::user("oi".to_string()),
+        ],
+        sampling: Sampling {
+            temperature: 0.5,
+            top_p: 0.9,
+            repeat_penalty: 1.2,
+            max_tokens: 70,
+        },
+        timeout: std::time::Duration::from_secs(30),
+        max_attempts: 1,
+    };
+    let body = openai_request_body(&request);
+    assert_eq!(body["model"], "qwen2.5-7b-instruct");
+    assert_eq!(body["stream"], true);
+    assert_eq!(body["max_tokens"], 70);
+    assert_eq!(body["messages"][0]["role"], "system");
+    assert_eq!(body["messages"][1]["role"], "user");
+    assert_eq!(body["messages"][1]["content"], "oi");
+}
+
+#[test]
+fn openai_stream_lines_yield_deltas_until_done() {
+    assert_eq!(
+        parse_stream_line(r#"data: {"choices":[{"delta":{"content":"Olá"}}]}"#),
+        StreamLine::Delta("Olá".to_string())
+    );
+    assert_eq!(parse_stream_line("data: [DONE]"), StreamLine::Done);
+    assert_eq!(
+        parse_stream_line(r#"data: {"choices":[{"delta":{"role":"assistant"}}]}"#),
+        StreamLine::Ignored
+    );
+    assert_eq!(parse_stream_line(": keep-alive"), StreamLine::Ignored);
+    assert_eq!(parse_stream_line(""), StreamLine::Ignored);
+}
+
+#[test]
+fn forced_prompts_match_once_and_expire() {
+    let start = Instant::now();
+    let mut prompts = ForcedPrompts::default();
+    prompts.push("Alguem sabe do ferreiro?", start);
+    assert!(prompts.take_match("  Alguem sabe do ferreiro? ", start));
+    assert!(!prompts.take_match("Alguem sabe do ferreiro?", start));
+
+    // Envio rejeitado: a mensagem nunca chega e a entrada vence sozinha.
+    prompts.push("oi", start);
+    let later = start + AI_FORCED_PROMPT_TTL;
+    assert!(!prompts.take_match("oi", later));
+}
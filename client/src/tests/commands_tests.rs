@@ -0,0 +1,173 @@
+use super::{Command, parse_command};
+
+#[test]
+fn plain_text_and_unknown_commands_are_not_commands() {
+    assert_eq!(parse_command("oi pessoal"), None);
+    assert_eq!(parse_command(""), None);
+    assert_eq!(parse_command("/shrug"), None);
+    assert_eq!(parse_command("/names"), None);
+    assert_eq!(parse_command("a /quit no meio"), None);
+}
+
+#[test]
+fn commands_without_arguments() {
+    assert_eq!(parse_command("/clear"), Some(Command::Clear));
+    assert_eq!(parse_command("/quit"), Some(Command::Quit));
+    assert_eq!(parse_command("/help"), Some(Command::Help));
+    assert_eq!(parse_command("/hideoffline"), Some(Command::HideOffline));
+    assert_eq!(parse_command("/botsquiet"), Some(Command::BotsQuiet));
+    assert_eq!(parse_command("/quit now"), Some(Command::Usage("/quit")));
+}
+
+#[test]
+fn name_requires_a_new_name() {
+    assert_eq!(
+        parse_command("/name  Rafael Ramos"),
+        Some(Command::Name("Rafael Ramos".to_string()))
+    );
+    assert_eq!(parse_command("/name"), Some(Command::Usage("/name <name>")));
+}
+
+#[test]
+fn whisper_splits_recipient_and_text() {
+    assert_eq!(
+        parse_command("/w Lyria  oi, tudo bem?"),
+        Some(Command::Whisper {
+            to: "Lyria".to_string(),
+            text: "oi, tudo bem?".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_command("/w Lyria"),
+        Some(Command::Usage("/w <name> <text>"))
+    );
+    assert_eq!(
+        parse_command("/w"),
+        Some(Command::Usage("/w <name> <text>"))
+    );
+}
+
+#[test]
+fn optional_and_required_arguments() {
+    assert_eq!(
+        parse_command("/status"),
+        Some(Command::Status(String::new()))
+    );
+    assert_eq!(
+        parse_command("/status em combate"),
+        Some(Command::Status("em combate".to_string()))
+    );
+    assert_eq!(
+        parse_command("/roll 2d6"),
+        Some(Command::Roll("2d6".to_string()))
+    );
+    assert_eq!(
+        parse_command("/react 👍"),
+        Some(Command::React("👍".to_string()))
+    );
+    assert_eq!(
+        parse_command("/react"),
+        Some(Command::Usage("/react <emoji>"))
+    );
+    assert_eq!(
+        parse_command("/announce Evento às 20h"),
+        Some(Command::Announce("Evento às 20h".to_string()))
+    );
+    assert_eq!(
+        parse_command("/announce"),
+        Some(Command::Usage("/announce <text>"))
+    );
+}
+
+#[test]
+fn preview_takes_bot_and_optional_prompt() {
+    assert_eq!(
+        parse_command("/preview Aelric qual seu feitiço favorito?"),
+        Some(Command::Preview {
+            bot: "Aelric".to_string(),
+            prompt: "qual seu feitiço favorito?".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_command("/preview Aelric"),
+        Some(Command::Preview {
+            bot: "Aelric".to_string(),
+            prompt: String::new(),
+        })
+    );
+    assert_eq!(
+        parse_command("/preview"),
+        Some(Command::Usage("/preview <bot> [prompt]"))
+    );
+}
+
+#[test]
+fn ai_and_ask_force_a_bot_reply() {
+    assert_eq!(
+        parse_command("/ai alguém aí?"),
+        Some(Command::Ask {
+            bot: None,
+            text: "alguém aí?".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_command("/ask @Aelric qual seu feitiço favorito?"),
+        Some(Command::Ask {
+            bot: Some("Aelric".to_string()),
+            text: "qual seu feitiço favorito?".to_string(),
+        })
+    );
+    assert_eq!(parse_command("/ai"), Some(Command::Usage("/ai <text>")));
+    assert_eq!(
+        parse_command("/ask Aelric"),
+        Some(Command::Usage("/ask <bot> <text>"))
+    );
+}
+
+#[test]
+fn title_targets_self_or_a_named_user() {
+    assert_eq!(
+        parse_command("/title bardo errante"),
+        Some(Command::Title {
+            target: None,
+            title: "bardo errante".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_command("/title"),
+        Some(Command::Title {
+            target: None,
+            title: String::new(),
+        })
+    );
+    assert_eq!(
+        parse_command("/title @Lyria mod"),
+        Some(Command::Title {
+            target: Some("Lyria".to_string()),
+            title: "mod".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_command("/title @"),
+        Some(Command::Usage("/title @<name> [text]"))
+    );
+}
+
+#[test]
+fn connect_takes_exactly_uri_and_module() {
+    assert_eq!(
+        parse_command("/connect http://other:3000 chat-room"),
+        Some(Command::Connect {
+            uri: "http://other:3000".to_string(),
+            module: "chat-room".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_command("/connect http://other:3000"),
+        Some(Command::Usage("/connect <uri> <module>"))
+    );
+    assert_eq!(
+        parse_command("/connect http://other:3000 chat-room extra"),
+        Some(Command::Usage("/connect <uri> <module>"))
+    );
+}
@@ -0,0 +1,578 @@
+use std::collections::HashMap;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use std::path::PathBuf;
+
+use super::{
+    Config, ConfigSource, ConnectionConfig, DEFAULT_EVENT_LOG, LOCAL_CONFIG_FILE, VALUE_FLAGS,
+    config_sources, key_matches, parse_key_binding, parse_layer,
+};
+use crate::connection::ConnectError;
+
+fn env_from(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let map: HashMap<String, String> = pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    move |key| map.get(key).cloned()
+}
+
+#[test]
+fn partial_config_keeps_defaults_for_missing_fields() {
+    let config = Config::from_toml_str(
+        r#"
+        [connection]
+        uri = "http://server:3000"
+
+        [ui]
+        idle_animation = false
+        "#,
+    )
+    .expect("valid toml");
+
+    let defaults = Config::default();
+    assert_eq!(config.connection.uri, "http://server:3000");
+    assert_eq!(config.connection.module, defaults.connection.module);
+    assert!(!config.ui.idle_animation);
+    assert_eq!(config.ai, defaults.ai);
+    assert_eq!(config.keybindings, defaults.keybindings);
+}
+
+#[test]
+fn empty_config_equals_default() {
+    assert_eq!(Config::from_toml_str("").ok(), Some(Config::default()));
+}
+
+#[test]
+fn invalid_config_is_reported() {
+    assert!(Config::from_toml_str("[connection]\nconnect_timeout_secs = \"x\"").is_err());
+}
+
+#[test]
+fn precedence_is_cli_then_env_then_file_then_default() {
+    let mut config = Config::from_toml_str(
+        r#"
+        [connection]
+        module = "from-file"
+        uri = "http://file:3000"
+        connect_timeout_secs = 5
+
+        [ai]
+        model = "file-model"
+        "#,
+    )
+    .expect("valid toml");
+
+    config.apply_env(env_from(&[
+        ("STDB_MODULE", "from-env"),
+        ("STDB_URI", "http://env:3000"),
+    ]));
+    config.apply_cli(&["from-cli".to_string()]);
+
+    // CLI > env
+    assert_eq!(config.connection.module, "from-cli");
+    // env > arquivo
+    assert_eq!(config.connection.uri, "http://env:3000");
+    // arquivo > padrão
+    assert_eq!(config.connection.connect_timeout_secs, 5);
+    assert_eq!(config.ai.model, "file-model");
+    // padrão quando ninguém define
+    assert_eq!(config.ai.port, Config::default().ai.port);
+}
+
+#[test]
+fn bots_theme_and_tuning_merge_across_file_env_and_cli() {
+    let mut config = Config::from_toml_str(
+        r#"
+        [ai]
+        bot_count = 2
+
+        [ai.tuning]
+        proactive_start_chance = 0.5
+        proactive_idle_secs = 30
+
+        [ui]
+        theme = "mono"
+        "#,
+    )
+    .expect("valid toml");
+
+    config.apply_env(env_from(&[
+        ("SHELLRELAY_BOTS", "4"),
+        ("SHELLRELAY_PROACTIVE_CHANCE", "0.1"),
+    ]));
+    config.apply_cli(&["--bots".to_string(), "6".to_string()]);
+
+    assert_eq!(config.ai.bot_count, Some(6));
+    assert_eq!(config.ai.tuning.proactive_start_chance, 0.1);
+    assert_eq!(config.ai.tuning.proactive_idle_secs, 30);
+    assert_eq!(config.ui.theme, "mono");
+}
+
+#[test]
+fn config_sources_put_local_file_over_user_config() {
+    let user = |path: &str| ConfigSource {
+        path: PathBuf::from(path),
+        trusted: true,
+    };
+    let local = ConfigSource {
+        path: PathBuf::from(LOCAL_CONFIG_FILE),
+        trusted: false,
+    };
+
+    let explicit = env_from(&[
+        ("SHELLRELAY_CONFIG", "/etc/relay.toml"),
+        ("HOME", "/home/ana"),
+    ]);
+    assert_eq!(
+        config_sources(&explicit, true),
+        vec![user("/etc/relay.toml"), local.clone()]
+    );
+
+    let home = env_from(&[("HOME", "/home/ana")]);
+    assert_eq!(
+        config_sources(&home, false),
+        vec![user("/home/ana/.config/shellrelay/config.toml")]
+    );
+
+    let xdg = env_from(&[("XDG_CONFIG_HOME", "/xdg"), ("HOME", "/home/ana")]);
+    assert_eq!(
+        config_sources(&xdg, false),
+        vec![user("/xdg/shellrelay/config.toml")]
+    );
+    assert_eq!(config_sources(env_from(&[]), true), vec![local]);
+}
+
+#[test]
+fn local_config_is_layered_over_user_config() {
+    let user = parse_layer(
+        "[ui]\ntheme = \"mono\"\n[ai]\nmodel = \"llama3\"\nbot_count = 2\n",
+        true,
+    )
+    .unwrap();
+    let local = parse_layer("[ai]\nbot_count = 5\n", false).unwrap();
+
+    let config = Config::from_layers([user, local]).unwrap();
+
+    assert_eq!(config.ui.theme, "mono");
+    assert_eq!(config.ai.model, "llama3");
+    assert_eq!(config.ai.bot_count, Some(5));
+}
+
+#[test]
+fn local_config_cannot_set_paths_or_endpoints() {
+    let raw = r#"
+[ai]
+host = "evil.example"
+port = 1
+openai_url = "http://evil.example/v1"
+personas = "/tmp/personas.toml"
+system_prompt_file = "/etc/passwd"
+memory_file = "/home/ana/.bashrc"
+bot_count = 4
+
+[ui]
+filter_file = "/etc/shadow"
+export_json = "/home/ana/.profile"
+
+[debug]
+event_log = "/home/ana/.ssh/authorized_keys"
+"#;
+    let user = parse_layer("[ai]\nhost = \"gpu-box\"\n", true).unwrap();
+    let local = parse_layer(raw, false).unwrap();
+
+    let config = Config::from_layers([user, local]).unwrap();
+    let defaults = Config::default();
+
+    assert_eq!(config.ai.host, "gpu-box");
+    assert_eq!(config.ai.port, defaults.ai.port);
+    assert_eq!(config.ai.openai_url, defaults.ai.openai_url);
+    assert_eq!(config.ai.personas, None);
+    assert_eq!(config.ai.system_prompt_file, None);
+    assert_eq!(config.ai.memory_file, None);
+    assert_eq!(config.ui.filter_file, None);
+    assert_eq!(config.ui.export_json, None);
+    assert_eq!(config.debug.event_log, defaults.debug.event_log);
+    assert_eq!(config.ai.bot_count, Some(4));
+
+    let trusted = Config::from_layers([parse_layer(raw, true).unwrap()]).unwrap();
+    assert_eq!(trusted.ai.host, "evil.example");
+    assert_eq!(
+        trusted.ai.memory_file,
+        Some(PathBuf::from("/home/ana/.bashrc"))
+    );
+}
+
+#[test]
+fn invalid_layer_is_rejected_before_merging() {
+    assert!(parse_layer("[ai]\nbot_count = \"muitos\"\n", false).is_err());
+    assert!(parse_layer("[ai\n", true).is_err());
+}
+
+#[test]
+fn invalid_env_values_are_ignored() {
+    let mut config = Config::default();
+    config.apply_env(env_from(&[
+        ("STDB_CONNECT_TIMEOUT_SECS", "0"),
+        ("OLLAMA_PORT", "not-a-port"),
+        ("SHELLRELAY_IDLE_ANIMATION", "off"),
+        ("SHELLRELAY_HISTORY_TOKENS", "lots"),
+        ("OLLAMA_TIMEOUT_SECS", "0"),
+        ("OLLAMA_RETRIES", "many"),
+        ("OLLAMA_TEMPERATURE", "NaN"),
+        ("OLLAMA_TOP_P", "alto"),
+    ]));
+    assert_eq!(config.connection.connect_timeout_secs, 10);
+    assert_eq!(config.ai.history_token_budget, 400);
+    assert_eq!(config.ai.port, 11434);
+    assert_eq!(config.ai.timeout_secs, 30);
+    assert_eq!(config.ai.retries, 3);
+    assert_eq!(config.ai.temperature, 0.85);
+    assert_eq!(config.ai.top_p, 0.95);
+    assert!(!config.ui.idle_animation);
+}
+
+#[test]
+fn ai_retries_are_capped_from_file_and_env() {
+    let config = Config::from_toml_str("[ai]\nretries = 4000000000").expect("valid toml");
+    assert_eq!(config.ai.retries, crate::ai::MAX_RETRIES);
+
+    let mut config = Config::default();
+    config.apply_env(env_from(&[("OLLAMA_RETRIES", "500")]));
+    assert_eq!(config.ai.retries, crate::ai::MAX_RETRIES);
+    config.apply_env(env_from(&[("OLLAMA_RETRIES", "2")]));
+    assert_eq!(config.ai.retries, 2);
+}
+
+#[test]
+fn ai_tuning_defaults_match_constants_and_env_overrides_are_clamped() {
+    use crate::ai::{
+        AI_PROACTIVE_COOLDOWN_SECS, AI_PROACTIVE_IDLE_SECS, AI_PROACTIVE_START_CHANCE,
+        AI_TO_AI_REPLY_CHANCE_IDLE, AI_TO_AI_REPLY_CHANCE_WITH_HUMANS,
+    };
+
+    let mut config = Config::default();
+    config.apply_env(env_from(&[]));
+    let tuning = config.ai.tuning;
+    assert_eq!(tuning.proactive_start_chance, AI_PROACTIVE_START_CHANCE);
+    assert_eq!(tuning.proactive_cooldown_secs, AI_PROACTIVE_COOLDOWN_SECS);
+    assert_eq!(tuning.proactive_idle_secs, AI_PROACTIVE_IDLE_SECS);
+    assert_eq!(tuning.ai_reply_chance_idle, AI_TO_AI_REPLY_CHANCE_IDLE);
+    assert_eq!(
+        tuning.ai_reply_chance_with_humans,
+        AI_TO_AI_REPLY_CHANCE_WITH_HUMANS
+    );
+
+    config.apply_env(env_from(&[
+        ("SHELLRELAY_PROACTIVE_CHANCE", "1.7"),
+        ("SHELLRELAY_PROACTIVE_COOLDOWN_SECS", "60"),
+        ("SHELLRELAY_PROACTIVE_IDLE_SECS", "abc"),
+        ("SHELLRELAY_AI_REPLY_CHANCE_IDLE", "-0.5"),
+        ("SHELLRELAY_AI_REPLY_CHANCE_WITH_HUMANS", "NaN"),
+    ]));
+    let tuning = config.ai.tuning;
+    assert_eq!(tuning.proactive_start_chance, 1.0);
+    assert_eq!(tuning.proactive_cooldown_secs, 60);
+    assert_eq!(tuning.proactive_idle_secs, AI_PROACTIVE_IDLE_SECS);
+    assert_eq!(tuning.ai_reply_chance_idle, 0.0);
+    assert_eq!(
+        tuning.ai_reply_chance_with_humans,
+        AI_TO_AI_REPLY_CHANCE_WITH_HUMANS
+    );
+}
+
+#[test]
+fn llm_backend_env_selects_the_openai_backend() {
+    let mut config = Config::default();
+    assert_eq!(config.ai.backend, "ollama");
+    config.apply_env(env_from(&[
+        ("LLM_BACKEND", "openai"),
+        ("OPENAI_BASE_URL", "http://127.0.0.1:8000/v1"),
+        ("OPENAI_API_KEY", "sk-local"),
+    ]));
+    assert_eq!(config.ai.backend, "openai");
+    assert_eq!(config.ai.openai_url, "http://127.0.0.1:8000/v1");
+    assert_eq!(config.ai.openai_api_key, "sk-local");
+}
+
+#[test]
+fn sampling_options_come_from_env() {
+    let mut config = Config::default();
+    config.apply_env(env_from(&[
+        ("OLLAMA_TEMPERATURE", "1.2"),
+        ("OLLAMA_TOP_P", " 0.8 "),
+        ("OLLAMA_REPEAT_PENALTY", "1.1"),
+        ("OLLAMA_NUM_PREDICT", "120"),
+    ]));
+    assert_eq!(config.ai.temperature, 1.2);
+    assert_eq!(config.ai.top_p, 0.8);
+    assert_eq!(config.ai.repeat_penalty, 1.1);
+    assert_eq!(config.ai.num_predict, 120);
+}
+
+#[test]
+fn parse_key_binding_accepts_function_keys_and_modifiers() {
+    assert_eq!(
+        parse_key_binding("F5"),
+        Some((KeyCode::F(5), KeyModifiers::NONE))
+    );
+    assert_eq!(
+        parse_key_binding("Ctrl+r"),
+        Some((KeyCode::Char('r'), KeyModifiers::CONTROL))
+    );
+    assert_eq!(
+        parse_key_binding("esc"),
+        Some((KeyCode::Esc, KeyModifiers::NONE))
+    );
+    assert_eq!(parse_key_binding("Hyper+x"), None);
+    assert_eq!(parse_key_binding("Fx"), None);
+}
+
+#[test]
+fn every_value_flag_has_a_handler() {
+    for flag in VALUE_FLAGS {
+        let mut config = Config::default();
+        config.apply_cli(&[flag.to_string(), "2".to_string()]);
+        config.apply_cli(&[format!("{flag}=2")]);
+    }
+    let mut config = Config::default();
+    config.apply_cli(&["--system-prompt-file".to_string(), "prompt.txt".to_string()]);
+    assert_eq!(
+        config.ai.system_prompt_file,
+        Some(PathBuf::from("prompt.txt"))
+    );
+    assert_eq!(config.ai.personas, None);
+}
+
+#[test]
+fn key_matches_uses_configured_binding() {
+    let ctrl_r = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL);
+    assert!(key_matches("Ctrl+r", &ctrl_r));
+    assert!(!key_matches("F5", &ctrl_r));
+    assert!(!key_matches("Ctrl+r", &KeyEvent::from(KeyCode::Char('r'))));
+}
+
+#[test]
+fn key_matches_requires_the_same_modifiers() {
+    let ctrl_alt_e = KeyEvent::new(
+        KeyCode::Char('e'),
+        KeyModifiers::CONTROL | KeyModifiers::ALT,
+    );
+    assert!(!key_matches("Ctrl+e", &ctrl_alt_e));
+    assert!(!key_matches("Alt+e", &ctrl_alt_e));
+    assert!(key_matches("Ctrl+Alt+e", &ctrl_alt_e));
+
+    let ctrl_f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::CONTROL);
+    assert!(!key_matches("F5", &ctrl_f5));
+    let shift_f5 = KeyEvent::new(KeyCode::F(5), KeyModifiers::SHIFT);
+    assert!(!key_matches("F5", &shift_f5));
+}
+
+#[test]
+fn key_matches_ignores_shift_on_characters() {
+    // Terminais mandam `?`/`E` com SHIFT; o caractere já diz tudo.
+    let shifted = KeyEvent::new(KeyCode::Char('E'), KeyModifiers::SHIFT);
+    assert!(key_matches("E", &shifted));
+    let ctrl_shift = KeyEvent::new(
+        KeyCode::Char('E'),
+        KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+    );
+    assert!(key_matches("Ctrl+E", &ctrl_shift));
+    assert!(!key_matches("E", &ctrl_shift));
+}
+
+#[test]
+fn debug_flag_enables_event_log_without_shifting_positionals() {
+    let mut config = Config::default();
+    config.apply_cli(&[
+        "--debug".to_string(),
+        "my-module".to_string(),
+        "http://host:3000".to_string(),
+    ]);
+    assert_eq!(config.connection.module, "my-module");
+    assert_eq!(config.connection.uri, "http://host:3000");
+    assert_eq!(
+        config.debug.event_log,
+        Some(PathBuf::from(DEFAULT_EVENT_LOG))
+    );
+    assert!(config.debug.raw_timestamps);
+}
+
+#[test]
+fn retarget_updates_uri_and_module_and_keeps_timeout() {
+    let connection = ConnectionConfig {
+        connect_timeout_secs: 3,
+        ..Default::default()
+    };
+
+    let next = connection
+        .retarget("wss://relay.example.com", "other-room")
+        .expect("valid target");
+    assert_eq!(next.uri, "wss://relay.example.com");
+    assert_eq!(next.module, "other-room");
+    assert_eq!(next.connect_timeout_secs, 3);
+}
+
+#[test]
+fn retarget_rejects_bad_uri_or_module() {
+    let connection = ConnectionConfig::default();
+    for uri in [
+        "relay.example.com",
+        "ftp://relay",
+        "http://",
+        "http:// relay",
+    ] {
+        assert!(
+            matches!(
+                connection.retarget(uri, "room"),
+                Err(ConnectError::InvalidUri { .. })
+            ),
+            "{uri}"
+        );
+    }
+    for module in ["Room_1", "", "room 1", "room.1"] {
+        assert!(
+            matches!(
+                connection.retarget("http://relay:3000", module),
+                Err(ConnectError::InvalidModule { .. })
+            ),
+            "{module}"
+        );
+    }
+}
+
+#[test]
+fn retarget_accepts_underscores_in_module() {
+    let connection = ConnectionConfig::default();
+    let next = connection
+        .retarget("http://relay:3000", "shell_relay-2")
+        .expect("valid target");
+    assert_eq!(next.module, "shell_relay-2");
+}
+
+#[test]
+fn bots_flag_sets_bot_count_without_shifting_positionals() {
+    let mut config = Config::default();
+    config.apply_cli(&[
+        "--bots".to_string(),
+        "5".to_string(),
+        "my-module".to_string(),
+        "http://host:3000".to_string(),
+    ]);
+    assert_eq!(config.ai.bot_count, Some(5));
+    assert_eq!(config.connection.module, "my-module");
+    assert_eq!(config.connection.uri, "http://host:3000");
+
+    config.apply_cli(&["--bots=0".to_string()]);
+    assert_eq!(config.ai.bot_count, Some(0));
+    // Valor inválido mantém o anterior.
+    config.apply_cli(&["--bots".to_string(), "muitos".to_string()]);
+    assert_eq!(config.ai.bot_count, Some(0));
+}
+
+#[test]
+fn bot_count_remembers_whether_it_was_set() {
+    let mut config = Config::default();
+    assert_eq!(config.ai.bot_count, None);
+    assert_eq!(
+        config.ai.requested_bot_count(),
+        crate::ai::AI_BOT_COUNT as i64
+    );
+
+    // Pedir explicitamente o mesmo valor do padrão continua contando como pedido.
+    let default = crate::ai::AI_BOT_COUNT.to_string();
+    config.apply_cli(&["--bots".to_string(), default]);
+    assert_eq!(config.ai.bot_count, Some(crate::ai::AI_BOT_COUNT as i64));
+}
+
+#[test]
+fn persist_memory_flag_sets_the_memory_file() {
+    let mut config = Config::default();
+    assert_eq!(config.ai.memory_file, None);
+    config.apply_cli(&[
+        "--persist-memory".to_string(),
+        "memory.json".to_string(),
+        "my-module".to_string(),
+    ]);
+    assert_eq!(config.ai.memory_file, Some(PathBuf::from("memory.json")));
+    assert_eq!(config.connection.module, "my-module");
+}
+
+#[test]
+fn export_json_flag_sets_the_export_file() {
+    let mut config = Config::default();
+    assert_eq!(config.ui.export_json, None);
+    config.apply_cli(&[
+        "--export-json=chat.json".to_string(),
+        "my-module".to_string(),
+    ]);
+    assert_eq!(config.ui.export_json, Some(PathBuf::from("chat.json")));
+    assert_eq!(config.connection.module, "my-module");
+}
+
+#[test]
+fn ai_can_be_disabled_by_flag_or_env() {
+    let mut config = Config::default();
+    assert!(config.ai.enabled);
+    config.apply_cli(&["--no-ai".to_string(), "my-module".to_string()]);
+    assert!(!config.ai.enabled);
+    assert_eq!(config.connection.module, "my-module");
+
+    let mut config = Config::default();
+    config.apply_env(env_from(&[("SHELLRELAY_NO_AI", "on")]));
+    assert!(!config.ai.enabled);
+    config.apply_env(env_from(&[("SHELLRELAY_NO_AI", "0")]));
+    assert!(config.ai.enabled);
+}
+
+#[test]
+fn system_prompt_comes_from_env_and_file_flag() {
+    let mut config = Config::default();
+    config.apply_env(env_from(&[(
+        "OLLAMA_SYSTEM_PROMPT",
+        "You are a regular chat user.",
+    )]));
+    assert_eq!(config.ai.system_prompt, "You are a regular chat user.");
+
+    config.apply_cli(&[
+        "--system-prompt-file=prompt.txt".to_string(),
+        "my-module".to_string(),
+    ]);
+    assert_eq!(
+        config.ai.system_prompt_file,
+        Some(PathBuf::from("prompt.txt"))
+    );
+    assert_eq!(config.connection.module, "my-module");
+}
+
+#[test]
+fn personas_flag_sets_the_file_without_shifting_positionals() {
+    let mut config = Config::default();
+    config.apply_cli(&[
+        "--personas".to_string(),
+        "noir.toml".to_string(),
+        "my-module".to_string(),
+    ]);
+    assert_eq!(config.ai.personas, Some(PathBuf::from("noir.toml")));
+    assert_eq!(config.connection.module, "my-module");
+
+    config.apply_cli(&["--personas=scifi.toml".to_string()]);
+    assert_eq!(config.ai.personas, Some(PathBuf::from("scifi.toml")));
+}
+
+#[test]
+fn bots_env_is_overridden_by_cli() {
+    let mut config = Config::default();
+    config.apply_env(|key| (key == "SHELLRELAY_BOTS").then(|| " 7 ".to_string()));
+    assert_eq!(config.ai.bot_count, Some(7));
+    config.apply_cli(&["--bots=2".to_string()]);
+    assert_eq!(config.ai.bot_count, Some(2));
+}
+
+#[test]
+fn ollama_models_env_splits_the_list() {
+    let mut config = Config::default();
+    config.apply_env(|key| (key == "OLLAMA_MODELS").then(|| "mistral:7b, llama3,,".to_string()));
+    assert_eq!(config.ai.models, vec!["mistral:7b", "llama3"]);
+}
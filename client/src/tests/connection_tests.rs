@@ -0,0 +1,140 @@
+use std::{
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use super::{
+    ConnectError, RECONNECT_BASE_DELAY, RECONNECT_MAX_DELAY, ReconnectAction, ReconnectBackoff,
+    connect_with_timeout, reconnect_action, reconnect_delay, spawn_connect_attempt,
+};
+
+#[test]
+fn connect_attempt_is_delivered_while_someone_is_waiting() {
+    let (discarded_tx, discarded_rx) = mpsc::channel();
+    let attempt = spawn_connect_attempt(|| Ok(7), move |stale| discarded_tx.send(stale).unwrap());
+    let result = attempt
+        .recv_timeout(Duration::from_secs(1))
+        .expect("attempt result");
+    assert_eq!(result.ok(), Some(7));
+    assert!(
+        discarded_rx
+            .recv_timeout(Duration::from_millis(50))
+            .is_err()
+    );
+}
+
+#[test]
+fn connect_attempt_is_discarded_once_nobody_waits() {
+    let (release_tx, release_rx) = mpsc::channel::<()>();
+    let (discarded_tx, discarded_rx) = mpsc::channel();
+    let attempt = spawn_connect_attempt(
+        move || {
+            let _ = release_rx.recv();
+            Ok(7)
+        },
+        move |stale| discarded_tx.send(stale).unwrap(),
+    );
+    // Sessão cancelada antes de a conexão abrir.
+    drop(attempt);
+    release_tx.send(()).unwrap();
+    assert_eq!(discarded_rx.recv_timeout(Duration::from_secs(1)), Ok(7));
+}
+
+#[test]
+fn connect_with_timeout_returns_connection_when_fast() {
+    let res = connect_with_timeout("http://fake", Duration::from_secs(1), || {
+        Ok::<_, String>(42)
+    });
+    assert_eq!(res.ok(), Some(42));
+}
+
+#[test]
+fn connect_with_timeout_reports_uri_and_seconds_on_timeout() {
+    let res = connect_with_timeout("http://fake:3000", Duration::from_millis(20), || {
+        thread::sleep(Duration::from_millis(500));
+        Ok::<_, String>(())
+    });
+    let err = res.expect_err("slow connector must time out");
+    assert!(matches!(err, ConnectError::Timeout { .. }));
+    assert!(
+        err.to_string()
+            .starts_with("could not connect to http://fake:3000 within")
+    );
+}
+
+#[test]
+fn connect_with_timeout_propagates_connector_error() {
+    let res = connect_with_timeout("http://fake", Duration::from_secs(1), || {
+        Err::<(), _>("refused".to_string())
+    });
+    let err = res.expect_err("connector error must propagate");
+    assert_eq!(err.to_string(), "could not connect to http://fake: refused");
+}
+
+#[test]
+fn reconnect_delay_doubles_up_to_the_cap() {
+    assert_eq!(reconnect_delay(0), RECONNECT_BASE_DELAY);
+    assert_eq!(reconnect_delay(1), RECONNECT_BASE_DELAY * 2);
+    assert_eq!(reconnect_delay(2), RECONNECT_BASE_DELAY * 4);
+    assert_eq!(reconnect_delay(10), RECONNECT_MAX_DELAY);
+    assert_eq!(reconnect_delay(u32::MAX), RECONNECT_MAX_DELAY);
+}
+
+#[test]
+fn reconnect_action_schedules_waits_and_retries_with_backoff() {
+    let now = Instant::now();
+    // Conexão viva: nada a fazer.
+    assert_eq!(
+        reconnect_action(false, None, false, now),
+        ReconnectAction::Wait
+    );
+    // Acabou de cair: agenda, sem tentar na hora.
+    assert_eq!(
+        reconnect_action(true, None, false, now),
+        ReconnectAction::Schedule
+    );
+
+    let mut backoff = ReconnectBackoff::new(now);
+    let before = now + RECONNECT_BASE_DELAY - Duration::from_millis(1);
+    let due = now + RECONNECT_BASE_DELAY;
+    assert_eq!(
+        reconnect_action(true, Some(&backoff), false, before),
+        ReconnectAction::Wait
+    );
+    assert_eq!(
+        reconnect_action(true, Some(&backoff), false, due),
+        ReconnectAction::Attempt
+    );
+    // Uma tentativa por vez.
+    assert_eq!(
+        reconnect_action(true, Some(&backoff), true, due),
+        ReconnectAction::Wait
+    );
+
+    // Falhou: a próxima espera dobra.
+    backoff.record_failure(due);
+    assert_eq!(backoff.failures(), 1);
+    assert_eq!(backoff.remaining(due), RECONNECT_BASE_DELAY * 2);
+    assert_eq!(
+        reconnect_action(true, Some(&backoff), false, due + RECONNECT_BASE_DELAY),
+        ReconnectAction::Wait
+    );
+    assert_eq!(
+        reconnect_action(true, Some(&backoff), false, due + RECONNECT_BASE_DELAY * 2),
+        ReconnectAction::Attempt
+    );
+}
+
+#[test]
+fn reconnect_backoff_schedule_runs_2_4_8_up_to_the_cap() {
+    let mut now = Instant::now();
+    let mut backoff = ReconnectBackoff::new(now);
+    let mut schedule = vec![backoff.remaining(now).as_secs()];
+    for _ in 0..6 {
+        now += backoff.remaining(now);
+        backoff.record_failure(now);
+        schedule.push(backoff.remaining(now).as_secs());
+    }
+    assert_eq!(schedule, vec![2, 4, 8, 16, 32, 60, 60]);
+}
@@ -0,0 +1,67 @@
+use rand::{SeedableRng, rngs::StdRng};
+
+use super::{DiceError, DiceSpec, MAX_DICE, format_roll, parse_dice, roll_dice};
+
+#[test]
+fn parse_dice_accepts_common_notations() {
+    assert_eq!(parse_dice("2d6"), Ok(DiceSpec { count: 2, sides: 6 }));
+    assert_eq!(parse_dice(" 3D8 "), Ok(DiceSpec { count: 3, sides: 8 }));
+    assert_eq!(
+        parse_dice("d20"),
+        Ok(DiceSpec {
+            count: 1,
+            sides: 20
+        })
+    );
+}
+
+#[test]
+fn parse_dice_rejects_malformed_specs() {
+    for raw in [
+        "", "2", "d", "2d", "2x6", "-1d6", "2d6+1", "ad6", "2d6d6", "🎲d6",
+    ] {
+        assert!(
+            matches!(parse_dice(raw), Err(DiceError::Invalid(_))),
+            "{raw:?}"
+        );
+    }
+}
+
+#[test]
+fn parse_dice_rejects_out_of_range_values() {
+    assert_eq!(parse_dice("0d6"), Err(DiceError::TooManyDice));
+    assert_eq!(
+        parse_dice(&format!("{}d6", MAX_DICE + 1)),
+        Err(DiceError::TooManyDice)
+    );
+    assert_eq!(parse_dice("99999999999d6"), Err(DiceError::TooManyDice));
+    assert_eq!(parse_dice("1d1"), Err(DiceError::BadSides));
+    assert_eq!(parse_dice("1d0"), Err(DiceError::BadSides));
+    assert_eq!(parse_dice("1d1001"), Err(DiceError::BadSides));
+}
+
+#[test]
+fn roll_dice_stays_in_range_and_is_reproducible_with_seed() {
+    let spec = DiceSpec {
+        count: MAX_DICE,
+        sides: 6,
+    };
+    let rolls = roll_dice(spec, &mut StdRng::seed_from_u64(7));
+    assert_eq!(rolls.len(), MAX_DICE as usize);
+    assert!(rolls.iter().all(|r| (1..=6).contains(r)));
+    assert_eq!(rolls, roll_dice(spec, &mut StdRng::seed_from_u64(7)));
+}
+
+#[test]
+fn format_roll_lists_each_die_and_total() {
+    let two_d6 = DiceSpec { count: 2, sides: 6 };
+    assert_eq!(
+        format_roll("Rafael", two_d6, &[4, 3]),
+        "🎲 Rafael rolled 2d6: 4+3 = 7"
+    );
+    let d20 = DiceSpec {
+        count: 1,
+        sides: 20,
+    };
+    assert_eq!(format_roll("Lia", d20, &[17]), "🎲 Lia rolled 1d20: 17");
+}
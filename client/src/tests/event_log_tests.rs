@@ -0,0 +1,56 @@
+use std::{
+    io::{self, Write},
+    sync::{Arc, Mutex},
+};
+
+use crate::state::AppStateBuilder;
+
+use super::{EventLog, format_entry};
+
+/// Buffer compartilhado para inspecionar o que foi escrito.
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().expect("buffer").extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn record_writes_one_line_per_callback() {
+    let buffer = SharedBuffer::default();
+    let log = EventLog::with_sink(buffer.clone());
+    let state = AppStateBuilder::new()
+        .with_user("id_a", "Lia", true)
+        .with_message(1, "id_a", "oi")
+        .build();
+
+    log.record("message", "insert", "id=1", &state);
+    log.record("user", "update", "id_a", &state);
+    log.record("message", "delete", "id=1", &state);
+
+    let written = String::from_utf8(buffer.0.lock().expect("buffer").clone()).expect("utf8");
+    let lines: Vec<&str> = written.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].contains("\tmessage\tinsert\tid=1\t"));
+    assert!(lines[1].contains("\tuser\tupdate\tid_a\t"));
+}
+
+#[test]
+fn format_entry_includes_resulting_state_sizes() {
+    let state = AppStateBuilder::new()
+        .with_user("id_a", "Lia", true)
+        .with_message(1, "id_a", "oi")
+        .with_message(2, "id_a", "tudo bem?")
+        .build();
+    assert_eq!(
+        format_entry(42, "message", "insert", "id=2", &state),
+        "42\tmessage\tinsert\tid=2\tmessages=2 users=1 direct=0 system=0 pending=0"
+    );
+}
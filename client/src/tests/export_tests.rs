@@ -0,0 +1,135 @@
+use chrono::{Local, TimeZone};
+use serde_json::json;
+
+use super::{chat_export, timestamped_export_path, write_transcript};
+use crate::ui::ui_state::{UiMessage, UiUser};
+
+fn render(messages: &[UiMessage], users: &[UiUser]) -> String {
+    let mut out = Vec::new();
+    write_transcript(&mut out, messages, users).expect("write to buffer");
+    String::from_utf8(out).expect("utf-8 transcript")
+}
+
+#[test]
+fn transcript_resolves_names_and_formats_timestamps() {
+    let users = vec![UiUser {
+        identity: "c200abc".to_string(),
+        name: "Ana".to_string(),
+        online: true,
+        ..Default::default()
+    }];
+    let messages = vec![
+        UiMessage {
+            id: 1,
+            sender: "c200abc".to_string(),
+            text: "oi, tudo bem?".to_string(),
+            sent_at: "2026-10-17T14:05:09.123456Z".to_string(),
+            ..Default::default()
+        },
+        UiMessage {
+            id: 2,
+            sender: "c200def".to_string(),
+            text: "tudo\ne com você?".to_string(),
+            sent_at: "2026-10-17T14:06:00Z".to_string(),
+            is_edited: true,
+            ..Default::default()
+        },
+        UiMessage {
+            id: 3,
+            sender: "c200abc".to_string(),
+            text: "ainda enviando".to_string(),
+            is_pending: true,
+            ..Default::default()
+        },
+    ];
+
+    assert_eq!(
+        render(&messages, &users),
+        "[17/10/2026 14:05] Ana: oi, tudo bem?\n\
+         [17/10/2026 14:06] c200def: tudo (edited)\n    e com você?\n"
+    );
+}
+
+#[test]
+fn system_and_announcement_lines_keep_their_labels() {
+    let messages = vec![
+        UiMessage {
+            id: 1,
+            sender: "System".to_string(),
+            text: "Ana connected".to_string(),
+            ..Default::default()
+        },
+        UiMessage {
+            id: 2,
+            sender: "host".to_string(),
+            text: "manutenção às 22h".to_string(),
+            is_announcement: true,
+            ..Default::default()
+        },
+    ];
+    assert_eq!(
+        render(&messages, &[]),
+        "System: Ana connected\nAnnouncement from host: manutenção às 22h\n"
+    );
+}
+
+#[test]
+fn export_path_carries_date_and_time() {
+    let now = Local.with_ymd_and_hms(2026, 10, 17, 14, 5, 0).unwrap();
+    assert_eq!(
+        timestamped_export_path(now, "txt").to_str(),
+        Some("shellrelay-chat-20261017-140500.txt")
+    );
+}
+
+#[test]
+fn json_export_keeps_raw_fields_and_resolved_names() {
+    let users = vec![UiUser {
+        identity: "c200abc".to_string(),
+        name: "Ana".to_string(),
+        online: true,
+        ..Default::default()
+    }];
+    let messages = vec![
+        UiMessage {
+            id: 7,
+            sender: "c200abc".to_string(),
+            text: "oi".to_string(),
+            sent_at: "2026-10-17T14:05:09Z".to_string(),
+            reactions: vec![("👍".to_string(), 2)],
+            ..Default::default()
+        },
+        UiMessage {
+            id: 8,
+            sender: "c200abc".to_string(),
+            text: "ainda enviando".to_string(),
+            is_pending: true,
+            ..Default::default()
+        },
+    ];
+
+    let value = serde_json::to_value(chat_export(&messages, &users)).expect("serialize export");
+    assert_eq!(
+        value,
+        json!({
+            "users": [{
+                "identity": "c200abc",
+                "name": "Ana",
+                "online": true,
+                "status": "",
+                "title": "",
+            }],
+            "messages": [{
+                "id": 7,
+                "sender": "c200abc",
+                "sender_name": "Ana",
+                "text": "oi",
+                "sent_at": "2026-10-17T14:05:09Z",
+                "is_announcement": false,
+                "is_pending": false,
+                "is_edited": false,
+                "reactions": [["👍", 2]],
+            }],
+        })
+    );
+}
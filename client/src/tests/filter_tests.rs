@@ -0,0 +1,63 @@
+use std::borrow::Cow;
+
+use super::{mask_for_display, mask_profanity, parse_filter_words};
+
+fn words(list: &[&str]) -> Vec<String> {
+    list.iter().map(|w| w.to_string()).collect()
+}
+
+#[test]
+fn masks_whole_words_ignoring_case_and_keeps_length() {
+    let filter = words(&["droga", "merda"]);
+    assert_eq!(
+        mask_profanity("Que DROGA, isso é merda!", &filter),
+        "Que *****, isso é *****!"
+    );
+    let masked = mask_profanity("Droga", &filter);
+    assert_eq!(masked.chars().count(), "Droga".chars().count());
+}
+
+#[test]
+fn partial_words_are_not_masked() {
+    let filter = words(&["ass", "cu"]);
+    assert_eq!(
+        mask_profanity("a class passou pela cuia", &filter),
+        "a class passou pela cuia"
+    );
+    assert_eq!(mask_profanity("ass-cu", &filter), "***-**");
+}
+
+#[test]
+fn accented_words_mask_one_asterisk_per_letter() {
+    let filter = words(&["porcaria", "maldição"]);
+    assert_eq!(mask_profanity("MALDIÇÃO!", &filter), "********!");
+}
+
+#[test]
+fn empty_filter_leaves_text_untouched() {
+    assert_eq!(mask_profanity("nada muda", &[]), "nada muda");
+}
+
+#[test]
+fn filter_file_skips_blank_lines_and_comments() {
+    assert_eq!(
+        parse_filter_words("# lista da live\ndroga\n\n  merda  \n"),
+        words(&["droga", "merda"])
+    );
+}
+
+#[test]
+fn filter_file_words_are_lowercased_once() {
+    let filter = parse_filter_words("DROGA\nMaldição\n");
+    assert_eq!(filter, words(&["droga", "maldição"]));
+    assert_eq!(
+        mask_profanity("droga, MALDIÇÃO", &filter),
+        "*****, ********"
+    );
+}
+
+#[test]
+fn display_mask_borrows_without_a_filter() {
+    assert!(matches!(mask_for_display("oi", &[]), Cow::Borrowed("oi")));
+    assert_eq!(mask_for_display("oi droga", &words(&["droga"])), "oi *****");
+}
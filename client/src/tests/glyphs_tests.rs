@@ -0,0 +1,56 @@
+use super::{ASCII_GLYPHS, Glyphs, UNICODE_GLYPHS};
+
+fn all_strings(glyphs: &Glyphs) -> Vec<&'static str> {
+    let border = glyphs.border;
+    let mut all = vec![
+        glyphs.online,
+        glyphs.offline,
+        glyphs.up,
+        glyphs.down,
+        glyphs.ellipsis,
+        glyphs.reload,
+        glyphs.separator,
+        glyphs.footer_line,
+        border.top_left,
+        border.top_right,
+        border.bottom_left,
+        border.bottom_right,
+        border.vertical_left,
+        border.vertical_right,
+        border.horizontal_top,
+        border.horizontal_bottom,
+    ];
+    all.extend(glyphs.spinner);
+    let bars = glyphs.bars;
+    all.extend([
+        bars.full,
+        bars.seven_eighths,
+        bars.three_quarters,
+        bars.five_eighths,
+        bars.half,
+        bars.three_eighths,
+        bars.one_quarter,
+        bars.one_eighth,
+        bars.empty,
+    ]);
+    all
+}
+
+#[test]
+fn accessibility_mode_selects_ascii_only_glyphs() {
+    let glyphs = Glyphs::select(true);
+    assert_eq!(glyphs, &ASCII_GLYPHS);
+    assert!(
+        all_strings(glyphs).iter().all(|s| s.is_ascii()),
+        "{glyphs:?}"
+    );
+    assert_eq!((glyphs.online, glyphs.offline), ("[*]", "[ ]"));
+    assert_eq!((glyphs.up, glyphs.down), ("^", "v"));
+}
+
+#[test]
+fn default_mode_keeps_unicode_glyphs() {
+    let glyphs = Glyphs::select(false);
+    assert_eq!(glyphs, &UNICODE_GLYPHS);
+    assert!(!all_strings(glyphs).iter().all(|s| s.is_ascii()));
+}
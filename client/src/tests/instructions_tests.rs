@@ -0,0 +1,145 @@
+use ratatui::style::Color;
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    config::UiConfig,
+    ui::{
+        glyphs::{ASCII_GLYPHS, UNICODE_GLYPHS},
+        theme::Theme,
+    },
+};
+
+use super::{FooterStyle, InstructionItem, instruction_spans, instruction_text_width};
+
+fn line_text(spans: &[ratatui::text::Span<'_>]) -> String {
+    spans.iter().map(|span| span.content.as_ref()).collect()
+}
+
+#[test]
+fn instruction_spans_use_theme_colors() {
+    let theme = Theme {
+        footer_line: Color::Red,
+        footer_label: Color::Green,
+        footer_key: Color::Blue,
+        dim: Color::DarkGray,
+        whisper: Color::Magenta,
+        ..Theme::default()
+    };
+    let items = [InstructionItem {
+        label: "Send",
+        key: "Enter",
+    }];
+    let config = UiConfig::default();
+    let spans = instruction_spans(
+        &items,
+        40,
+        &theme,
+        &FooterStyle::new(&config, &UNICODE_GLYPHS),
+    );
+
+    let color_of = |text: &str| {
+        spans
+            .iter()
+            .find(|span| span.content.contains(text))
+            .and_then(|span| span.style.fg)
+    };
+    assert_eq!(color_of("Send"), Some(Color::Green));
+    assert_eq!(color_of("<Enter>"), Some(Color::Blue));
+    assert_eq!(color_of("\u{2500}"), Some(Color::Red));
+}
+
+#[test]
+fn unknown_theme_name_falls_back_to_default() {
+    assert_eq!(Theme::by_name("nope"), Theme::default());
+    assert_ne!(Theme::by_name("AMBER"), Theme::default());
+}
+
+#[test]
+fn instruction_spans_use_ascii_footer_line_in_accessibility_mode() {
+    let items = [InstructionItem {
+        label: "Send",
+        key: "Enter",
+    }];
+    let config = UiConfig::default();
+    let spans = instruction_spans(
+        &items,
+        40,
+        &Theme::high_contrast(),
+        &FooterStyle::new(&config, &ASCII_GLYPHS),
+    );
+    assert!(spans.iter().all(|span| span.content.is_ascii()));
+    assert!(spans.iter().any(|span| span.content.contains("---")));
+}
+
+#[test]
+fn instruction_text_width_counts_display_columns() {
+    let items = [InstructionItem {
+        label: "送信",
+        key: "Enter",
+    }];
+    // "送信" ocupa 4 colunas + espaço + "[Enter]".
+    let config = UiConfig::default();
+    let style = FooterStyle::new(&config, &UNICODE_GLYPHS);
+    assert_eq!(instruction_text_width(&items, &style), 4 + 1 + 7);
+}
+
+#[test]
+fn footer_uses_configured_separator_brackets_and_rule() {
+    let config = UiConfig {
+        footer_separator: " | ".to_string(),
+        footer_brackets: "[]".to_string(),
+        footer_rule: "=".to_string(),
+        ..UiConfig::default()
+    };
+    let items = [
+        InstructionItem {
+            label: "Send",
+            key: "Enter",
+        },
+        InstructionItem {
+            label: "Quit",
+            key: "Esc",
+        },
+    ];
+    let spans = instruction_spans(
+        &items,
+        40,
+        &Theme::default(),
+        &FooterStyle::new(&config, &UNICODE_GLYPHS),
+    );
+    let text = line_text(&spans);
+    assert!(text.contains("Send [Enter] | Quit [Esc]"));
+    assert!(text.starts_with("==="));
+}
+
+#[test]
+fn footer_drops_items_that_overflow_narrow_terminals() {
+    let config = UiConfig::default();
+    let style = FooterStyle::new(&config, &UNICODE_GLYPHS);
+    let items = [
+        InstructionItem {
+            label: "Send",
+            key: "Enter",
+        },
+        InstructionItem {
+            label: "Menu",
+            key: "F1",
+        },
+        InstructionItem {
+            label: "Quit",
+            key: "Esc",
+        },
+    ];
+    for width in [0, 5, 14, 22, 30] {
+        let spans = instruction_spans(&items, width, &Theme::default(), &style);
+        let text = line_text(&spans);
+        assert!(text.width() <= width.max(1), "{width}: {text:?}");
+        assert!(text.contains('…'), "{width}: {text:?}");
+    }
+    let text = line_text(&instruction_spans(&items, 22, &Theme::default(), &style));
+    assert_eq!(
+        text,
+        "\u{2500}\u{2500} Send <Enter>  \u{2026} \u{2500}\u{2500}"
+    );
+}
@@ -0,0 +1,73 @@
+use crate::ui::ui_state::UiUser;
+
+use super::{MessageSegment, complete_name, mentioned_names, name_candidates, split_mentions};
+
+#[test]
+fn split_mentions_separates_plain_and_known_mentions() {
+    let segments = split_mentions("oi @Rafael, tudo bem?", &["Rafael", "Lia"]);
+    assert_eq!(
+        segments,
+        vec![
+            MessageSegment::Plain("oi "),
+            MessageSegment::Mention("@Rafael"),
+            MessageSegment::Plain(", tudo bem?"),
+        ]
+    );
+}
+
+#[test]
+fn split_mentions_keeps_unknown_names_and_partial_words_as_plain() {
+    let names = ["Lia"];
+    assert_eq!(
+        split_mentions("@Ninguem e @Liana", &names),
+        vec![MessageSegment::Plain("@Ninguem e @Liana")]
+    );
+}
+
+#[test]
+fn split_mentions_is_case_insensitive_and_prefers_longest_name() {
+    let names = ["Ana", "Ana Clara"];
+    assert_eq!(
+        split_mentions("@ana clara chegou", &names),
+        vec![
+            MessageSegment::Mention("@ana clara"),
+            MessageSegment::Plain(" chegou"),
+        ]
+    );
+}
+
+#[test]
+fn mentioned_names_returns_unique_known_names_in_order() {
+    let names = ["Kael", "Lyria"];
+    assert_eq!(
+        mentioned_names("@lyria e @Kael, cade @Lyria?", &names),
+        vec!["Lyria", "Kael"]
+    );
+}
+
+fn users(entries: &[(&str, bool)]) -> Vec<UiUser> {
+    entries
+        .iter()
+        .map(|(name, online)| UiUser {
+            identity: format!("id-{name}"),
+            name: name.to_string(),
+            online: *online,
+            ..Default::default()
+        })
+        .collect()
+}
+
+#[test]
+fn complete_name_matches_online_users_case_insensitively() {
+    let users = users(&[("Aelric", true), ("Aeryn", false), ("Lyria", true)]);
+    assert_eq!(complete_name("ae", &users), Some("Aelric".to_string()));
+    assert_eq!(complete_name("LY", &users), Some("Lyria".to_string()));
+    assert_eq!(complete_name("aer", &users), None);
+    assert_eq!(complete_name("", &users), None);
+}
+
+#[test]
+fn name_candidates_keep_user_list_order() {
+    let users = users(&[("Aelric", true), ("Ana", true), ("Bruno", true)]);
+    assert_eq!(name_candidates("a", &users), vec!["Aelric", "Ana"]);
+}
@@ -2,35 +2,91 @@ use std::sync::{Arc, Mutex};
 
 use crate::{
     state::AppState,
-    ui::ui_state::{UiMessage, UiUser},
+    ui::ui_state::{PendingMessage, UiDirectMessage, UiMessage, UiUser},
 };
 
-use super::{SYSTEM_MESSAGE_ID_BASE, add_local_system_message, display_user_name, short_identity};
+use super::{
+    ConfirmedMessage, OPTIMISTIC_ECHO_WINDOW_MICROS, PRESENCE_BURST_THRESHOLD,
+    SYSTEM_MESSAGE_ID_BASE, SeenMessages, add_local_system_message, aggregate_reactions,
+    anonymous_name, clear_server_state, display_user_name, pending_matches, presence_messages,
+    push_local_system_message, reconcile_pending_messages, reducer_rejection_notice,
+    remove_local_system_message, update_local_system_message, visible_direct_messages,
+};
 
 #[test]
-fn short_identity_truncates_long_values() {
-    assert_eq!(short_identity("abc"), "abc");
-    assert_eq!(
-        short_identity("abcdefghijklmnopqrstuvwxyz"),
-        "abcdefghij..uvwxyz"
-    );
+fn anonymous_name_is_stable_and_differs_between_identities() {
+    let first = anonymous_name("c200a1b2c3d4e5f6");
+    let second = anonymous_name("c200ffeeddccbbaa");
+    assert_eq!(first, anonymous_name("c200a1b2c3d4e5f6"));
+    assert_ne!(first, second);
+    assert!(first.starts_with("Anon-") && first.len() == "Anon-".len() + 4);
 }
 
 #[test]
-fn display_user_name_prefers_name_and_falls_back_to_identity() {
+fn display_user_name_prefers_name_and_falls_back_to_anonymous_name() {
     let named = UiUser {
         identity: "id_user".to_string(),
         name: "Rafael".to_string(),
         online: true,
+        ..Default::default()
     };
     let unnamed = UiUser {
         identity: "abcdefghijklmnopqrstuvwxyz".to_string(),
         name: "   ".to_string(),
         online: true,
+        ..Default::default()
     };
 
     assert_eq!(display_user_name(&named), "Rafael");
-    assert_eq!(display_user_name(&unnamed), "abcdefghij..uvwxyz");
+    let server_default = UiUser {
+        name: "Anônimo".to_string(),
+        ..unnamed.clone()
+    };
+
+    assert_eq!(
+        display_user_name(&unnamed),
+        anonymous_name("abcdefghijklmnopqrstuvwxyz")
+    );
+    assert_eq!(
+        display_user_name(&server_default),
+        display_user_name(&unnamed)
+    );
+}
+
+#[test]
+fn seen_messages_detects_non_contiguous_and_late_ids_exactly_once() {
+    let mut seen = SeenMessages::default();
+    assert_eq!(seen.take_new([10, 11]), None);
+
+    assert_eq!(seen.take_new([10, 11, 15, 40]), Some(vec![15, 40]));
+    // Id menor que o maior já visto, chegando depois: ainda é novo.
+    assert_eq!(seen.take_new([10, 11, 15, 40, 12]), Some(vec![12]));
+    assert_eq!(seen.take_new([10, 11, 12, 15, 40]), Some(vec![]));
+    // Retenção apagou as antigas: nada é reprocessado.
+    assert_eq!(seen.take_new([15, 40, 41]), Some(vec![41]));
+
+    seen.reset();
+    assert_eq!(seen.take_new([1, 2]), None);
+    assert_eq!(seen.take_new([1, 2, 3]), Some(vec![3]));
+}
+
+#[test]
+fn local_system_message_can_be_edited_and_removed_by_id() {
+    let state = Arc::new(Mutex::new(AppState::default()));
+    add_local_system_message(&state, "System", "antes");
+    let id = push_local_system_message(&state, "Aelric", "Ola…");
+
+    update_local_system_message(&state, id, "Ola, tudo bem…");
+    {
+        let guard = state.lock().expect("lock state");
+        let last = guard.ui.messages.last().expect("partial message");
+        assert_eq!((last.id, last.text.as_str()), (id, "Ola, tudo bem…"));
+    }
+
+    remove_local_system_message(&state, id);
+    let guard = state.lock().expect("lock state");
+    assert_eq!(guard.ui.messages.len(), 1);
+    assert_eq!(guard.ui.messages[0].text, "antes");
 }
 
 #[test]
@@ -43,6 +99,7 @@ fn add_local_system_message_appends_and_rebuilds_message_list() {
             sender: "id_user".to_string(),
             text: "mensagem remota".to_string(),
             sent_at: "2026-02-12T10:00:00Z".to_string(),
+            ..Default::default()
         });
     }
 
@@ -56,3 +113,191 @@ fn add_local_system_message_appends_and_rebuilds_message_list() {
     assert_eq!(guard.ui.messages[0].id, 42);
     assert!(guard.ui.messages[1].id >= SYSTEM_MESSAGE_ID_BASE);
 }
+
+#[test]
+fn clear_server_state_drops_remote_data_but_keeps_local_notices() {
+    let state = Arc::new(Mutex::new(AppState::default()));
+    {
+        let mut guard = state.lock().expect("lock state");
+        guard.status = true;
+        guard.ui.users_presence_initialized = true;
+        guard.ui.users.push(UiUser {
+            identity: "id_user".to_string(),
+            name: "Lyria".to_string(),
+            online: true,
+            ..Default::default()
+        });
+        guard.ui.messages.push(UiMessage {
+            id: 42,
+            sender: "id_user".to_string(),
+            text: "mensagem remota".to_string(),
+            ..Default::default()
+        });
+    }
+    add_local_system_message(
+        &state,
+        "System",
+        "Connecting to http://other:3000 (chat)...",
+    );
+
+    clear_server_state(&state);
+
+    let guard = state.lock().expect("lock state");
+    assert!(!guard.status);
+    assert!(guard.ui.users.is_empty());
+    assert!(!guard.ui.users_presence_initialized);
+    assert_eq!(guard.ui.messages.len(), 1);
+    assert_eq!(guard.ui.messages[0].sender, "System");
+}
+
+#[test]
+fn reducer_rejection_notice_names_the_action_and_reason() {
+    assert_eq!(
+        reducer_rejection_notice("send_message", "empty message"),
+        "message rejected: empty message"
+    );
+    assert_eq!(
+        reducer_rejection_notice("send_message", "not in chat: choose a name first"),
+        "message rejected: not in chat: choose a name first"
+    );
+    assert_eq!(
+        reducer_rejection_notice("set_name", "empty name"),
+        "name rejected: empty name"
+    );
+    assert_eq!(
+        reducer_rejection_notice("announce", "only the host can do this"),
+        "announcement rejected: only the host can do this"
+    );
+    assert_eq!(
+        reducer_rejection_notice("set_title", "only the host can assign this title"),
+        "title rejected: only the host can assign this title"
+    );
+}
+
+fn pending(text: &str, sent_at_micros: i64) -> PendingMessage {
+    PendingMessage {
+        local_id: SYSTEM_MESSAGE_ID_BASE + 1,
+        sender: "id_me".to_string(),
+        text: text.to_string(),
+        sent_at_micros,
+        after_id: 10,
+    }
+}
+
+fn row<'a>(id: u64, sender: &'a str, text: &'a str, sent_at_micros: i64) -> ConfirmedMessage<'a> {
+    ConfirmedMessage {
+        id,
+        sender,
+        text,
+        sent_at_micros,
+    }
+}
+
+#[test]
+fn pending_matches_same_sender_text_and_time_window() {
+    let p = pending("oi pessoal", 1_000_000);
+    assert!(pending_matches(
+        &p,
+        &row(11, "id_me", "oi pessoal", 1_200_000)
+    ));
+    assert!(pending_matches(
+        &p,
+        &row(11, "id_me", "  oi pessoal ", 1_200_000)
+    ));
+}
+
+#[test]
+fn pending_does_not_match_near_duplicates_other_senders_old_rows_or_late_rows() {
+    let p = pending("oi", 1_000_000);
+    assert!(!pending_matches(&p, &row(11, "id_me", "oi!", 1_000_000)));
+    assert!(!pending_matches(&p, &row(11, "id_me", "Oi", 1_000_000)));
+    assert!(!pending_matches(&p, &row(11, "id_other", "oi", 1_000_000)));
+    // Mesmo texto enviado antes (ID já conhecido) não confirma o eco novo.
+    assert!(!pending_matches(&p, &row(10, "id_me", "oi", 1_000_000)));
+    let late = 1_000_000 + OPTIMISTIC_ECHO_WINDOW_MICROS + 1;
+    assert!(!pending_matches(&p, &row(11, "id_me", "oi", late)));
+}
+
+#[test]
+fn reconcile_replaces_one_pending_per_row_and_expires_stale_ones() {
+    let mut queue = vec![pending("oi", 1_000_000), pending("oi", 1_100_000)];
+    reconcile_pending_messages(&mut queue, &[row(11, "id_me", "oi", 1_050_000)], 1_200_000);
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue[0].sent_at_micros, 1_100_000);
+
+    let much_later = 1_100_000 + OPTIMISTIC_ECHO_WINDOW_MICROS * 2 + 1;
+    reconcile_pending_messages(&mut queue, &[], much_later);
+    assert!(queue.is_empty());
+}
+
+fn names(prefix: &str, count: usize) -> Vec<String> {
+    (0..count).map(|i| format!("{prefix}{i}")).collect()
+}
+
+#[test]
+fn presence_messages_keep_individual_lines_up_to_threshold() {
+    let connected = names("on", PRESENCE_BURST_THRESHOLD - 1);
+    let disconnected = names("off", 1);
+    let lines = presence_messages(&connected, &disconnected);
+    assert_eq!(lines.len(), PRESENCE_BURST_THRESHOLD);
+    assert_eq!(lines[0], "on0 connected");
+    assert_eq!(lines.last().map(String::as_str), Some("off0 disconnected"));
+}
+
+#[test]
+fn presence_messages_collapse_bursts_into_one_summary() {
+    let connected = names("on", PRESENCE_BURST_THRESHOLD);
+    let lines = presence_messages(&connected, &names("off", 1));
+    assert_eq!(
+        lines,
+        vec![format!(
+            "{PRESENCE_BURST_THRESHOLD} users connected, 1 user disconnected"
+        )]
+    );
+
+    let only_connected = presence_messages(&names("on", 12), &[]);
+    assert_eq!(only_connected, vec!["12 users connected".to_string()]);
+}
+
+fn dm(id: u64, sender: &str, recipient: &str) -> UiDirectMessage {
+    UiDirectMessage {
+        id,
+        sender: sender.to_string(),
+        recipient: recipient.to_string(),
+        text: format!("dm {id}"),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn visible_direct_messages_keeps_only_my_conversations_in_order() {
+    let all = vec![
+        dm(3, "id_lia", "id_me"),
+        dm(1, "id_me", "id_kael"),
+        dm(2, "id_lia", "id_kael"),
+    ];
+    let ids: Vec<u64> = visible_direct_messages(all.clone(), Some("id_me"))
+        .iter()
+        .map(|m| m.id)
+        .collect();
+    assert_eq!(ids, vec![1, 3]);
+    assert!(visible_direct_messages(all, None).is_empty());
+}
+
+#[test]
+fn aggregate_reactions_counts_per_message_in_first_seen_order() {
+    let rows = vec![
+        (3, 10, "🎉".to_string()),
+        (1, 10, "👍".to_string()),
+        (2, 10, "👍".to_string()),
+        (4, 11, "👍".to_string()),
+        (5, 10, "👍".to_string()),
+    ];
+    let aggregated = aggregate_reactions(rows);
+    assert_eq!(
+        aggregated.get(&10),
+        Some(&vec![("👍".to_string(), 3), ("🎉".to_string(), 1)])
+    );
+    assert_eq!(aggregated.get(&11), Some(&vec![("👍".to_string(), 1)]));
+    assert_eq!(aggregated.get(&12), None);
+}
@@ -0,0 +1,38 @@
+use super::visible_input_window;
+
+#[test]
+fn short_input_is_shown_whole() {
+    assert_eq!(visible_input_window("Rafa", 4, 10), ("Rafa".to_string(), 4));
+    assert_eq!(visible_input_window("Rafa", 1, 10), ("Rafa".to_string(), 1));
+    assert_eq!(visible_input_window("", 0, 10), (String::new(), 0));
+}
+
+#[test]
+fn long_input_scrolls_to_keep_the_cursor_visible() {
+    let name = "Seraphina-do-Vale";
+    // Cursor no fim: mostra a cauda, deixando a última coluna para o cursor.
+    assert_eq!(
+        visible_input_window(name, name.len(), 8),
+        ("do-Vale".to_string(), 7)
+    );
+    // Cursor no começo: volta a mostrar o início.
+    assert_eq!(
+        visible_input_window(name, 0, 8),
+        ("Seraphin".to_string(), 0)
+    );
+    // Cursor no meio: a janela termina logo depois dele.
+    assert_eq!(
+        visible_input_window(name, 10, 8),
+        ("aphina-d".to_string(), 7)
+    );
+}
+
+#[test]
+fn window_counts_characters_not_bytes() {
+    let name = "ÇãoÉé";
+    assert_eq!(
+        visible_input_window(name, name.len(), 3),
+        ("Éé".to_string(), 2)
+    );
+    assert_eq!(visible_input_window(name, 0, 0), (String::new(), 0));
+}
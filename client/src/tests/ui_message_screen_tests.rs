@@ -1,4 +1,35 @@
-use super::{format_message_datetime, wrap_message_lines, wrap_plain_lines};
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::text::Line;
+
+use ratatui::style::Modifier;
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::ui::ui_state::{UiDirectMessage, UiMessage, UiUser, UserLookup};
+
+use crate::config::KeybindingsConfig;
+use crate::ui::glyphs::Glyphs;
+use crate::ui::theme::Theme;
+use unicode_width::UnicodeWidthStr;
+
+use super::{
+    ACTIVITY_SPARKLINE_WIDTH, HEADER_STATUS_WIDTH, HEADER_TEXT, HeaderLayout, INPUT_MAX_LINES,
+    activity_window, capped_messages_area, chat_instructions, color_for_identity,
+    direct_message_text, format_message_datetime, header_layout, highlight_links,
+    highlight_matches, humanize_age, input_cursor_position, input_lines_height, link_continuations,
+    link_ranges, message_body, message_style, message_time_label, messages_overflow_hint_text,
+    pad_to_width, reaction_summary, sender_line, typing_text, user_status_suffix,
+    user_title_prefix, whisper_style, wrap_fixed_lines, wrap_message_lines, wrap_plain_lines,
+};
+
+fn message_from(sender: &str) -> UiMessage {
+    UiMessage {
+        sender: sender.to_string(),
+        text: "oi".to_string(),
+        ..Default::default()
+    }
+}
 
 #[test]
 fn format_message_datetime_parses_iso_timestamp() {
@@ -7,6 +38,21 @@ fn format_message_datetime_parses_iso_timestamp() {
     assert_eq!(formatted.as_deref(), Some("12/02/2026 13:44"));
 }
 
+#[test]
+fn message_time_label_shows_raw_timestamp_in_debug_mode() {
+    let raw = "2026-02-14 18:03:27.123456 +00:00";
+    let now = SystemTime::now();
+    assert_eq!(
+        message_time_label(raw, true, true, now).as_deref(),
+        Some(raw)
+    );
+    assert_eq!(
+        message_time_label(raw, false, false, now),
+        format_message_datetime(raw)
+    );
+    assert_eq!(message_time_label("", true, false, now), None);
+}
+
 #[test]
 fn format_message_datetime_handles_empty_and_unknown_formats() {
     assert_eq!(format_message_datetime(""), None);
@@ -16,6 +62,100 @@ fn format_message_datetime_handles_empty_and_unknown_formats() {
     );
 }
 
+#[test]
+fn format_message_datetime_falls_back_to_raw_for_out_of_range_fields() {
+    for raw in [
+        "2026-13-40T99:99",
+        "2026-02-12T24:00:00Z",
+        "2026-2-12T13:44:00Z",
+        "2026-02T13:44",
+        "2026-02-12-01T13:44",
+        "2026-02-12T13",
+        "abcd-ef-ghT13:44",
+    ] {
+        assert_eq!(format_message_datetime(raw).as_deref(), Some(raw), "{raw}");
+    }
+}
+
+#[test]
+fn format_message_datetime_does_not_panic_on_multibyte_time() {
+    // Regressões: o corte em 5 bytes caía no meio de caracteres UTF-8.
+    for raw in [
+        "2026-02-12T1ç:44",
+        "2026-02-12Tçççç",
+        "2026-02-12 🙂🙂",
+        "-- T日本語",
+    ] {
+        assert_eq!(format_message_datetime(raw).as_deref(), Some(raw), "{raw}");
+    }
+}
+
+#[test]
+fn format_message_datetime_never_panics_on_random_input() {
+    const ALPHABET: &[char] = &[
+        '0', '1', '2', '9', '-', 'T', ' ', ':', '.', 'Z', '+', 'a', 'ç', '🙂', '日',
+    ];
+    let mut seed: u64 = 0x5eed_1234;
+    let mut next = || {
+        seed = seed
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (seed >> 33) as usize
+    };
+
+    for _ in 0..5_000 {
+        let len = next() % 24;
+        let raw: String = (0..len)
+            .map(|_| ALPHABET[next() % ALPHABET.len()])
+            .collect();
+        let formatted = format_message_datetime(&raw);
+        if raw.trim().is_empty() {
+            assert_eq!(formatted, None);
+        } else {
+            assert!(formatted.is_some(), "{raw:?}");
+        }
+    }
+}
+
+/// `2026-02-12T10:00:00Z` em segundos Unix.
+const SENT_AT_SECS: u64 = 1_770_890_400;
+
+fn age(secs: u64) -> Option<String> {
+    let now = UNIX_EPOCH + Duration::from_secs(SENT_AT_SECS + secs);
+    humanize_age("2026-02-12T10:00:00Z", now)
+}
+
+#[test]
+fn humanize_age_switches_units_at_the_minute_and_hour_boundaries() {
+    assert_eq!(age(0).as_deref(), Some("now"));
+    assert_eq!(age(59).as_deref(), Some("59s ago"));
+    assert_eq!(age(60).as_deref(), Some("1m ago"));
+    assert_eq!(age(59 * 60).as_deref(), Some("59m ago"));
+    assert_eq!(age(60 * 60 - 1).as_deref(), Some("59m ago"));
+    assert_eq!(age(60 * 60), None);
+}
+
+#[test]
+fn humanize_age_handles_offsets_future_and_garbage() {
+    let now = UNIX_EPOCH + Duration::from_secs(SENT_AT_SECS + 120);
+    assert_eq!(
+        humanize_age("2026-02-12T07:00:00-03:00", now).as_deref(),
+        Some("2m ago")
+    );
+    // Frações contam: 119,9s ainda é "1m".
+    assert_eq!(
+        humanize_age("2026-02-12T10:00:00.100000+00:00", now).as_deref(),
+        Some("1m ago")
+    );
+    let before = UNIX_EPOCH + Duration::from_secs(SENT_AT_SECS - 30);
+    assert_eq!(
+        humanize_age("2026-02-12T10:00:00Z", before).as_deref(),
+        Some("now")
+    );
+    assert_eq!(humanize_age("", SystemTime::now()), None);
+    assert_eq!(humanize_age("12/02/2026 10:00", SystemTime::now()), None);
+}
+
 #[test]
 fn wrap_plain_lines_splits_fixed_width_and_preserves_newlines() {
     let wrapped = wrap_plain_lines("ab\ncdef", 3);
@@ -27,3 +167,484 @@ fn wrap_message_lines_indents_following_lines() {
     let wrapped = wrap_message_lines("Rafael: ", "1234567890", 12);
     assert_eq!(wrapped, vec!["Rafael: 1234", "        5678", "        90"]);
 }
+
+#[test]
+fn wrap_plain_lines_breaks_long_sentences_at_spaces() {
+    let wrapped = wrap_plain_lines("o dragão dorme sob a montanha antiga", 12);
+    assert_eq!(
+        wrapped,
+        vec!["o dragão", "dorme sob a", "montanha", "antiga"]
+    );
+}
+
+#[test]
+fn wrap_plain_lines_hard_splits_only_tokens_longer_than_the_line() {
+    let wrapped = wrap_plain_lines("abcdefghijklmnopqrst", 10);
+    assert_eq!(wrapped, vec!["abcdefghij", "klmnopqrst"]);
+
+    let wrapped = wrap_plain_lines("ver https://example.com/abc ok", 10);
+    assert_eq!(wrapped, vec!["ver", "https://ex", "ample.com/", "abc ok"]);
+}
+
+#[test]
+fn wrap_plain_lines_keeps_blank_lines() {
+    assert_eq!(wrap_plain_lines("", 5), vec![""]);
+    assert_eq!(wrap_plain_lines("a\n\nb", 5), vec!["a", "", "b"]);
+    // Espaço duplo bem na quebra não gera linha vazia.
+    assert_eq!(wrap_plain_lines("abc  de", 3), vec!["abc", "de"]);
+}
+
+#[test]
+fn wrap_plain_lines_counts_full_width_characters_as_two_columns() {
+    let text = "東京は今日もとても暑いです";
+    let wrapped = wrap_plain_lines(text, 7);
+    assert!(wrapped.iter().all(|line| line.width() <= 7), "{wrapped:?}");
+    assert_eq!(wrapped.concat(), text);
+    assert_eq!(wrapped[0], "東京は");
+
+    let wrapped = wrap_plain_lines("ok 🎉🎉🎉 fim", 4);
+    assert!(wrapped.iter().all(|line| line.width() <= 4), "{wrapped:?}");
+}
+
+#[test]
+fn wrap_message_lines_keeps_wide_text_inside_the_box() {
+    let wrapped = wrap_message_lines("Aiko: ", "こんにちは、みなさん元気ですか", 16);
+    assert!(wrapped.iter().all(|line| line.width() <= 16), "{wrapped:?}");
+    assert!(wrapped[1].starts_with("      "));
+}
+
+#[test]
+fn wrap_message_lines_indents_word_wrapped_lines() {
+    let wrapped = wrap_message_lines("Ana: ", "vamos para a taverna", 15);
+    assert_eq!(wrapped, vec!["Ana: vamos para", "     a taverna"]);
+}
+
+#[test]
+fn wrap_fixed_lines_splits_by_characters_for_the_input() {
+    assert_eq!(wrap_fixed_lines("ab cd\nef", 3), vec!["ab ", "cd", "ef"]);
+    assert_eq!(wrap_fixed_lines("", 3), vec![""]);
+}
+
+#[test]
+fn message_style_highlights_only_own_messages() {
+    let own = message_style(
+        &message_from("id_me"),
+        Some("id_me"),
+        Some(Color::Magenta),
+        &Theme::default(),
+    );
+    let other = message_style(
+        &message_from("id_other"),
+        Some("id_me"),
+        Some(Color::Magenta),
+        &Theme::default(),
+    );
+    assert_eq!(own, Style::default().fg(Color::Magenta));
+    assert_eq!(other, Style::default());
+}
+
+#[test]
+fn message_style_is_plain_when_disabled_or_identity_unknown() {
+    let mine = message_from("id_me");
+    assert_eq!(
+        message_style(&mine, Some("id_me"), None, &Theme::default()),
+        Style::default()
+    );
+    assert_eq!(
+        message_style(&mine, None, Some(Color::Magenta), &Theme::default()),
+        Style::default()
+    );
+}
+
+#[test]
+fn announcements_use_distinct_style_even_when_sent_by_self() {
+    let announcement = UiMessage {
+        is_announcement: true,
+        ..message_from("id_me")
+    };
+    let theme = Theme::default();
+    let style = message_style(&announcement, Some("id_me"), Some(Color::Magenta), &theme);
+    assert_eq!(style.bg, Some(theme.announcement_bg));
+    assert!(style.add_modifier.contains(Modifier::BOLD));
+    assert_ne!(
+        style,
+        message_style(&message_from("id_other"), Some("id_me"), None, &theme)
+    );
+}
+
+#[test]
+fn announcements_follow_the_theme_and_high_contrast() {
+    let announcement = UiMessage {
+        is_announcement: true,
+        ..message_from("id_lia")
+    };
+    let contrast = Theme::high_contrast();
+    let style = message_style(&announcement, None, None, &contrast);
+    assert_eq!(style.fg, Some(contrast.announcement));
+    assert_eq!(style.bg, Some(contrast.announcement_bg));
+    assert_ne!(
+        style,
+        message_style(&announcement, None, None, &Theme::default())
+    );
+}
+
+#[test]
+fn pad_to_width_fills_full_line_for_announcements() {
+    assert_eq!(pad_to_width("ab".to_string(), 5), "ab   ");
+    assert_eq!(pad_to_width("abcdef".to_string(), 3), "abcdef");
+}
+
+#[test]
+fn message_body_marks_edited_messages() {
+    let edited = UiMessage {
+        is_edited: true,
+        ..message_from("id_me")
+    };
+    assert_eq!(message_body(&edited), "oi (editado)");
+    assert_eq!(message_body(&message_from("id_me")), "oi");
+}
+
+#[test]
+fn direct_message_text_names_both_ends_and_marks_self() {
+    let names = UserLookup::build(
+        &[UiUser {
+            identity: "id_lia".to_string(),
+            name: "Lia".to_string(),
+            ..Default::default()
+        }],
+        0,
+    );
+    let dm = UiDirectMessage {
+        sender: "id_lia".to_string(),
+        recipient: "id_me".to_string(),
+        text: "psiu".to_string(),
+        sent_at: "2026-02-12T13:44:59Z".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(
+        direct_message_text(&dm, &names, Some("id_me"), &[]),
+        "[DM] [12/02/2026 13:44] Lia -> you: psiu"
+    );
+    assert_eq!(
+        direct_message_text(&dm, &names, Some("id_me"), &["psiu".to_string()]),
+        "[DM] [12/02/2026 13:44] Lia -> you: ****"
+    );
+}
+
+#[test]
+fn whisper_style_uses_the_theme_color_not_the_public_one() {
+    let theme = Theme::by_name("amber");
+    let style = whisper_style(&theme);
+    assert_eq!(style.fg, Some(theme.whisper));
+    assert!(style.add_modifier.contains(Modifier::ITALIC));
+    assert_ne!(
+        style,
+        message_style(&message_from("id_lia"), None, None, &theme)
+    );
+}
+
+#[test]
+fn reaction_summary_lists_counts_in_order() {
+    let reactions = vec![("👍".to_string(), 3), ("🎉".to_string(), 1)];
+    assert_eq!(reaction_summary(&reactions).as_deref(), Some("👍 3  🎉 1"));
+    assert_eq!(reaction_summary(&[]), None);
+}
+
+#[test]
+fn typing_text_summarizes_who_is_typing() {
+    let names = |list: &[&str]| list.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+    assert_eq!(typing_text(&[]), None);
+    assert_eq!(
+        typing_text(&names(&["Lia"])).as_deref(),
+        Some("Lia is typing...")
+    );
+    assert_eq!(
+        typing_text(&names(&["Lia", "Rui"])).as_deref(),
+        Some("Lia and Rui are typing...")
+    );
+    assert_eq!(
+        typing_text(&names(&["Lia", "Rui", "Ana"])).as_deref(),
+        Some("Several people are typing...")
+    );
+}
+
+#[test]
+fn user_status_suffix_hides_empty_status() {
+    assert_eq!(user_status_suffix("", "·"), None);
+    assert_eq!(user_status_suffix("   ", "·"), None);
+    assert_eq!(
+        user_status_suffix(" lendo grimórios ", "·").as_deref(),
+        Some(" · lendo grimórios")
+    );
+}
+
+#[test]
+fn color_for_identity_is_stable_and_spreads_users() {
+    assert_eq!(color_for_identity("id_lia"), color_for_identity("id_lia"));
+    let colors: std::collections::HashSet<String> = (0..32)
+        .map(|i| format!("{:?}", color_for_identity(&format!("c200{i:02}"))))
+        .collect();
+    assert!(colors.len() > 1);
+    assert_eq!(color_for_identity("System"), Color::Gray);
+}
+
+#[test]
+fn sender_line_colors_only_the_sender_name() {
+    let line = sender_line("[12/02] Lia: oi".to_string(), 8, "Lia", Color::Cyan, &[]);
+    let spans: Vec<(&str, Option<Color>)> = line
+        .spans
+        .iter()
+        .map(|span| (span.content.as_ref(), span.style.fg))
+        .collect();
+    assert_eq!(
+        spans,
+        vec![
+            ("[12/02] ", None),
+            ("Lia", Some(Color::Cyan)),
+            (": oi", None)
+        ]
+    );
+
+    // Nome cortado pela quebra de linha: render comum, sem cor.
+    let cut = sender_line("[12/02] Li".to_string(), 8, "Lia", Color::Cyan, &[]);
+    assert!(cut.spans.iter().all(|span| span.style.fg.is_none()));
+}
+
+#[test]
+fn activity_window_keeps_the_most_recent_seconds() {
+    let activity: Vec<u64> = (1..=60).collect();
+    assert_eq!(activity_window(&activity, 3), &[58, 59, 60]);
+    assert_eq!(activity_window(&activity, 100).len(), 60);
+    assert!(activity_window(&[], 10).is_empty());
+}
+
+#[test]
+fn input_cursor_position_follows_fixed_width_wrapping() {
+    assert_eq!(input_cursor_position("", 0, 10), (0, 0));
+    assert_eq!(input_cursor_position("abc", 1, 10), (0, 1));
+    // Linha cheia: o cursor vai para o início da próxima.
+    assert_eq!(input_cursor_position("abcd", 4, 4), (1, 0));
+    assert_eq!(input_cursor_position("abcdef", 6, 4), (1, 2));
+    // Acentos contam como um caractere, não pelos bytes.
+    assert_eq!(input_cursor_position("çãé", "çã".len(), 10), (0, 2));
+    // Quebras de linha explícitas.
+    assert_eq!(input_cursor_position("ab\ncd", 4, 10), (1, 1));
+    assert_eq!(input_cursor_position("abcde\nx", 7, 4), (2, 1));
+    assert_eq!(input_cursor_position("abcd\nx", 5, 4), (1, 0));
+}
+
+#[test]
+fn input_height_grows_with_lines_up_to_the_limit() {
+    assert_eq!(input_lines_height("", 0, 10), 1);
+    assert_eq!(input_lines_height("oi", 2, 10), 1);
+    assert_eq!(input_lines_height("oi\n", 3, 10), 2);
+    // Linha cheia com o cursor no fim já reserva a próxima linha.
+    assert_eq!(input_lines_height("abcd", 4, 4), 2);
+    assert_eq!(
+        input_lines_height("a\nb\nc\nd\ne\nf\ng", 0, 10),
+        INPUT_MAX_LINES
+    );
+}
+
+#[test]
+fn chat_instructions_follow_configured_keybindings() {
+    let keybindings = KeybindingsConfig {
+        help: "F9".to_string(),
+        menu: "Ctrl+m".to_string(),
+        ..Default::default()
+    };
+    let items = chat_instructions(&keybindings, true);
+    let key_for = |label: &str| items.iter().find(|item| item.label == label).map(|i| i.key);
+    assert_eq!(key_for("Help"), Some("F9"));
+    assert_eq!(key_for("Menu"), Some("Ctrl+m"));
+    assert_eq!(key_for("Send"), Some("Enter"));
+}
+
+#[test]
+fn chat_instructions_reflect_system_message_visibility() {
+    let keybindings = KeybindingsConfig::default();
+    let labels = |show_system| {
+        chat_instructions(&keybindings, show_system)
+            .iter()
+            .map(|item| item.label)
+            .collect::<Vec<_>>()
+    };
+    assert!(labels(true).contains(&"Hide system"));
+    assert!(labels(false).contains(&"Show system"));
+}
+
+#[test]
+fn user_title_prefix_wraps_title_in_brackets() {
+    assert_eq!(user_title_prefix("host"), "[host] ");
+    assert_eq!(user_title_prefix("  bardo "), "[bardo] ");
+    assert_eq!(user_title_prefix(""), "");
+    assert_eq!(user_title_prefix("   "), "");
+}
+
+#[test]
+fn capped_messages_area_centers_the_pane_on_wide_terminals() {
+    let area = Rect::new(0, 3, 300, 40);
+    let capped = capped_messages_area(area, 100);
+    assert_eq!(capped, Rect::new(99, 3, 102, 40));
+    assert_eq!(capped.width.saturating_sub(2), 100);
+
+    assert_eq!(capped_messages_area(area, 0), area);
+    let narrow = Rect::new(0, 3, 80, 40);
+    assert_eq!(capped_messages_area(narrow, 100), narrow);
+}
+
+#[test]
+fn header_layout_keeps_everything_on_wide_terminals() {
+    let layout = header_layout(120, true);
+    assert_eq!(layout.sparkline_width, ACTIVITY_SPARKLINE_WIDTH);
+    assert_eq!(layout.status_width, HEADER_STATUS_WIDTH);
+    assert_eq!(
+        layout.text_width,
+        120 - ACTIVITY_SPARKLINE_WIDTH - HEADER_STATUS_WIDTH
+    );
+    assert_eq!(layout.status_label, "Server online");
+}
+
+#[test]
+fn header_layout_drops_text_then_shrinks_sparkline_then_label() {
+    // Sem espaço para o texto inteiro: some, e a sobra vai para o status.
+    let width = HEADER_STATUS_WIDTH + ACTIVITY_SPARKLINE_WIDTH + HEADER_TEXT.len() as u16 - 1;
+    let layout = header_layout(width, true);
+    assert_eq!(layout.text_width, 0);
+    assert_eq!(layout.sparkline_width, ACTIVITY_SPARKLINE_WIDTH);
+    assert_eq!(layout.status_width + layout.sparkline_width, width);
+
+    let layout = header_layout(HEADER_STATUS_WIDTH + 5, false);
+    assert_eq!((layout.text_width, layout.sparkline_width), (0, 5));
+    assert_eq!(layout.status_label, "Server offline");
+
+    assert_eq!(
+        header_layout(10, false),
+        HeaderLayout {
+            text_width: 0,
+            sparkline_width: 0,
+            status_width: 10,
+            status_label: "Offline",
+        }
+    );
+    assert_eq!(header_layout(0, true).status_width, 0);
+}
+
+#[test]
+fn highlight_matches_splits_spans_and_keeps_the_rest_intact() {
+    let line = Line::from("ana: Oi oi, OI").style(Style::default().fg(Color::Cyan));
+    let highlighted = highlight_matches(line, "oi");
+    let parts: Vec<(&str, Option<Color>)> = highlighted
+        .spans
+        .iter()
+        .map(|span| (span.content.as_ref(), span.style.bg))
+        .collect();
+    assert_eq!(
+        parts,
+        vec![
+            ("ana: ", None),
+            ("Oi", Some(Color::Yellow)),
+            (" ", None),
+            ("oi", Some(Color::Yellow)),
+            (", ", None),
+            ("OI", Some(Color::Yellow)),
+        ]
+    );
+    assert_eq!(highlighted.style.fg, Some(Color::Cyan));
+
+    let untouched = highlight_matches(Line::from("nada aqui"), "");
+    assert_eq!(untouched.spans.len(), 1);
+}
+
+#[test]
+fn link_ranges_finds_a_single_url() {
+    let text = "veja https://example.com/a?b=1.";
+    let ranges = link_ranges(text, false);
+    assert_eq!(ranges.len(), 1);
+    assert_eq!(&text[ranges[0].clone()], "https://example.com/a?b=1");
+}
+
+#[test]
+fn link_ranges_finds_multiple_urls() {
+    let text = "HTTP://a.io e (https://b.io) ok";
+    let found: Vec<&str> = link_ranges(text, false)
+        .into_iter()
+        .map(|range| &text[range])
+        .collect();
+    assert_eq!(found, vec!["HTTP://a.io", "https://b.io"]);
+}
+
+#[test]
+fn link_ranges_ignores_text_without_urls() {
+    assert!(link_ranges("sem link, só http:// e ftp://x.io", false).is_empty());
+    assert!(link_ranges("", false).is_empty());
+}
+
+#[test]
+fn highlight_links_styles_only_the_url() {
+    let line = highlight_links(Line::from("oi https://x.io tchau"), false);
+    let contents: Vec<&str> = line.spans.iter().map(|s| s.content.as_ref()).collect();
+    assert_eq!(contents, vec!["oi ", "https://x.io", " tchau"]);
+    assert_eq!(line.spans[0].style, Style::default());
+    assert_eq!(line.spans[1].style.fg, Some(Color::Cyan));
+    assert!(
+        line.spans[1]
+            .style
+            .add_modifier
+            .contains(Modifier::UNDERLINED)
+    );
+    assert_eq!(line.spans[2].style, Style::default());
+
+    let plain = highlight_links(Line::from("nada aqui"), false);
+    assert_eq!(plain.spans.len(), 1);
+    assert_eq!(plain.spans[0].style, Style::default());
+}
+
+#[test]
+fn links_cut_by_wrapping_stay_highlighted_on_the_next_line() {
+    let lines = wrap_message_lines(
+        "Lia: ",
+        "link https://example.com/um/caminho/bem/longo fim",
+        20,
+    );
+    let flags = link_continuations(&lines, 20);
+    assert_eq!(flags.len(), lines.len());
+    assert!(!flags[0]);
+    assert!(flags.iter().any(|&continued| continued));
+
+    let highlighted: Vec<String> = lines
+        .iter()
+        .zip(&flags)
+        .flat_map(|(line, &continued)| {
+            highlight_links(Line::from(line.clone()), continued)
+                .spans
+                .into_iter()
+                .filter(|span| span.style.fg == Some(Color::Cyan))
+                .map(|span| span.content.to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    assert_eq!(
+        highlighted.concat(),
+        "https://example.com/um/caminho/bem/longo"
+    );
+    assert!(!highlighted.iter().any(|piece| piece.contains("fim")));
+}
+
+#[test]
+fn overflow_hint_counts_unread_messages_when_scrolled_up() {
+    let glyphs = Glyphs::select(true);
+    assert_eq!(messages_overflow_hint_text(0, 10, 3, glyphs), "3 new v");
+    assert_eq!(
+        messages_overflow_hint_text(4, 10, 1, glyphs),
+        "Older ^ | 1 new v"
+    );
+    assert_eq!(
+        messages_overflow_hint_text(4, 10, 0, glyphs),
+        "Older ^ | Newer v"
+    );
+    assert_eq!(
+        messages_overflow_hint_text(10, 10, 2, glyphs),
+        "Newer messages v"
+    );
+}
@@ -0,0 +1,791 @@
+use std::{
+    borrow::Cow,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use ratatui::layout::Rect;
+
+use super::{
+    ChatPanel, DIRECT_MESSAGES_VISIBLE, INPUT_HISTORY_LIMIT, MESSAGES_PAGE_SIZE, QUOTE_MAX_CHARS,
+    ScrollPosition, TYPING_EXPIRY_MICROS, TYPING_REFRESH, UiDirectMessage, UiMessage, UiPopup,
+    UiState, UiTyping, UiUser, can_delete, find_matching_message_indices, quote_prefill,
+};
+
+fn user(identity: &str, online: bool) -> UiUser {
+    UiUser {
+        identity: identity.to_string(),
+        name: identity.to_string(),
+        online,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn visible_users_keeps_everyone_by_default() {
+    let state = UiState {
+        users: vec![user("a", true), user("b", false)],
+        ..Default::default()
+    };
+    assert_eq!(state.visible_users().len(), 2);
+}
+
+#[test]
+fn visible_users_drops_offline_when_hidden_but_keeps_state() {
+    let state = UiState {
+        users: vec![user("a", true), user("b", false), user("c", true)],
+        hide_offline_users: true,
+        ..Default::default()
+    };
+    let visible: Vec<&str> = state
+        .visible_users()
+        .iter()
+        .map(|u| u.identity.as_str())
+        .collect();
+    assert_eq!(visible, vec!["a", "c"]);
+    assert_eq!(state.users.len(), 3);
+}
+
+#[test]
+fn scroll_bottom_follows_new_messages() {
+    let pos = ScrollPosition::Bottom;
+    assert_eq!(pos.resolve(10), 10);
+    assert_eq!(pos.resolve(25), 25);
+    assert_eq!(pos.resolve(0), 0);
+}
+
+#[test]
+fn scroll_top_stays_on_oldest_when_history_grows_or_shrinks() {
+    let pos = ScrollPosition::Top;
+    assert_eq!(pos.resolve(10), 0);
+    assert_eq!(pos.resolve(3), 0);
+    assert_eq!(pos.scroll_down(5, 12), ScrollPosition::Offset(7));
+}
+
+#[test]
+fn scroll_offset_is_clamped_when_history_shrinks() {
+    let pos = ScrollPosition::Offset(8);
+    assert_eq!(pos.resolve(20), 12);
+    assert_eq!(pos.resolve(5), 0);
+    // Após encolher, descer parte do topo real em vez do offset antigo.
+    assert_eq!(pos.scroll_down(2, 5), ScrollPosition::Offset(3));
+}
+
+#[test]
+fn scroll_normalizes_to_extremes() {
+    assert_eq!(
+        ScrollPosition::Offset(3).scroll_down(5, 10),
+        ScrollPosition::Bottom
+    );
+    assert_eq!(
+        ScrollPosition::Offset(8).scroll_up(5, 10),
+        ScrollPosition::Top
+    );
+    assert_eq!(
+        ScrollPosition::Bottom.scroll_up(5, 10),
+        ScrollPosition::Offset(5)
+    );
+}
+
+fn state_with_messages(count: usize) -> UiState {
+    UiState {
+        messages: (0..count as u64)
+            .map(|id| UiMessage {
+                id,
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn windowed_messages_keeps_latest_page_and_grows_with_extra_pages() {
+    let mut state = state_with_messages(MESSAGES_PAGE_SIZE + 30);
+    assert_eq!(state.windowed_messages().len(), MESSAGES_PAGE_SIZE);
+    assert_eq!(state.windowed_messages()[0].id, 30);
+    assert!(state.has_older_messages());
+
+    state.messages_extra_pages = 1;
+    assert_eq!(state.windowed_messages().len(), MESSAGES_PAGE_SIZE + 30);
+    assert!(!state.has_older_messages());
+}
+
+#[test]
+fn load_older_triggers_only_at_top_with_hidden_history_and_not_loading() {
+    let mut state = state_with_messages(MESSAGES_PAGE_SIZE + 1);
+    state.messages_max_scroll = 40;
+    state.messages_scroll = ScrollPosition::Offset(10);
+    assert!(!state.should_load_older_messages());
+
+    state.messages_scroll = ScrollPosition::Top;
+    assert!(state.should_load_older_messages());
+
+    state.loading_older_messages = true;
+    assert!(!state.should_load_older_messages());
+
+    let mut small = state_with_messages(3);
+    small.messages_scroll = ScrollPosition::Top;
+    assert!(!small.should_load_older_messages());
+}
+
+#[test]
+fn room_is_empty_when_only_self_is_online() {
+    let state = UiState {
+        users: vec![user("me", true), user("old", false)],
+        ..Default::default()
+    };
+    assert!(state.is_room_empty(Some("me")));
+
+    let busy = UiState {
+        users: vec![user("me", true), user("lia", true)],
+        ..Default::default()
+    };
+    assert!(!busy.is_room_empty(Some("me")));
+    assert!(UiState::default().is_room_empty(None));
+
+    let only_bots = UiState {
+        users: vec![user("me", true), user("bot", true)],
+        bot_identities: ["bot".to_string()].into(),
+        ..Default::default()
+    };
+    assert!(only_bots.is_room_empty(Some("me")));
+}
+
+#[test]
+fn find_user_by_name_ignores_case() {
+    let state = UiState {
+        users: vec![UiUser {
+            identity: "id_lia".to_string(),
+            name: "Lia".to_string(),
+            online: true,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    assert_eq!(
+        state
+            .find_user_by_name(" lIA ")
+            .map(|u| u.identity.as_str()),
+        Some("id_lia")
+    );
+    assert!(state.find_user_by_name("Kael").is_none());
+}
+
+#[test]
+fn recent_direct_messages_keeps_only_the_latest() {
+    let state = UiState {
+        direct_messages: (0..10)
+            .map(|id| UiDirectMessage {
+                id,
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    };
+    let recent = state.recent_direct_messages();
+    assert_eq!(recent.len(), DIRECT_MESSAGES_VISIBLE);
+    assert_eq!(recent.last().map(|dm| dm.id), Some(9));
+}
+
+#[test]
+fn latest_reactable_message_skips_system_and_pending_lines() {
+    let message = |id: u64, sender: &str, is_pending: bool| UiMessage {
+        id,
+        sender: sender.to_string(),
+        is_pending,
+        ..Default::default()
+    };
+    let mut state = UiState {
+        messages: vec![
+            message(1, "id_lia", false),
+            message(2, "System", false),
+            message(3, "id_me", true),
+        ],
+        ..Default::default()
+    };
+    assert_eq!(state.latest_reactable_message_id(), Some(1));
+
+    state.messages.truncate(0);
+    assert_eq!(state.latest_reactable_message_id(), None);
+}
+
+#[test]
+fn offline_grace_holds_brief_disconnects_and_expires_later() {
+    let start = Instant::now();
+    let mut state = UiState {
+        users: vec![user("lia", true)],
+        offline_grace: Duration::from_secs(5),
+        ..Default::default()
+    };
+
+    // Caiu: continua online durante a carência.
+    let mut synced = vec![user("lia", false)];
+    state.apply_offline_grace(&mut synced, start);
+    assert!(synced[0].online);
+    state.users = synced;
+    assert!(
+        state
+            .expire_offline_grace(start + Duration::from_secs(2))
+            .1
+            .is_empty()
+    );
+
+    // Voltou antes do prazo: o timer é cancelado.
+    let mut synced = vec![user("lia", true)];
+    state.apply_offline_grace(&mut synced, start + Duration::from_secs(3));
+    state.users = synced;
+    assert!(state.pending_presence.is_empty());
+
+    // Caiu de novo e não voltou: vira offline quando a carência expira.
+    let mut synced = vec![user("lia", false)];
+    state.apply_offline_grace(&mut synced, start + Duration::from_secs(4));
+    state.users = synced;
+    let (_, expired) = state.expire_offline_grace(start + Duration::from_secs(9));
+    assert_eq!(expired.len(), 1);
+    assert!(!state.users[0].online);
+    assert!(state.pending_presence.is_empty());
+}
+
+#[test]
+fn offline_grace_ignores_users_never_shown_online_and_zero_grace() {
+    let now = Instant::now();
+    let mut state = UiState {
+        users: vec![user("lia", true)],
+        ..Default::default()
+    };
+    let mut synced = vec![user("lia", false), user("rui", false)];
+    state.apply_offline_grace(&mut synced, now);
+    assert!(!synced[0].online);
+    assert!(!synced[1].online);
+    assert!(state.pending_presence.is_empty());
+}
+
+#[test]
+fn quick_join_and_leave_within_grace_produces_no_events() {
+    let start = Instant::now();
+    let mut state = UiState {
+        offline_grace: Duration::from_secs(5),
+        users_presence_initialized: true,
+        ..Default::default()
+    };
+
+    // Entrou: aparece online, mas o aviso espera a carência.
+    let mut synced = vec![user("lia", true)];
+    let (connected, disconnected) = state.apply_offline_grace(&mut synced, start);
+    assert!(connected.is_empty() && disconnected.is_empty());
+    assert!(synced[0].online);
+    state.users = synced;
+
+    // Saiu meio segundo depois: some sem aviso, nem depois do prazo.
+    let mut synced = vec![user("lia", false)];
+    let (connected, disconnected) =
+        state.apply_offline_grace(&mut synced, start + Duration::from_millis(500));
+    assert!(connected.is_empty() && disconnected.is_empty());
+    assert!(!synced[0].online);
+    state.users = synced;
+    let (connected, went_offline) = state.expire_offline_grace(start + Duration::from_secs(10));
+    assert!(connected.is_empty() && went_offline.is_empty());
+    assert!(state.pending_presence.is_empty());
+}
+
+#[test]
+fn stable_join_is_announced_after_the_grace() {
+    let start = Instant::now();
+    let mut state = UiState {
+        offline_grace: Duration::from_secs(5),
+        users_presence_initialized: true,
+        ..Default::default()
+    };
+    let mut synced = vec![user("rui", true)];
+    state.apply_offline_grace(&mut synced, start);
+    state.users = synced;
+    assert!(
+        state
+            .expire_offline_grace(start + Duration::from_secs(2))
+            .0
+            .is_empty()
+    );
+    let (connected, _) = state.expire_offline_grace(start + Duration::from_secs(5));
+    assert_eq!(connected.len(), 1);
+    assert_eq!(connected[0].identity, "rui");
+
+    // Sem carência, entrar e sair são avisados na hora.
+    let mut state = UiState {
+        users_presence_initialized: true,
+        ..Default::default()
+    };
+    let mut synced = vec![user("ana", true)];
+    assert_eq!(state.apply_offline_grace(&mut synced, start).0.len(), 1);
+    state.users = synced;
+    let mut synced = vec![user("ana", false)];
+    assert_eq!(state.apply_offline_grace(&mut synced, start).1.len(), 1);
+}
+
+#[test]
+fn first_sync_announces_nobody() {
+    let mut state = UiState {
+        offline_grace: Duration::from_secs(5),
+        ..Default::default()
+    };
+    let mut synced = vec![user("lia", true)];
+    let (connected, disconnected) = state.apply_offline_grace(&mut synced, Instant::now());
+    assert!(connected.is_empty() && disconnected.is_empty());
+    assert!(state.pending_presence.is_empty());
+}
+
+#[test]
+fn typing_names_skip_self_offline_and_stale_entries() {
+    let now = 100_000_000;
+    let typing = |identity: &str, started_at_micros: i64| UiTyping {
+        identity: identity.to_string(),
+        started_at_micros,
+    };
+    let state = UiState {
+        users: vec![
+            user("lia", true),
+            user("me", true),
+            user("rui", false),
+            user("ana", true),
+        ],
+        typing: vec![
+            typing("lia", now - 1_000_000),
+            typing("me", now),
+            typing("rui", now),
+            typing("ana", now - TYPING_EXPIRY_MICROS),
+        ],
+        ..Default::default()
+    };
+    assert_eq!(state.typing_names(Some("me"), now), vec!["lia".to_string()]);
+}
+
+#[test]
+fn mark_typing_throttles_refreshes() {
+    let start = Instant::now();
+    let mut state = UiState::default();
+    assert!(state.mark_typing(start));
+    assert!(!state.mark_typing(start + Duration::from_millis(500)));
+    assert!(state.mark_typing(start + TYPING_REFRESH));
+}
+
+#[test]
+fn cursor_editing_inserts_and_deletes_in_the_middle() {
+    let mut state = UiState::default();
+    for c in "olá".chars() {
+        state.insert_at_cursor(c);
+    }
+    assert_eq!(state.cursor, "olá".len());
+
+    state.move_cursor_left();
+    state.move_cursor_left();
+    state.insert_at_cursor('X');
+    assert_eq!(state.input, "oXlá");
+
+    state.move_cursor_end();
+    state.delete_before_cursor();
+    assert_eq!(state.input, "oXl");
+
+    state.move_cursor_home();
+    state.delete_before_cursor();
+    state.move_cursor_left();
+    assert_eq!((state.input.as_str(), state.cursor), ("oXl", 0));
+
+    state.move_cursor_right();
+    state.delete_before_cursor();
+    assert_eq!((state.input.as_str(), state.cursor), ("Xl", 0));
+}
+
+#[test]
+fn cursor_is_clamped_to_char_boundaries() {
+    let mut state = UiState {
+        input: "çé".to_string(),
+        // Meio do "é" (bytes 2..4) e depois além do fim.
+        cursor: 3,
+        ..Default::default()
+    };
+    assert_eq!(state.clamped_cursor(), 2);
+    state.move_cursor_right();
+    assert_eq!(state.cursor, 4);
+
+    state.cursor = 99;
+    state.insert_at_cursor('!');
+    assert_eq!(state.input, "çé!");
+    state.move_cursor_right();
+    assert_eq!(state.cursor, "çé!".len());
+}
+
+#[test]
+fn quote_prefill_flattens_and_truncates_long_messages() {
+    assert_eq!(
+        quote_prefill("Ana", "oi\n  tudo bem?"),
+        "> Ana: oi tudo bem?\n"
+    );
+
+    let long = "á".repeat(QUOTE_MAX_CHARS + 10);
+    let quote = quote_prefill("Ana", &long);
+    assert_eq!(
+        quote,
+        format!("> Ana: {}...\n", "á".repeat(QUOTE_MAX_CHARS))
+    );
+}
+
+#[test]
+fn user_lookup_cache_invalidates_when_users_change() {
+    let mut state = UiState::default();
+    state.set_users(vec![user("lia", true)]);
+    assert!(matches!(state.user_lookup(), Cow::Owned(_)));
+
+    state.refresh_user_lookup();
+    let cached = Arc::clone(&state.user_lookup);
+    assert!(matches!(state.user_lookup(), Cow::Borrowed(_)));
+    assert_eq!(state.user_lookup().name("lia"), Some("lia"));
+
+    // Sem mudança na lista, o cache é reaproveitado.
+    state.refresh_user_lookup();
+    assert!(Arc::ptr_eq(&cached, &state.user_lookup));
+
+    let mut renamed = user("lia", true);
+    renamed.name = "Lia Nova".to_string();
+    state.set_users(vec![renamed, user("bob", false)]);
+    assert!(matches!(state.user_lookup(), Cow::Owned(_)));
+    assert_eq!(state.user_lookup().name("lia"), Some("Lia Nova"));
+
+    state.refresh_user_lookup();
+    assert!(!Arc::ptr_eq(&cached, &state.user_lookup));
+    assert_eq!(state.user_lookup.name("bob"), Some("bob"));
+}
+
+#[test]
+fn copy_target_prefers_search_focus_then_newest_visible() {
+    let message = |id: u64, sender: &str, text: &str| UiMessage {
+        id,
+        sender: sender.to_string(),
+        text: text.to_string(),
+        ..Default::default()
+    };
+    let mut state = UiState {
+        messages: vec![
+            message(1, "id-bob", "primeira"),
+            message(2, "id-ana", "segunda"),
+            message(3, "System", "Ana connected"),
+        ],
+        ..Default::default()
+    };
+    assert_eq!(state.copy_target().map(|m| m.id), Some(3));
+
+    state.hide_system = true;
+    assert_eq!(state.copy_target().map(|m| m.id), Some(2));
+
+    state.search_match = Some(1);
+    assert_eq!(state.copy_target().map(|m| m.id), Some(1));
+
+    state.messages.clear();
+    state.search_match = None;
+    assert!(state.copy_target().is_none());
+}
+
+#[test]
+fn delete_is_only_enabled_for_the_local_users_messages() {
+    let message = |id: u64, sender: &str| UiMessage {
+        id,
+        sender: sender.to_string(),
+        text: format!("msg {id}"),
+        ..Default::default()
+    };
+    let mine = message(1, "id-ana");
+    let pending = UiMessage {
+        is_pending: true,
+        ..message(4, "id-ana")
+    };
+    assert!(can_delete(&mine, Some("id-ana")));
+    assert!(!can_delete(&mine, Some("id-bob")));
+    assert!(!can_delete(&mine, None));
+    assert!(!can_delete(&pending, Some("id-ana")));
+    assert!(!can_delete(&message(3, "System"), Some("id-ana")));
+
+    let mut state = UiState {
+        messages: vec![mine, message(2, "id-bob"), message(3, "id-ana"), pending],
+        ..Default::default()
+    };
+    state.step_delete_selection(true, Some("id-ana"));
+    assert_eq!(state.delete_selection, Some(3));
+    state.step_delete_selection(true, Some("id-ana"));
+    assert_eq!(state.delete_selection, Some(1));
+    state.step_delete_selection(false, Some("id-ana"));
+    assert_eq!(state.delete_selection, Some(3));
+    assert_eq!(state.scroll_to_message, Some(3));
+
+    // Mensagem alheia nunca vira alvo, nem se a seleção apontar para ela.
+    state.delete_selection = Some(2);
+    assert!(state.delete_target(Some("id-ana")).is_none());
+
+    state.delete_selection = None;
+    state.step_delete_selection(true, Some("id-carol"));
+    assert_eq!(state.delete_selection, None);
+}
+
+#[test]
+fn quote_previous_message_walks_back_and_keeps_the_reply() {
+    let message = |id: u64, sender: &str, text: &str| UiMessage {
+        id,
+        sender: sender.to_string(),
+        text: text.to_string(),
+        ..Default::default()
+    };
+    let mut state = UiState {
+        users: vec![UiUser {
+            identity: "id-ana".to_string(),
+            name: "Ana".to_string(),
+            ..Default::default()
+        }],
+        messages: vec![
+            message(1, "id-bob", "primeira"),
+            message(2, "id-ana", "segunda"),
+            message(3, "System", "Ana connected"),
+        ],
+        input: "concordo".to_string(),
+        ..Default::default()
+    };
+
+    state.quote_previous_message();
+    assert_eq!(state.input, "> Ana: segunda\nconcordo");
+    assert_eq!(state.cursor, state.input.len());
+
+    // Repetir troca a citação pela mensagem anterior, sem perder a resposta.
+    state.quote_previous_message();
+    assert_eq!(state.input, "> id-bob: primeira\nconcordo");
+
+    // Passando da mais antiga, recomeça pela mais recente.
+    state.quote_previous_message();
+    assert_eq!(state.input, "> Ana: segunda\nconcordo");
+
+    state.clear_input();
+    assert_eq!(state.quoted_message_id, None);
+}
+
+#[test]
+fn input_history_cycles_and_restores_the_draft() {
+    let mut state = UiState::default();
+    state.push_input_history("primeira");
+    state.push_input_history("segunda");
+    state.push_input_history("segunda");
+    state.push_input_history("");
+    assert_eq!(state.input_history, vec!["primeira", "segunda"]);
+
+    state.input = "rascunho".to_string();
+    state.history_previous();
+    assert_eq!((state.input.as_str(), state.cursor), ("segunda", 7));
+    state.history_previous();
+    state.history_previous();
+    assert_eq!(state.input, "primeira");
+
+    state.history_next();
+    assert_eq!(state.input, "segunda");
+    state.history_next();
+    assert_eq!(
+        (state.input.as_str(), state.history_cursor),
+        ("rascunho", None)
+    );
+    state.history_next();
+    assert_eq!(state.input, "rascunho");
+}
+
+#[test]
+fn input_history_is_capped() {
+    let mut state = UiState::default();
+    for i in 0..INPUT_HISTORY_LIMIT + 5 {
+        state.push_input_history(&format!("msg {i}"));
+    }
+    assert_eq!(state.input_history.len(), INPUT_HISTORY_LIMIT);
+    assert_eq!(state.input_history[0], "msg 5");
+}
+
+#[test]
+fn tab_completion_cycles_through_online_names() {
+    let mut state = UiState {
+        users: vec![
+            user("Aelric", true),
+            user("Ana", true),
+            user("Aeryn", false),
+        ],
+        input: "oi @a".to_string(),
+        ..Default::default()
+    };
+
+    state.complete_name_at_end();
+    assert_eq!(state.input, "oi @Aelric");
+    assert_eq!(state.cursor, state.input.len());
+    state.complete_name_at_end();
+    assert_eq!(state.input, "oi @Ana");
+    state.complete_name_at_end();
+    assert_eq!(state.input, "oi @Aelric");
+
+    // Depois de editar, o Tab recomeça pela palavra atual.
+    state.input = "olá an".to_string();
+    state.complete_name_at_end();
+    assert_eq!(state.input, "olá Ana");
+
+    state.input = "nada xyz".to_string();
+    state.complete_name_at_end();
+    assert_eq!(state.input, "nada xyz");
+    assert_eq!(state.name_completion, None);
+}
+
+#[test]
+fn rejected_send_restores_input_and_flags_the_error() {
+    let mut state = UiState::default();
+    state.restore_rejected_send("mensagem longa demais", "message too long");
+    assert_eq!(state.input, "mensagem longa demais");
+    assert_eq!(state.cursor, state.input.len());
+    assert_eq!(state.input_error.as_deref(), Some("message too long"));
+
+    // Editar limpa o indicador.
+    state.insert_at_cursor('!');
+    assert_eq!(state.input_error, None);
+}
+
+#[test]
+fn rejected_send_keeps_text_typed_in_the_meantime() {
+    let mut state = UiState {
+        input: "próxima".to_string(),
+        ..Default::default()
+    };
+    state.restore_rejected_send("anterior", "rate limited");
+    assert_eq!(state.input, "próxima");
+    assert_eq!(state.input_error.as_deref(), Some("rate limited"));
+}
+
+fn searchable_state() -> UiState {
+    let message = |id: u64, sender: &str, text: &str| UiMessage {
+        id,
+        sender: sender.to_string(),
+        text: text.to_string(),
+        ..Default::default()
+    };
+    UiState {
+        messages: vec![
+            message(1, "id_lia", "a taverna abriu"),
+            message(2, "id_rafa", "bom dia"),
+            message(3, "System", "Taverna fechada para limpeza"),
+            message(4, "id_lia", "vamos na TAVERNA?"),
+        ],
+        search_input: "taverna".to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn start_search_focuses_the_newest_match_and_closes_popup() {
+    let mut state = searchable_state();
+    state.popup = Some(UiPopup::Search);
+    state.start_search();
+    assert!(state.popup.is_none());
+    assert_eq!(state.search_query.as_deref(), Some("taverna"));
+    assert_eq!(state.search_match, Some(4));
+    assert_eq!(state.scroll_to_message, Some(4));
+    assert!(state.search_input.is_empty());
+}
+
+#[test]
+fn step_search_walks_matches_and_stops_at_the_ends() {
+    let mut state = searchable_state();
+    state.start_search();
+    state.step_search(true);
+    assert_eq!(state.search_match, Some(3));
+    state.step_search(true);
+    state.step_search(true);
+    assert_eq!(state.search_match, Some(1));
+    state.step_search(false);
+    assert_eq!(state.search_match, Some(3));
+
+    // Avisos ocultos saem da busca.
+    state.hide_system = true;
+    state.search_match = None;
+    state.step_search(true);
+    state.step_search(true);
+    assert_eq!(state.search_match, Some(1));
+    state.step_search(false);
+    assert_eq!(state.search_match, Some(4));
+}
+
+#[test]
+fn empty_search_ends_the_search() {
+    let mut state = searchable_state();
+    state.start_search();
+    state.search_input = "  ".to_string();
+    state.start_search();
+    assert_eq!(state.search_query, None);
+    assert_eq!(state.search_match, None);
+}
+
+#[test]
+fn unread_count_tracks_messages_after_the_last_read_one() {
+    let message = |id: u64, sender: &str| UiMessage {
+        id,
+        sender: sender.to_string(),
+        text: "oi".to_string(),
+        ..Default::default()
+    };
+    let mut state = UiState {
+        messages: vec![message(1, "id-ana"), message(2, "id-bob")],
+        ..Default::default()
+    };
+    state.mark_messages_read();
+    assert_eq!(state.last_read_message_id, 2);
+    assert_eq!(state.unread_count(), 0);
+
+    // Avisos do sistema e ecos pendentes não contam.
+    state.messages.push(message(3, "id-ana"));
+    state.messages.push(message(4, "id-bob"));
+    state.messages.push(message(u64::MAX, "System"));
+    state.messages.push(UiMessage {
+        is_pending: true,
+        ..message(u64::MAX - 1, "id-me")
+    });
+    assert_eq!(state.unread_count(), 2);
+
+    state.mark_messages_read();
+    assert_eq!(state.last_read_message_id, 4);
+    assert_eq!(state.unread_count(), 0);
+}
+
+#[test]
+fn panel_at_hit_tests_the_last_rendered_areas() {
+    let state = UiState {
+        messages_area: Rect::new(0, 3, 70, 20),
+        users_area: Rect::new(70, 3, 30, 20),
+        ..Default::default()
+    };
+    assert_eq!(state.panel_at(10, 3), Some(ChatPanel::Messages));
+    assert_eq!(state.panel_at(69, 22), Some(ChatPanel::Messages));
+    assert_eq!(state.panel_at(70, 10), Some(ChatPanel::Users));
+    // Cabeçalho e input ficam fora dos painéis.
+    assert_eq!(state.panel_at(10, 2), None);
+    assert_eq!(state.panel_at(10, 23), None);
+
+    // Antes do primeiro render não há área nenhuma.
+    assert_eq!(UiState::default().panel_at(0, 0), None);
+}
+
+fn text_message(id: u64, text: &str) -> UiMessage {
+    UiMessage {
+        id,
+        sender: "id_lia".to_string(),
+        text: text.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn find_matching_message_indices_ignores_case_and_empty_queries() {
+    let messages = [
+        text_message(1, "O Dragão acordou"),
+        text_message(2, "bom dia"),
+        text_message(3, "cuidado com o DRAGÃO"),
+    ];
+    let refs: Vec<&UiMessage> = messages.iter().collect();
+    assert_eq!(find_matching_message_indices(&refs, "dragão"), vec![0, 2]);
+    assert_eq!(find_matching_message_indices(&refs, "  DIA "), vec![1]);
+    assert!(find_matching_message_indices(&refs, "taverna").is_empty());
+    assert!(find_matching_message_indices(&refs, "   ").is_empty());
+}
@@ -0,0 +1,35 @@
+//! Cópia de texto para a área de transferência do sistema.
+//!
+//! Usa o `arboard` quando a feature `clipboard` está ativa; sem ela, ou sem um
+//! backend disponível (ex.: terminal sem X11/Wayland), a cópia só retorna erro.
+
+#[cfg(feature = "clipboard")]
+use std::sync::Mutex;
+
+/// Mantida viva durante o app: no Linux o conteúdo some quando ela é descartada.
+#[cfg(feature = "clipboard")]
+static CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+
+/// Copia `text` para a área de transferência; nunca entra em pânico.
+#[cfg(feature = "clipboard")]
+pub fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    let mut clipboard = CLIPBOARD
+        .lock()
+        .map_err(|_| "clipboard unavailable".to_string())?;
+    let backend = match clipboard.as_mut() {
+        Some(backend) => backend,
+        None => clipboard.insert(arboard::Clipboard::new().map_err(|err| err.to_string())?),
+    };
+    if let Err(err) = backend.set_text(text) {
+        // Descarta o backend para tentar de novo do zero na próxima cópia.
+        *clipboard = None;
+        return Err(err.to_string());
+    }
+    Ok(())
+}
+
+/// Copia `text` para a área de transferência; nunca entra em pânico.
+#[cfg(not(feature = "clipboard"))]
+pub fn copy_to_clipboard(_text: &str) -> Result<(), String> {
+    Err("clipboard support not compiled in".to_string())
+}
@@ -0,0 +1,150 @@
+/// Comando digitado no input do chat (texto começando com `/`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    /// `/name <novo nome>`: troca o nome sem sair do chat.
+    Name(String),
+    /// `/clear`: apaga os avisos locais do sistema.
+    Clear,
+    /// `/quit`: fecha o app.
+    Quit,
+    /// `/help`: abre o popup de ajuda.
+    Help,
+    /// `/hideoffline`: alterna a exibição de usuários offline.
+    HideOffline,
+    /// `/botsquiet`: alterna os bots entre conversa livre e só quando chamados.
+    BotsQuiet,
+    /// `/w <nome> <texto>`: mensagem privada.
+    Whisper { to: String, text: String },
+    /// `/status [texto]`: define (ou limpa, sem texto) o status.
+    Status(String),
+    /// `/title [texto]` no próprio usuário; `/title @nome [texto]` (host) em outro.
+    Title {
+        target: Option<String>,
+        title: String,
+    },
+    /// `/roll [NdM]`: rola dados e publica o resultado.
+    Roll(String),
+    /// `/react <emoji>`: alterna uma reação na mensagem mais recente.
+    React(String),
+    /// `/announce <texto>`: aviso do host.
+    Announce(String),
+    /// `/preview <bot> [pergunta]`: resposta de amostra de um bot, só local.
+    Preview { bot: String, prompt: String },
+    /// `/ai <texto>` ou `/ask <bot> <texto>`: envia o texto e garante a resposta de um bot.
+    Ask { bot: Option<String>, text: String },
+    /// `/connect <uri> <module>`: troca de servidor sem reiniciar o app.
+    Connect { uri: String, module: String },
+    /// Comando conhecido com argumentos faltando; carrega o uso correto.
+    Usage(&'static str),
+}
+
+/// Uso e descrição de cada comando, na ordem exibida pelo `/help`.
+pub const COMMAND_HELP: &[(&str, &str)] = &[
+    ("/name <name>", "change your name"),
+    ("/w <name> <text>", "private message"),
+    ("/status [text]", "set or clear your status"),
+    ("/title [text]", "set or clear your title"),
+    ("/title @<name> [text]", "set someone's title (host)"),
+    ("/react <emoji>", "react to the latest message"),
+    ("/roll NdM", "roll dice (e.g. 2d6)"),
+    ("/announce <text>", "host announcement"),
+    ("/preview <bot> [prompt]", "sample a bot reply locally"),
+    ("/ai <text>", "say something a bot will answer"),
+    ("/ask <bot> <text>", "say something a given bot will answer"),
+    ("/connect <uri> <module>", "switch to another server"),
+    ("/botsquiet", "bots reply only when called"),
+    ("/hideoffline", "toggle offline users"),
+    ("/clear", "clear local system messages"),
+    ("/help", "show this help"),
+    ("/quit", "close the app"),
+];
+
+/// Interpreta o input do chat; `None` quando é texto comum (ou `/` desconhecido).
+///
+/// Espera o texto já sem espaços nas pontas. Comandos conhecidos com
+/// argumentos faltando (ou sobrando, nos que não aceitam) viram `Usage`.
+pub fn parse_command(input: &str) -> Option<Command> {
+    let (word, rest) = input
+        .split_once(char::is_whitespace)
+        .map_or((input, ""), |(word, rest)| (word, rest.trim()));
+    let no_args = |command: Command, usage: &'static str| {
+        Some(if rest.is_empty() {
+            command
+        } else {
+            Command::Usage(usage)
+        })
+    };
+    let required = |usage: &'static str, build: fn(String) -> Command| {
+        Some(if rest.is_empty() {
+            Command::Usage(usage)
+        } else {
+            build(rest.to_string())
+        })
+    };
+
+    match word {
+        "/name" => required("/name <name>", Command::Name),
+        "/clear" => no_args(Command::Clear, "/clear"),
+        "/quit" => no_args(Command::Quit, "/quit"),
+        "/help" => no_args(Command::Help, "/help"),
+        "/hideoffline" => no_args(Command::HideOffline, "/hideoffline"),
+        "/botsquiet" => no_args(Command::BotsQuiet, "/botsquiet"),
+        "/w" => Some(match rest.split_once(char::is_whitespace) {
+            Some((to, text)) => Command::Whisper {
+                to: to.to_string(),
+                text: text.trim().to_string(),
+            },
+            None => Command::Usage("/w <name> <text>"),
+        }),
+        "/status" => Some(Command::Status(rest.to_string())),
+        "/title" => Some(match rest.strip_prefix('@') {
+            Some("") => Command::Usage("/title @<name> [text]"),
+            Some(target) => {
+                let (name, title) = target
+                    .split_once(char::is_whitespace)
+                    .unwrap_or((target, ""));
+                Command::Title {
+                    target: Some(name.to_string()),
+                    title: title.trim().to_string(),
+                }
+            }
+            None => Command::Title {
+                target: None,
+                title: rest.to_string(),
+            },
+        }),
+        "/roll" => Some(Command::Roll(rest.to_string())),
+        "/react" => required("/react <emoji>", Command::React),
+        "/announce" => required("/announce <text>", Command::Announce),
+        "/preview" => {
+            if rest.is_empty() {
+                return Some(Command::Usage("/preview <bot> [prompt]"));
+            }
+            let (bot, prompt) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            Some(Command::Preview {
+                bot: bot.to_string(),
+                prompt: prompt.trim().to_string(),
+            })
+        }
+        "/ai" => required("/ai <text>", |text| Command::Ask { bot: None, text }),
+        "/ask" => Some(match rest.split_once(char::is_whitespace) {
+            Some((bot, text)) => Command::Ask {
+                bot: Some(bot.trim_start_matches('@').to_string()),
+                text: text.trim().to_string(),
+            },
+            None => Command::Usage("/ask <bot> <text>"),
+        }),
+        "/connect" => Some(match rest.split_whitespace().collect::<Vec<_>>()[..] {
+            [uri, module] => Command::Connect {
+                uri: uri.to_string(),
+                module: module.to_string(),
+            },
+            _ => Command::Usage("/connect <uri> <module>"),
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+#[path = "../tests/commands_tests.rs"]
+mod tests;
@@ -0,0 +1,120 @@
+//! Exporta o chat atual para arquivo: transcrição legível (`keybindings.export`)
+//! ou JSON para ferramentas (`keybindings.export_json`, ou `--export-json FILE` na saída).
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+
+use crate::ui::{
+    ui_message_screen::format_message_datetime,
+    ui_state::{UiMessage, UiUser},
+};
+
+/// Grava em `path` a transcrição legível de `messages`, com nomes no lugar das identities.
+pub fn export_chat(messages: &[UiMessage], users: &[UiUser], path: &Path) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write_transcript(&mut out, messages, users)?;
+    out.flush()
+}
+
+/// Uma linha por mensagem (`[data hora] nome: texto`); quebras de linha do
+/// texto viram linhas recuadas. Ecos ainda não confirmados ficam de fora.
+pub fn write_transcript(
+    out: &mut impl Write,
+    messages: &[UiMessage],
+    users: &[UiUser],
+) -> io::Result<()> {
+    let names = user_names(users);
+    for message in messages.iter().filter(|m| !m.is_pending) {
+        let sender = sender_name(&names, &message.sender);
+        let time = format_message_datetime(&message.sent_at)
+            .map(|time| format!("[{time}] "))
+            .unwrap_or_default();
+        let kind = if message.is_announcement {
+            "Announcement from "
+        } else {
+            ""
+        };
+        let edited = if message.is_edited { " (edited)" } else { "" };
+        let mut lines = message.text.lines();
+        writeln!(
+            out,
+            "{time}{kind}{sender}: {}{edited}",
+            lines.next().unwrap_or_default()
+        )?;
+        for line in lines {
+            writeln!(out, "    {line}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Mensagem no JSON exportado: os campos de [`UiMessage`] mais o nome de quem enviou.
+#[derive(Serialize)]
+pub struct ExportedMessage<'a> {
+    #[serde(flatten)]
+    pub message: &'a UiMessage,
+    pub sender_name: &'a str,
+}
+
+/// Conteúdo do JSON exportado.
+#[derive(Serialize)]
+pub struct ChatExport<'a> {
+    pub users: &'a [UiUser],
+    pub messages: Vec<ExportedMessage<'a>>,
+}
+
+/// Monta o export das mensagens confirmadas, com `sent_at` cru e nomes resolvidos.
+pub fn chat_export<'a>(messages: &'a [UiMessage], users: &'a [UiUser]) -> ChatExport<'a> {
+    let names = user_names(users);
+    let messages = messages
+        .iter()
+        .filter(|m| !m.is_pending)
+        .map(|message| ExportedMessage {
+            message,
+            sender_name: sender_name(&names, &message.sender),
+        })
+        .collect();
+    ChatExport { users, messages }
+}
+
+/// Grava em `path` o chat em JSON (ver [`chat_export`]).
+pub fn export_chat_json(messages: &[UiMessage], users: &[UiUser], path: &Path) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut out, &chat_export(messages, users))?;
+    writeln!(out)?;
+    out.flush()
+}
+
+/// Arquivo no diretório atual com data e hora no nome: `shellrelay-chat-20261017-140500.txt`
+/// (ou `.json`).
+pub fn timestamped_export_path(now: DateTime<Local>, extension: &str) -> PathBuf {
+    PathBuf::from(format!(
+        "shellrelay-chat-{}.{extension}",
+        now.format("%Y%m%d-%H%M%S")
+    ))
+}
+
+/// identity -> nome, só para quem tem nome definido.
+fn user_names(users: &[UiUser]) -> HashMap<&str, &str> {
+    users
+        .iter()
+        .filter(|u| !u.name.trim().is_empty())
+        .map(|u| (u.identity.as_str(), u.name.trim()))
+        .collect()
+}
+
+/// Nome de quem enviou; sem nome conhecido fica a identity (ou "System").
+fn sender_name<'a>(names: &HashMap<&str, &'a str>, sender: &'a str) -> &'a str {
+    names.get(sender).copied().unwrap_or(sender)
+}
+
+#[cfg(test)]
+#[path = "../tests/export_tests.rs"]
+mod tests;
@@ -0,0 +1,70 @@
+//! Máscara de palavras no render (`--filter-file`), para transmissões sem palavrão.
+//!
+//! Só muda o que é exibido: o texto guardado e enviado continua intacto.
+
+use std::{borrow::Cow, fs, path::Path};
+
+/// Troca por `*` as palavras inteiras de `text` que estão em `words`, sem
+/// diferenciar caixa; cada letra vira um asterisco, então o tamanho se mantém.
+///
+/// `words` vem em minúsculas (`parse_filter_words`). Palavras que só contêm
+/// uma da lista (`"classe"` com `"class"`) ficam como estão.
+pub fn mask_profanity(text: &str, words: &[String]) -> String {
+    if words.is_empty() {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut word_start = None;
+    let flush = |out: &mut String, word: &str| {
+        let lowered = word.to_lowercase();
+        if words.contains(&lowered) {
+            out.extend(word.chars().map(|_| '*'));
+        } else {
+            out.push_str(word);
+        }
+    };
+    for (index, ch) in text.char_indices() {
+        if ch.is_alphanumeric() {
+            word_start.get_or_insert(index);
+            continue;
+        }
+        if let Some(start) = word_start.take() {
+            flush(&mut out, &text[start..index]);
+        }
+        out.push(ch);
+    }
+    if let Some(start) = word_start {
+        flush(&mut out, &text[start..]);
+    }
+    out
+}
+
+/// `mask_profanity` para a tela: sem lista, devolve o próprio texto sem copiar.
+pub fn mask_for_display<'a>(text: &'a str, words: &[String]) -> Cow<'a, str> {
+    if words.is_empty() {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(mask_profanity(text, words))
+    }
+}
+
+/// Uma palavra por linha, já em minúsculas; linhas vazias e comentários (`#`)
+/// são ignorados.
+pub fn parse_filter_words(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Lê a lista de palavras de `path`.
+pub fn load_filter_words(path: &Path) -> Result<Vec<String>, String> {
+    fs::read_to_string(path)
+        .map(|text| parse_filter_words(&text))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+#[path = "../tests/filter_tests.rs"]
+mod tests;
@@ -0,0 +1,109 @@
+use ratatui::{
+    symbols::{bar, border},
+    widgets::{Block, Borders},
+};
+
+use crate::ui::spinner::{ASCII_SPINNER_FRAMES, SPINNER_FRAMES, spinner_frame_in};
+
+/// Símbolos desenhados na TUI (presença, setas, bordas...).
+///
+/// O modo de acessibilidade (`ui.accessibility`) troca todos por ASCII, para
+/// leitores de tela e terminais sem suporte a Unicode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Glyphs {
+    pub online: &'static str,
+    pub offline: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+    pub ellipsis: &'static str,
+    /// Ícone do "Load older messages".
+    pub reload: &'static str,
+    /// Separador entre nome e status no painel de usuários.
+    pub separator: &'static str,
+    /// Traço das laterais do rodapé de instruções.
+    pub footer_line: &'static str,
+    pub spinner: &'static [&'static str],
+    pub border: border::Set<'static>,
+    /// Barras do sparkline de atividade.
+    pub bars: &'static bar::Set<'static>,
+}
+
+/// Barras do sparkline só com ASCII (três níveis).
+const ASCII_BARS: bar::Set<'static> = bar::Set {
+    full: "#",
+    seven_eighths: "#",
+    three_quarters: "#",
+    five_eighths: "=",
+    half: "=",
+    three_eighths: "=",
+    one_quarter: ".",
+    one_eighth: ".",
+    empty: " ",
+};
+
+/// Bordas de caixa só com ASCII.
+const ASCII_BORDER: border::Set<'static> = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+pub const UNICODE_GLYPHS: Glyphs = Glyphs {
+    online: "●",
+    offline: "○",
+    up: "↑",
+    down: "↓",
+    ellipsis: "…",
+    reload: "⟳",
+    separator: "·",
+    footer_line: "\u{2500}",
+    spinner: SPINNER_FRAMES,
+    border: border::PLAIN,
+    bars: &bar::NINE_LEVELS,
+};
+
+pub const ASCII_GLYPHS: Glyphs = Glyphs {
+    online: "[*]",
+    offline: "[ ]",
+    up: "^",
+    down: "v",
+    ellipsis: "...",
+    reload: "*",
+    separator: "-",
+    footer_line: "-",
+    spinner: ASCII_SPINNER_FRAMES,
+    border: ASCII_BORDER,
+    bars: &ASCII_BARS,
+};
+
+impl Glyphs {
+    /// Conjunto de símbolos do modo atual.
+    pub fn select(accessibility: bool) -> &'static Glyphs {
+        if accessibility {
+            &ASCII_GLYPHS
+        } else {
+            &UNICODE_GLYPHS
+        }
+    }
+
+    /// Bloco com borda completa desenhada com os símbolos do modo.
+    pub fn block(&self) -> Block<'static> {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(self.border)
+    }
+
+    /// Quadro do spinner para o tick informado.
+    pub fn spinner_frame(&self, tick: u64) -> &'static str {
+        spinner_frame_in(self.spinner, tick)
+    }
+}
+
+#[cfg(test)]
+#[path = "../tests/glyphs_tests.rs"]
+mod tests;
@@ -1,10 +1,17 @@
 use ratatui::{
     layout::Rect,
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::Paragraph,
 };
 
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    config::UiConfig,
+    ui::{glyphs::Glyphs, theme::Theme},
+};
+
 /// Item simples de instrução exibido no rodapé.
 #[derive(Clone, Copy)]
 pub struct InstructionItem<'a> {
@@ -12,63 +19,158 @@ pub struct InstructionItem<'a> {
     pub key: &'a str,
 }
 
+/// Separadores, colchetes e traço do rodapé (`ui.footer_*`), com o modo de glifos.
+pub struct FooterStyle<'a> {
+    pub separator: &'a str,
+    pub open: &'a str,
+    pub close: &'a str,
+    pub rule: &'a str,
+    /// Marca de itens que não couberam na largura.
+    pub ellipsis: &'a str,
+}
+
+impl<'a> FooterStyle<'a> {
+    pub fn new(config: &'a UiConfig, glyphs: &'a Glyphs) -> Self {
+        let brackets = config.footer_brackets.as_str();
+        let split = brackets.chars().next().map_or(0, char::len_utf8);
+        let (open, close) = brackets.split_at(split);
+        Self {
+            separator: &config.footer_separator,
+            open,
+            close,
+            rule: match config.footer_rule.as_str() {
+                "" => glyphs.footer_line,
+                rule => rule,
+            },
+            ellipsis: glyphs.ellipsis,
+        }
+    }
+}
+
 /// Renderiza barra de instruções centralizada com "linhas" laterais.
 pub fn render_instructions(
     frame: &mut ratatui::Frame<'_>,
     area: Rect,
     items: &[InstructionItem<'_>],
+    theme: &Theme,
+    glyphs: &Glyphs,
 ) {
     if area.width == 0 || area.height == 0 {
         return;
     }
 
+    let style = FooterStyle::new(&crate::config::current().ui, glyphs);
+    let spans = instruction_spans(items, area.width as usize, theme, &style);
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// Monta os spans do rodapé com as cores do tema e o estilo configurado.
+///
+/// Itens que não cabem em `total_width` saem do fim da lista, trocados pela reticência.
+fn instruction_spans<'a>(
+    items: &[InstructionItem<'a>],
+    total_width: usize,
+    theme: &Theme,
+    style: &FooterStyle<'_>,
+) -> Vec<Span<'a>> {
+    let shown = fitting_item_count(items, total_width, style);
+    let truncated = shown < items.len();
+    let items = &items[..shown];
+
     // Calcula quanto espaço sobrou para preencher com traços nas laterais.
-    let content_width = instruction_text_width(items);
-    let total_width = area.width as usize;
-    let side_len = total_width.saturating_sub(content_width + 2) / 2;
-    let side = "\u{2500}".repeat(side_len);
+    let mut content_width = instruction_text_width(items, style);
+    if truncated {
+        content_width += truncation_width(items, style);
+    }
+    let side_len = total_width.saturating_sub(content_width + 2) / 2 / style.rule.width().max(1);
+    let side = style.rule.repeat(side_len);
 
-    let mut spans: Vec<Span<'_>> = Vec::new();
+    let mut spans: Vec<Span<'a>> = Vec::new();
     if !side.is_empty() {
         spans.push(Span::styled(
             side.clone(),
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.footer_line),
         ));
         spans.push(Span::raw(" "));
     }
 
     for (idx, item) in items.iter().enumerate() {
         if idx > 0 {
-            spans.push(Span::raw("  "));
+            spans.push(Span::raw(style.separator.to_string()));
         }
-        spans.push(Span::styled(item.label, Style::default().fg(Color::Gray)));
+        spans.push(Span::styled(
+            item.label,
+            Style::default().fg(theme.footer_label),
+        ));
         spans.push(Span::raw(" "));
         spans.push(Span::styled(
-            format!("<{}>", item.key),
+            format!("{}{}{}", style.open, item.key, style.close),
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.footer_key)
                 .add_modifier(Modifier::BOLD),
         ));
     }
+    if truncated {
+        if !items.is_empty() {
+            spans.push(Span::raw(style.separator.to_string()));
+        }
+        spans.push(Span::styled(
+            style.ellipsis.to_string(),
+            Style::default().fg(theme.footer_label),
+        ));
+    }
 
     if !side.is_empty() {
         spans.push(Span::raw(" "));
-        spans.push(Span::styled(side, Style::default().fg(Color::DarkGray)));
+        spans.push(Span::styled(side, Style::default().fg(theme.footer_line)));
     }
 
-    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+    spans
+}
+
+/// Quantos itens (do início) cabem em `total_width`, reservando espaço para a
+/// reticência quando nem todos cabem.
+fn fitting_item_count(
+    items: &[InstructionItem<'_>],
+    total_width: usize,
+    style: &FooterStyle<'_>,
+) -> usize {
+    if instruction_text_width(items, style) <= total_width {
+        return items.len();
+    }
+    (0..items.len())
+        .rev()
+        .find(|&count| {
+            let shown = &items[..count];
+            instruction_text_width(shown, style) + truncation_width(shown, style) <= total_width
+        })
+        .unwrap_or(0)
+}
+
+/// Largura da reticência final (com separador se houver itens antes dela).
+fn truncation_width(shown: &[InstructionItem<'_>], style: &FooterStyle<'_>) -> usize {
+    let separator = if shown.is_empty() {
+        0
+    } else {
+        style.separator.width()
+    };
+    separator + style.ellipsis.width()
 }
 
 /// Mede largura textual do conjunto de instruções para centralização.
-fn instruction_text_width(items: &[InstructionItem<'_>]) -> usize {
+fn instruction_text_width(items: &[InstructionItem<'_>], style: &FooterStyle<'_>) -> usize {
     let mut width = 0;
     for (idx, item) in items.iter().enumerate() {
         if idx > 0 {
-            width += 2;
+            width += style.separator.width();
         }
-        width += item.label.chars().count();
+        width += item.label.width();
         width += 1;
-        width += item.key.chars().count() + 2;
+        width += style.open.width() + item.key.width() + style.close.width();
     }
     width
 }
+
+#[cfg(test)]
+#[path = "../tests/instructions_tests.rs"]
+mod tests;
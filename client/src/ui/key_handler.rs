@@ -1,11 +1,27 @@
-use crossterm::event::{self, KeyCode, KeyEvent, KeyModifiers};
+use std::time::Instant;
+
+use chrono::Local;
+use crossterm::event::{self, KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use spacetimedb_sdk::Identity;
 
 use crate::{
+    ai::BotReplyMode,
+    config::{self, key_matches},
+    dice::{format_roll, parse_dice, roll_dice},
     module_bindings::{
-        DbConnection, send_message as SendMessageReducerExt, set_name as SetNameReducerExt,
+        DbConnection, announce as AnnounceReducerExt, delete_account as DeleteAccountReducerExt,
+        delete_message as DeleteMessageReducerExt, send_direct as SendDirectReducerExt,
+        send_message as SendMessageReducerExt, set_name as SetNameReducerExt,
+        set_status as SetStatusReducerExt, set_title as SetTitleReducerExt,
+        set_typing as SetTypingReducerExt, toggle_reaction as ToggleReactionReducerExt,
     },
+    state::AppState,
     state::{SharedState, update_state},
-    ui::ui_state::{MainMenuItem, UiPopup, UiScreen},
+    sync::{add_local_system_message, add_pending_message, clear_local_system_messages},
+    ui::clipboard::copy_to_clipboard,
+    ui::commands::{Command, parse_command},
+    ui::export::{export_chat, export_chat_json, timestamped_export_path},
+    ui::ui_state::{ChatPanel, MAX_NAME_LEN, MainMenuItem, ScrollPosition, UiPopup, UiScreen},
 };
 
 /// Roteador principal de teclado.
@@ -73,10 +89,10 @@ fn handle_menu_key(key: KeyEvent, state: &SharedState) -> Result<(), Box<dyn std
                 match MainMenuItem::from_index(s.ui.menu_selected) {
                     MainMenuItem::EnterChat => {
                         s.ui.popup = Some(UiPopup::ChooseName);
-                        s.ui.input.clear();
+                        s.ui.clear_input();
                     }
                     MainMenuItem::Options => {
-                        s.ui.popup = Some(UiPopup::Soon);
+                        s.ui.popup = Some(UiPopup::Options);
                     }
                     MainMenuItem::Exit => {
                         s.ui.should_quit = true;
@@ -102,20 +118,23 @@ fn handle_popup_key(
             KeyCode::Esc => {
                 update_state(state, |s| {
                     s.ui.popup = None;
-                    s.ui.input.clear();
+                    s.ui.name_error = None;
+                    s.ui.clear_input();
                 });
             }
             KeyCode::Backspace => {
-                update_state(state, |s| {
-                    s.ui.input.pop();
-                });
+                update_state(state, |s| s.ui.delete_before_cursor());
             }
+            KeyCode::Left => update_state(state, |s| s.ui.move_cursor_left()),
+            KeyCode::Right => update_state(state, |s| s.ui.move_cursor_right()),
+            KeyCode::Home => update_state(state, |s| s.ui.move_cursor_home()),
+            KeyCode::End => update_state(state, |s| s.ui.move_cursor_end()),
             KeyCode::Enter => {
                 // Confirma nome e entra no chat.
                 let name = {
                     let mut guard = state.lock().expect("state poisoned");
                     let text = guard.ui.input.trim().to_string();
-                    guard.ui.input.clear();
+                    guard.ui.clear_input();
                     text
                 };
 
@@ -127,6 +146,7 @@ fn handle_popup_key(
                 if reducer_res.is_ok() {
                     update_state(state, |s| {
                         s.ui.popup = None;
+                        s.ui.name_error = None;
                         s.ui.screen = UiScreen::Chat;
                         s.ui.users_scroll = 0;
                     });
@@ -136,22 +156,100 @@ fn handle_popup_key(
                     });
                 }
             }
-            KeyCode::Char(c) => {
-                // Entrada textual simples (sem Ctrl/Alt).
+            // Entrada textual simples (sem Ctrl/Alt), limitada a `MAX_NAME_LEN`.
+            KeyCode::Char(c)
                 if !key.modifiers.contains(KeyModifiers::CONTROL)
-                    && !key.modifiers.contains(KeyModifiers::ALT)
-                {
-                    update_state(state, |s| s.ui.input.push(c));
-                }
+                    && !key.modifiers.contains(KeyModifiers::ALT) =>
+            {
+                update_state(state, |s| {
+                    if s.ui.input.chars().count() < MAX_NAME_LEN {
+                        s.ui.insert_at_cursor(c);
+                    }
+                });
+            }
+            _ => {}
+        },
+        UiPopup::Search => match key.code {
+            KeyCode::Esc => {
+                update_state(state, |s| {
+                    s.ui.popup = None;
+                    s.ui.search_input.clear();
+                });
+            }
+            KeyCode::Enter => update_state(state, |s| s.ui.start_search()),
+            KeyCode::Backspace => {
+                update_state(state, |s| {
+                    s.ui.search_input.pop();
+                });
+            }
+            KeyCode::Char(c)
+                if !key.modifiers.contains(KeyModifiers::CONTROL)
+                    && !key.modifiers.contains(KeyModifiers::ALT) =>
+            {
+                update_state(state, |s| s.ui.search_input.push(c));
             }
             _ => {}
         },
-        UiPopup::Soon => match key.code {
+        UiPopup::Help => match key.code {
+            KeyCode::Esc | KeyCode::Enter => {
+                update_state(state, |s| s.ui.popup = None);
+            }
+            // `??` fecha a ajuda e começa a mensagem com um `?` literal.
+            KeyCode::Char('?') => {
+                update_state(state, |s| {
+                    s.ui.popup = None;
+                    s.ui.insert_at_cursor('?');
+                });
+            }
+            _ => {}
+        },
+        UiPopup::Options => match key.code {
+            KeyCode::Char('d') | KeyCode::Char('D') => {
+                update_state(state, |s| s.ui.popup = Some(UiPopup::ConfirmDeleteAccount));
+            }
             KeyCode::Enter | KeyCode::Esc => {
                 update_state(state, |s| s.ui.popup = None);
             }
             _ => {}
         },
+        UiPopup::ConfirmDeleteAccount => match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                // A desconexão acontece no callback, depois da confirmação do servidor.
+                let reducer_res = conn.reducers.delete_account();
+                update_state(state, |s| {
+                    s.ui.popup = None;
+                    if reducer_res.is_err() {
+                        s.status = false;
+                    }
+                });
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                update_state(state, |s| s.ui.popup = Some(UiPopup::Options));
+            }
+            _ => {}
+        },
+        UiPopup::ConfirmDeleteMessage => match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                // A mensagem some da tela quando o `on_delete` da tabela ressincroniza.
+                let target = {
+                    let mut guard = state.lock().expect("state poisoned");
+                    let me = guard.my_identity.clone();
+                    let id = guard.ui.delete_target(me.as_deref()).map(|m| m.id);
+                    guard.ui.popup = None;
+                    guard.ui.delete_selection = None;
+                    id
+                };
+                if let Some(id) = target
+                    && conn.reducers.delete_message(id).is_err()
+                {
+                    update_state(state, |s| s.status = false);
+                }
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                update_state(state, |s| s.ui.popup = None);
+            }
+            _ => {}
+        },
     }
 
     Ok(())
@@ -166,29 +264,128 @@ fn handle_chat_key(
     conn: &DbConnection,
     state: &SharedState,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let keybindings = &config::current().keybindings;
     match key.code {
+        _ if key_matches(&keybindings.reroll_bots, &key) => {
+            update_state(state, |s| s.ui.reroll_bots_requested = true);
+        }
+        _ if key_matches(&keybindings.menu, &key) => {
+            update_state(state, |s| {
+                s.ui.screen = UiScreen::MainMenu;
+                s.ui.popup = None;
+                s.ui.clear_input();
+            });
+        }
+        _ if key_matches(&keybindings.help, &key) => {
+            update_state(state, |s| s.ui.popup = Some(UiPopup::Help));
+        }
+        // `?` no input vazio abre a ajuda; com texto, é só um caractere.
+        KeyCode::Char('?') if state.lock().is_ok_and(|s| s.ui.input.is_empty()) => {
+            update_state(state, |s| s.ui.popup = Some(UiPopup::Help));
+        }
+        _ if key_matches(&keybindings.search, &key) => {
+            update_state(state, |s| {
+                s.ui.search_input = s.ui.search_query.clone().unwrap_or_default();
+                s.ui.popup = Some(UiPopup::Search);
+            });
+        }
+        // Com busca ativa, os atalhos navegam entre as ocorrências; Esc (input vazio) encerra.
+        _ if key_matches(&keybindings.search_next, &key) => {
+            update_state(state, |s| s.ui.step_search(true));
+        }
+        _ if key_matches(&keybindings.search_previous, &key) => {
+            update_state(state, |s| s.ui.step_search(false));
+        }
+        KeyCode::Esc if search_keys_active(state) => {
+            update_state(state, |s| s.ui.clear_search());
+        }
+        _ if key_matches(&keybindings.copy, &key) => {
+            copy_visible_message(state);
+        }
+        _ if key_matches(&keybindings.export, &key) => {
+            export_current_chat(state, ExportFormat::Transcript);
+        }
+        _ if key_matches(&keybindings.export_json, &key) => {
+            export_current_chat(state, ExportFormat::Json);
+        }
+        // Primeiro aperto seleciona a mensagem própria mais recente; o segundo pede confirmação.
+        _ if key_matches(&keybindings.delete_message, &key) => {
+            if delete_selection_active(state) {
+                confirm_delete_selection(state);
+            } else {
+                select_message_for_deletion(state);
+            }
+        }
+        KeyCode::Up if delete_selection_active(state) => {
+            update_state(state, |s| {
+                let me = s.my_identity.clone();
+                s.ui.step_delete_selection(true, me.as_deref());
+            });
+        }
+        KeyCode::Down if delete_selection_active(state) => {
+            update_state(state, |s| {
+                let me = s.my_identity.clone();
+                s.ui.step_delete_selection(false, me.as_deref());
+            });
+        }
+        KeyCode::Enter if delete_selection_active(state) => confirm_delete_selection(state),
+        KeyCode::Esc if delete_selection_active(state) => {
+            update_state(state, |s| s.ui.delete_selection = None);
+        }
+        _ if key_matches(&keybindings.system_messages, &key) => {
+            update_state(state, |s| s.ui.hide_system = !s.ui.hide_system);
+        }
+        _ if key_matches(&keybindings.timestamps, &key) => {
+            update_state(state, |s| {
+                s.ui.relative_timestamps = !s.ui.relative_timestamps
+            });
+        }
+        _ if key_matches(&keybindings.quote, &key) => {
+            update_state(state, |s| s.ui.quote_previous_message());
+        }
         KeyCode::PageUp => {
             update_state(state, |s| {
-                s.ui.messages_scroll_from_bottom =
-                    s.ui.messages_scroll_from_bottom.saturating_add(5);
+                s.ui.messages_scroll = s.ui.messages_scroll.scroll_up(5, s.ui.messages_max_scroll);
+                request_older_messages_if_at_top(s);
             });
         }
         KeyCode::PageDown => {
             update_state(state, |s| {
-                s.ui.messages_scroll_from_bottom =
-                    s.ui.messages_scroll_from_bottom.saturating_sub(5);
+                s.ui.messages_scroll =
+                    s.ui.messages_scroll
+                        .scroll_down(5, s.ui.messages_max_scroll);
             });
         }
+        // Com texto no input, Left/Right/Home/End editam; vazio, Home/End rolam as mensagens.
+        KeyCode::Left => update_state(state, |s| s.ui.move_cursor_left()),
+        KeyCode::Right => update_state(state, |s| s.ui.move_cursor_right()),
         KeyCode::Home => {
             update_state(state, |s| {
-                s.ui.messages_scroll_from_bottom = usize::MAX;
+                if s.ui.input.is_empty() {
+                    s.ui.messages_scroll = ScrollPosition::Top;
+                    request_older_messages_if_at_top(s);
+                } else {
+                    s.ui.move_cursor_home();
+                }
             });
         }
         KeyCode::End => {
             update_state(state, |s| {
-                s.ui.messages_scroll_from_bottom = 0;
+                if s.ui.input.is_empty() {
+                    s.ui.messages_scroll = ScrollPosition::Bottom;
+                    s.ui.mark_messages_read();
+                } else {
+                    s.ui.move_cursor_end();
+                }
             });
         }
+        // Alt+Up/Alt+Down percorrem o histórico do input; sem Alt, rolam os usuários.
+        KeyCode::Up if key.modifiers.contains(KeyModifiers::ALT) => {
+            update_state(state, |s| s.ui.history_previous());
+        }
+        KeyCode::Down if key.modifiers.contains(KeyModifiers::ALT) => {
+            update_state(state, |s| s.ui.history_next());
+        }
         KeyCode::Up => {
             update_state(state, |s| {
                 if s.ui.users_scroll > 0 {
@@ -198,51 +395,60 @@ fn handle_chat_key(
         }
         KeyCode::Down => {
             update_state(state, |s| {
-                if s.ui.users_scroll + 1 < s.ui.users.len() {
+                if s.ui.users_scroll + 1 < s.ui.visible_users().len() {
                     s.ui.users_scroll += 1;
                 }
             });
         }
-        KeyCode::F(1) => {
-            update_state(state, |s| {
-                s.ui.screen = UiScreen::MainMenu;
-                s.ui.popup = None;
-                s.ui.input.clear();
-            });
+        KeyCode::Tab => {
+            update_state(state, |s| s.ui.complete_name_at_end());
         }
         KeyCode::Esc => {
-            update_state(state, |s| s.ui.input.clear());
+            update_state(state, |s| s.ui.clear_input());
+            stop_typing(conn, state);
         }
         KeyCode::Backspace => {
-            update_state(state, |s| {
-                s.ui.input.pop();
-            });
+            update_state(state, |s| s.ui.delete_before_cursor());
+        }
+        // Shift+Enter (ou Alt+Enter, em terminais que não distinguem Shift) quebra a linha.
+        KeyCode::Enter
+            if key
+                .modifiers
+                .intersects(KeyModifiers::SHIFT | KeyModifiers::ALT) =>
+        {
+            update_state(state, |s| s.ui.insert_at_cursor('\n'));
         }
         KeyCode::Enter => {
             // Move conteúdo do input para uma string própria para evitar lock longo.
             let text = {
                 let mut guard = state.lock().expect("state poisoned");
                 let text = guard.ui.input.trim().to_string();
-                guard.ui.input.clear();
+                guard.ui.clear_input();
+                guard.ui.push_input_history(&text);
                 text
             };
+            stop_typing(conn, state);
 
             if text.is_empty() {
                 return Ok(());
             }
 
-            let reducer_res = conn.reducers.send_message(text.clone());
-            if reducer_res.is_err() {
-                update_state(state, |s| {
-                    s.status = false;
-                });
+            match parse_command(&text) {
+                Some(command) => run_command(command, conn, state),
+                None => send_chat_message(text, conn, state),
             }
         }
-        KeyCode::Char(c) => {
+        KeyCode::Char(c)
             if !key.modifiers.contains(KeyModifiers::CONTROL)
-                && !key.modifiers.contains(KeyModifiers::ALT)
-            {
-                update_state(state, |s| s.ui.input.push(c));
+                && !key.modifiers.contains(KeyModifiers::ALT) =>
+        {
+            update_state(state, |s| s.ui.insert_at_cursor(c));
+            let due = state
+                .lock()
+                .map(|mut s| s.ui.mark_typing(Instant::now()))
+                .unwrap_or(false);
+            if due {
+                let _ = conn.reducers.set_typing(true);
             }
         }
         _ => {}
@@ -250,3 +456,322 @@ fn handle_chat_key(
 
     Ok(())
 }
+
+/// Executa um comando `/...` digitado no chat.
+fn run_command(command: Command, conn: &DbConnection, state: &SharedState) {
+    let reducer_res = match command {
+        Command::Name(name) => conn.reducers.set_name(name),
+        Command::Clear => {
+            clear_local_system_messages(state);
+            Ok(())
+        }
+        Command::Quit => {
+            update_state(state, |s| s.ui.should_quit = true);
+            Ok(())
+        }
+        Command::Help => {
+            update_state(state, |s| s.ui.popup = Some(UiPopup::Help));
+            Ok(())
+        }
+        Command::HideOffline => {
+            update_state(state, |s| {
+                s.ui.hide_offline_users = !s.ui.hide_offline_users;
+                let visible = s.ui.visible_users().len();
+                s.ui.users_scroll = s.ui.users_scroll.min(visible.saturating_sub(1));
+            });
+            Ok(())
+        }
+        Command::BotsQuiet => {
+            update_state(state, |s| s.bot_reply_mode = s.bot_reply_mode.toggled());
+            let mode = state.lock().map(|s| s.bot_reply_mode).unwrap_or_default();
+            let notice = match mode {
+                BotReplyMode::SpokenToOnly => "Bots now reply only when called by name",
+                BotReplyMode::Ambient => "Bots are chatting freely again",
+            };
+            add_local_system_message(state, "System", notice);
+            Ok(())
+        }
+        Command::Whisper { to, text } => {
+            send_whisper(&to, text, conn, state);
+            Ok(())
+        }
+        Command::Status(status) => conn.reducers.set_status(status),
+        Command::Title { target, title } => {
+            set_title(target.as_deref(), title, conn, state);
+            Ok(())
+        }
+        // O resultado da rolagem é publicado como mensagem comum.
+        Command::Roll(spec) => {
+            if let Some(rolled) = roll_message(&spec, state) {
+                send_chat_message(rolled, conn, state);
+            }
+            Ok(())
+        }
+        Command::React(emoji) => {
+            react_to_latest(&emoji, conn, state);
+            Ok(())
+        }
+        // O servidor valida se quem anuncia é o host.
+        Command::Announce(text) => conn.reducers.announce(text),
+        Command::Preview { bot, prompt } => {
+            update_state(state, |s| s.ui.bot_preview_requested = Some((bot, prompt)));
+            Ok(())
+        }
+        // O texto vai para o chat como mensagem comum; o loop escolhe o bot que responde.
+        Command::Ask { bot, text } => {
+            send_chat_message(text.clone(), conn, state);
+            update_state(state, |s| s.ui.ai_reply_requested = Some((bot, text)));
+            Ok(())
+        }
+        Command::Connect { uri, module } => {
+            update_state(state, |s| s.ui.connect_requested = Some((uri, module)));
+            Ok(())
+        }
+        Command::Usage(usage) => {
+            add_local_system_message(state, "System", format!("usage: {usage}"));
+            Ok(())
+        }
+    };
+    if reducer_res.is_err() {
+        update_state(state, |s| s.status = false);
+    }
+}
+
+/// Publica `text` no chat com eco otimista até o servidor confirmar.
+fn send_chat_message(text: String, conn: &DbConnection, state: &SharedState) {
+    let res = conn.reducers.send_message(text.clone());
+    let my_identity = state.lock().ok().and_then(|s| s.my_identity.clone());
+    match (res, my_identity) {
+        (Ok(()), Some(me)) => add_pending_message(state, me, text),
+        (Ok(()), None) => {}
+        (Err(_), _) => update_state(state, |s| {
+            s.status = false;
+            s.ui.restore_rejected_send(&text, "not connected");
+        }),
+    }
+}
+
+/// Busca ativa com o input vazio: Esc encerra a busca em vez de agir no input.
+fn search_keys_active(state: &SharedState) -> bool {
+    state
+        .lock()
+        .map(|s| s.ui.search_query.is_some() && s.ui.input.is_empty())
+        .unwrap_or(false)
+}
+
+/// Seleção para apagar ativa: Up/Down/Enter/Esc agem sobre ela em vez do input.
+fn delete_selection_active(state: &SharedState) -> bool {
+    state
+        .lock()
+        .map(|s| s.ui.delete_selection.is_some())
+        .unwrap_or(false)
+}
+
+/// Seleciona a mensagem própria mais recente; sem nenhuma, avisa no chat.
+fn select_message_for_deletion(state: &SharedState) {
+    let selected = state
+        .lock()
+        .map(|mut s| {
+            let me = s.my_identity.clone();
+            s.ui.step_delete_selection(true, me.as_deref());
+            s.ui.delete_selection.is_some()
+        })
+        .unwrap_or(false);
+    if !selected {
+        add_local_system_message(state, "System", "no messages of yours to delete");
+    }
+}
+
+/// Abre a confirmação se a seleção ainda aponta para uma mensagem própria visível.
+fn confirm_delete_selection(state: &SharedState) {
+    update_state(state, |s| {
+        let me = s.my_identity.clone();
+        if s.ui.delete_target(me.as_deref()).is_some() {
+            s.ui.popup = Some(UiPopup::ConfirmDeleteMessage);
+        } else {
+            s.ui.delete_selection = None;
+        }
+    });
+}
+
+/// Copia o texto da mensagem alvo; sem área de transferência, avisa no chat.
+fn copy_visible_message(state: &SharedState) {
+    let text = state
+        .lock()
+        .ok()
+        .and_then(|s| s.ui.copy_target().map(|m| m.text.clone()));
+    let Some(text) = text else {
+        return;
+    };
+    if let Err(err) = copy_to_clipboard(&text) {
+        add_local_system_message(state, "System", format!("copy failed: {err}"));
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ExportFormat {
+    Transcript,
+    Json,
+}
+
+/// Grava o chat em `shellrelay-chat-<data>.txt` (ou `.json`) e avisa no chat.
+fn export_current_chat(state: &SharedState, format: ExportFormat) {
+    let Some((messages, users)) = state
+        .lock()
+        .ok()
+        .map(|s| (s.ui.messages.clone(), s.ui.users.clone()))
+    else {
+        return;
+    };
+    let extension = match format {
+        ExportFormat::Transcript => "txt",
+        ExportFormat::Json => "json",
+    };
+    let path = timestamped_export_path(Local::now(), extension);
+    let result = match format {
+        ExportFormat::Transcript => export_chat(&messages, &users, &path),
+        ExportFormat::Json => export_chat_json(&messages, &users, &path),
+    };
+    let text = match result {
+        Ok(()) => format!("Chat exported to {}", path.display()),
+        Err(err) => format!("Could not export chat to {} ({err})", path.display()),
+    };
+    add_local_system_message(state, "System", text);
+}
+
+/// Desliga o indicador "digitando..." se esta conexão o tinha ligado.
+fn stop_typing(conn: &DbConnection, state: &SharedState) {
+    let was_typing = state
+        .lock()
+        .map(|mut s| s.ui.typing_sent_at.take().is_some())
+        .unwrap_or(false);
+    if was_typing {
+        let _ = conn.reducers.set_typing(false);
+    }
+}
+
+/// Resolve o destinatário pelo nome e chama `send_direct`; erros locais viram aviso no chat.
+fn send_whisper(name: &str, body: String, conn: &DbConnection, state: &SharedState) {
+    let recipient = state
+        .lock()
+        .ok()
+        .and_then(|s| s.ui.find_user_by_name(name).map(|u| u.identity.clone()));
+    let Some(recipient) = recipient.and_then(|id| Identity::from_hex(id).ok()) else {
+        add_local_system_message(state, "System", format!("unknown user: {name}"));
+        return;
+    };
+    if conn.reducers.send_direct(recipient, body).is_err() {
+        update_state(state, |s| s.status = false);
+    }
+}
+
+/// Chama `set_title` em si mesmo ou, com `name`, no usuário com esse nome.
+fn set_title(name: Option<&str>, title: String, conn: &DbConnection, state: &SharedState) {
+    let target = state.lock().ok().and_then(|s| match name {
+        Some(name) => s.ui.find_user_by_name(name).map(|u| u.identity.clone()),
+        None => s.my_identity.clone(),
+    });
+    let Some(target) = target.and_then(|id| Identity::from_hex(id).ok()) else {
+        let name = name.unwrap_or("you");
+        add_local_system_message(state, "System", format!("unknown user: {name}"));
+        return;
+    };
+    if conn.reducers.set_title(target, title).is_err() {
+        update_state(state, |s| s.status = false);
+    }
+}
+
+/// Rola `NdM` e devolve o texto a enviar; notação inválida vira aviso local.
+fn roll_message(spec: &str, state: &SharedState) -> Option<String> {
+    let dice = match parse_dice(spec) {
+        Ok(dice) => dice,
+        Err(err) => {
+            add_local_system_message(state, "System", format!("/roll: {err}"));
+            return None;
+        }
+    };
+    let name = state
+        .lock()
+        .ok()
+        .and_then(|s| {
+            let me = s.my_identity.clone()?;
+            s.ui.users
+                .iter()
+                .find(|u| u.identity == me)
+                .map(|u| u.name.clone())
+        })
+        .unwrap_or_else(|| "Someone".to_string());
+    let rolls = roll_dice(dice, &mut rand::rng());
+    Some(format_roll(&name, dice, &rolls))
+}
+
+/// Alterna `emoji` na mensagem mais recente; sem alvo, só avisa no chat.
+fn react_to_latest(emoji: &str, conn: &DbConnection, state: &SharedState) {
+    let target = state
+        .lock()
+        .ok()
+        .and_then(|s| s.ui.latest_reactable_message_id());
+    let Some(message_id) = target else {
+        add_local_system_message(state, "System", "no message to react to");
+        return;
+    };
+    if conn
+        .reducers
+        .toggle_reaction(message_id, emoji.trim().to_string())
+        .is_err()
+    {
+        update_state(state, |s| s.status = false);
+    }
+}
+
+/// Linhas roladas por passo da roda do mouse no painel de mensagens.
+const MOUSE_SCROLL_LINES: usize = 3;
+
+/// Roda do mouse no chat: rola o painel sob o cursor (mensagens ou usuários).
+///
+/// Com popup aberto ou fora do chat, o mouse é ignorado.
+pub fn handle_mouse_event(mouse: MouseEvent, state: &SharedState) {
+    let up = match mouse.kind {
+        MouseEventKind::ScrollUp => true,
+        MouseEventKind::ScrollDown => false,
+        _ => return,
+    };
+    update_state(state, |s| {
+        if s.ui.screen != UiScreen::Chat || s.ui.popup.is_some() {
+            return;
+        }
+        match s.ui.panel_at(mouse.column, mouse.row) {
+            Some(ChatPanel::Messages) if up => {
+                s.ui.messages_scroll =
+                    s.ui.messages_scroll
+                        .scroll_up(MOUSE_SCROLL_LINES, s.ui.messages_max_scroll);
+                request_older_messages_if_at_top(s);
+            }
+            Some(ChatPanel::Messages) => {
+                s.ui.messages_scroll =
+                    s.ui.messages_scroll
+                        .scroll_down(MOUSE_SCROLL_LINES, s.ui.messages_max_scroll);
+            }
+            Some(ChatPanel::Users) if up => {
+                s.ui.users_scroll = s.ui.users_scroll.saturating_sub(1);
+            }
+            Some(ChatPanel::Users) if s.ui.users_scroll + 1 < s.ui.visible_users().len() => {
+                s.ui.users_scroll += 1;
+            }
+            _ => {}
+        }
+    });
+}
+
+/// Expande a janela de mensagens quando o scroll chega à mais antiga carregada.
+///
+/// A nova página fica marcada como "carregando" até o próximo render, e o
+/// scroll é mantido no `Offset` equivalente para não pular para o novo topo.
+fn request_older_messages_if_at_top(state: &mut AppState) {
+    if !state.ui.should_load_older_messages() {
+        return;
+    }
+    state.ui.loading_older_messages = true;
+    state.ui.messages_extra_pages = state.ui.messages_extra_pages.saturating_add(1);
+    state.ui.messages_scroll = ScrollPosition::Offset(state.ui.messages_max_scroll);
+}
@@ -0,0 +1,104 @@
+use crate::ui::ui_state::UiUser;
+
+/// Trecho de uma linha de mensagem: texto comum ou menção `@nome` reconhecida.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageSegment<'a> {
+    Plain(&'a str),
+    Mention(&'a str),
+}
+
+/// Divide uma linha em trechos comuns e menções a usuários conhecidos.
+///
+/// A comparação ignora maiúsculas/minúsculas, prefere o nome mais longo
+/// (nomes com espaço) e exige fronteira de palavra após o nome.
+/// `@desconhecido` permanece como texto comum.
+pub fn split_mentions<'a>(line: &'a str, known_names: &[&str]) -> Vec<MessageSegment<'a>> {
+    let mut segments = Vec::new();
+    let mut plain_start = 0usize;
+    let mut cursor = 0usize;
+
+    while let Some(offset) = line[cursor..].find('@') {
+        let at = cursor + offset;
+        let rest = &line[at + 1..];
+        match longest_known_prefix(rest, known_names) {
+            Some(len) => {
+                if plain_start < at {
+                    segments.push(MessageSegment::Plain(&line[plain_start..at]));
+                }
+                let end = at + 1 + len;
+                segments.push(MessageSegment::Mention(&line[at..end]));
+                plain_start = end;
+                cursor = end;
+            }
+            None => cursor = at + 1,
+        }
+    }
+
+    if plain_start < line.len() {
+        segments.push(MessageSegment::Plain(&line[plain_start..]));
+    }
+    segments
+}
+
+/// Nomes conhecidos mencionados na mensagem (sem `@`), na ordem em que aparecem.
+pub fn mentioned_names<'a>(text: &str, known_names: &[&'a str]) -> Vec<&'a str> {
+    let mut out: Vec<&'a str> = Vec::new();
+    for segment in split_mentions(text, known_names) {
+        let MessageSegment::Mention(mention) = segment else {
+            continue;
+        };
+        let mention = mention.trim_start_matches('@').to_lowercase();
+        if let Some(name) = known_names
+            .iter()
+            .find(|name| name.to_lowercase() == mention)
+            .copied()
+            && !out.contains(&name)
+        {
+            out.push(name);
+        }
+    }
+    out
+}
+
+/// Primeiro usuário online cujo nome começa com `partial` (sem caixa).
+pub fn complete_name(partial: &str, users: &[UiUser]) -> Option<String> {
+    name_candidates(partial, users).into_iter().next()
+}
+
+/// Nomes online que completam `partial`, na ordem da lista de usuários.
+///
+/// `partial` vazio não completa nada, para o Tab não despejar a sala inteira.
+pub fn name_candidates(partial: &str, users: &[UiUser]) -> Vec<String> {
+    if partial.is_empty() {
+        return Vec::new();
+    }
+    let partial = partial.to_lowercase();
+    users
+        .iter()
+        .filter(|u| u.online && u.name.to_lowercase().starts_with(&partial))
+        .map(|u| u.name.clone())
+        .collect()
+}
+
+/// Tamanho em bytes do maior nome conhecido que prefixa `rest`.
+fn longest_known_prefix(rest: &str, known_names: &[&str]) -> Option<usize> {
+    known_names
+        .iter()
+        .filter(|name| !name.trim().is_empty())
+        .filter_map(|name| {
+            let candidate = rest.get(..name.len())?;
+            if candidate.to_lowercase() != name.to_lowercase() {
+                return None;
+            }
+            let at_boundary = rest[name.len()..]
+                .chars()
+                .next()
+                .is_none_or(|c| !c.is_alphanumeric() && c != '_');
+            at_boundary.then_some(name.len())
+        })
+        .max()
+}
+
+#[cfg(test)]
+#[path = "../tests/mentions_tests.rs"]
+mod tests;
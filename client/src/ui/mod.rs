@@ -1,5 +1,13 @@
+pub mod clipboard;
+pub mod commands;
+pub mod export;
+pub mod filter;
+pub mod glyphs;
 pub mod instructions;
 pub mod key_handler;
+pub mod mentions;
+pub mod spinner;
+pub mod theme;
 pub mod ui_menu_screen;
 pub mod ui_message_screen;
 pub mod ui_state;
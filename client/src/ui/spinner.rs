@@ -0,0 +1,19 @@
+/// Quadros do spinner usado em animações sutis da TUI.
+pub const SPINNER_FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Quadros equivalentes em ASCII (modo de acessibilidade).
+pub const ASCII_SPINNER_FRAMES: &[&str] = &["|", "/", "-", "\\"];
+
+/// Quantos ticks do loop principal (~50ms) cada quadro permanece visível.
+const TICKS_PER_FRAME: u64 = 2;
+
+/// Quadro atual do spinner para o tick informado.
+pub fn spinner_frame(tick: u64) -> &'static str {
+    spinner_frame_in(SPINNER_FRAMES, tick)
+}
+
+/// Quadro atual de um conjunto específico de quadros.
+pub fn spinner_frame_in(frames: &[&'static str], tick: u64) -> &'static str {
+    let index = (tick / TICKS_PER_FRAME) as usize % frames.len();
+    frames[index]
+}
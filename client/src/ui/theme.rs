@@ -0,0 +1,75 @@
+use ratatui::style::Color;
+
+/// Paleta de cores da interface, escolhida por `ui.theme` na config.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Theme {
+    /// Traços laterais do rodapé de instruções.
+    pub footer_line: Color,
+    /// Rótulo de cada instrução ("Send", "Menu"...).
+    pub footer_label: Color,
+    /// Tecla de cada instrução (`<Enter>`).
+    pub footer_key: Color,
+    /// Texto secundário: dicas de scroll, usuários offline, status, reações.
+    pub dim: Color,
+    /// Mensagens privadas (`/w`), para nunca se confundirem com o chat público.
+    pub whisper: Color,
+    /// Texto dos anúncios do host.
+    pub announcement: Color,
+    /// Fundo dos anúncios do host, que cobre a largura toda da linha.
+    pub announcement_bg: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            footer_line: Color::DarkGray,
+            footer_label: Color::Gray,
+            footer_key: Color::Cyan,
+            dim: Color::DarkGray,
+            whisper: Color::Magenta,
+            announcement: Color::White,
+            announcement_bg: Color::Blue,
+        }
+    }
+}
+
+impl Theme {
+    /// Tema pelo nome (sem diferenciar maiúsculas); desconhecido cai no padrão.
+    pub fn by_name(name: &str) -> Self {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "amber" => Self {
+                footer_line: Color::Rgb(120, 80, 0),
+                footer_label: Color::Rgb(200, 160, 60),
+                footer_key: Color::Yellow,
+                dim: Color::DarkGray,
+                whisper: Color::LightRed,
+                announcement: Color::Black,
+                announcement_bg: Color::Rgb(200, 160, 60),
+            },
+            "mono" => Self {
+                footer_line: Color::DarkGray,
+                footer_label: Color::Gray,
+                footer_key: Color::White,
+                dim: Color::DarkGray,
+                whisper: Color::Gray,
+                announcement: Color::Black,
+                announcement_bg: Color::Gray,
+            },
+            "high-contrast" => Self::high_contrast(),
+            _ => Self::default(),
+        }
+    }
+
+    /// Paleta do modo de acessibilidade: nada em cinza escuro sobre o fundo.
+    pub fn high_contrast() -> Self {
+        Self {
+            footer_line: Color::White,
+            footer_label: Color::White,
+            footer_key: Color::LightYellow,
+            dim: Color::Gray,
+            whisper: Color::LightMagenta,
+            announcement: Color::Black,
+            announcement_bg: Color::LightYellow,
+        }
+    }
+}
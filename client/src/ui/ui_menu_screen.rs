@@ -1,12 +1,15 @@
 use crate::ui::{
+    commands::COMMAND_HELP,
+    glyphs::Glyphs,
     instructions::{InstructionItem, render_instructions},
-    ui_state::{MainMenuItem, UiPopup, UiState},
+    theme::Theme,
+    ui_state::{MAX_NAME_LEN, MainMenuItem, UiPopup, UiState},
 };
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
-    text::Line,
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
+    text::{Line, Span},
+    widgets::{Clear, List, ListItem, Paragraph, Wrap},
 };
 
 /// Renderiza menu principal e popups associados.
@@ -23,6 +26,7 @@ pub fn render_menu_screen(
             Constraint::Length(1),
         ])
         .split(frame.area());
+    let glyphs = Glyphs::select(state.accessibility);
 
     let title = Paragraph::new("ShellRelay")
         .alignment(Alignment::Center)
@@ -31,7 +35,7 @@ pub fn render_menu_screen(
                 .fg(Color::Yellow)
                 .add_modifier(Modifier::BOLD),
         )
-        .block(Block::default().borders(Borders::ALL));
+        .block(glyphs.block());
     frame.render_widget(title, chunks[0]);
 
     let menu_area = centered_rect(42, 40, chunks[1]);
@@ -53,8 +57,8 @@ pub fn render_menu_screen(
         .collect();
 
     let menu = List::new(menu_items).block(
-        Block::default()
-            .borders(Borders::ALL)
+        glyphs
+            .block()
             .title("Main menu")
             .border_style(Style::default().fg(Color::Blue)),
     );
@@ -62,10 +66,17 @@ pub fn render_menu_screen(
 
     match state.popup {
         Some(UiPopup::ChooseName) => render_choose_name_popup(frame, state),
-        Some(UiPopup::Soon) => render_soon_popup(frame),
+        Some(UiPopup::Options) => render_options_popup(frame, &state.theme, glyphs),
+        Some(UiPopup::ConfirmDeleteAccount) => {
+            render_confirm_delete_popup(frame, &state.theme, glyphs)
+        }
+        Some(UiPopup::Help) => render_help_popup(frame, &menu_instructions(), &state.theme, glyphs),
+        Some(UiPopup::Search) => render_search_popup(frame, state),
+        // Só aberto na tela de chat.
+        Some(UiPopup::ConfirmDeleteMessage) => {}
         None => {
             let instructions = menu_instructions();
-            render_instructions(frame, chunks[2], &instructions);
+            render_instructions(frame, chunks[2], &instructions, &state.theme, glyphs);
         }
     }
 }
@@ -94,12 +105,13 @@ fn menu_instructions() -> [InstructionItem<'static>; 4] {
 
 /// Popup de entrada de nome antes de entrar no chat.
 fn render_choose_name_popup(frame: &mut ratatui::Frame<'_>, state: &UiState) {
+    let glyphs = Glyphs::select(state.accessibility);
     let area = centered_rect(50, 28, frame.area());
     frame.render_widget(Clear, area);
     frame.render_widget(
-        Block::default()
+        glyphs
+            .block()
             .title("Choose name")
-            .borders(Borders::ALL)
             .border_style(Style::default().fg(Color::Cyan)),
         area,
     );
@@ -115,22 +127,40 @@ fn render_choose_name_popup(frame: &mut ratatui::Frame<'_>, state: &UiState) {
         ])
         .split(area);
 
-    let hint = Paragraph::new("Type your name to enter the chat.")
-        .style(Style::default().fg(Color::Gray))
-        .wrap(Wrap { trim: true });
+    let hint = match &state.name_error {
+        Some(reason) => Paragraph::new(format!("Name rejected: {reason}. Try another one."))
+            .style(Style::default().fg(Color::Red)),
+        None if state.input.chars().count() >= MAX_NAME_LEN => {
+            Paragraph::new(format!("Names are limited to {MAX_NAME_LEN} characters."))
+                .style(Style::default().fg(Color::Yellow))
+        }
+        None => Paragraph::new("Type your name to enter the chat.")
+            .style(Style::default().fg(Color::Gray)),
+    }
+    .wrap(Wrap { trim: true });
     frame.render_widget(hint, inner[0]);
 
-    let input = Paragraph::new(state.input.as_str())
-        .block(Block::default().borders(Borders::ALL).title("Name"))
-        .style(Style::default().fg(Color::Yellow))
-        .wrap(Wrap { trim: false });
+    // Uma linha só: nomes longos rolam na horizontal acompanhando o cursor.
+    let input_width = inner[1].width.saturating_sub(2) as usize;
+    let (visible, cursor_col) =
+        visible_input_window(&state.input, state.clamped_cursor(), input_width);
+    let input = Paragraph::new(visible)
+        .block(glyphs.block().title("Name"))
+        .style(Style::default().fg(Color::Yellow));
     frame.render_widget(input, inner[1]);
+    if input_width > 0 && inner[1].height > 2 {
+        frame.set_cursor_position((inner[1].x + 1 + cursor_col as u16, inner[1].y + 1));
+    }
 
     let instructions = [
         InstructionItem {
             label: "Confirm",
             key: "Enter",
         },
+        InstructionItem {
+            label: "Move",
+            key: "Left/Right",
+        },
         InstructionItem {
             label: "Delete",
             key: "Backspace",
@@ -140,18 +170,19 @@ fn render_choose_name_popup(frame: &mut ratatui::Frame<'_>, state: &UiState) {
             key: "Esc",
         },
     ];
-    render_instructions(frame, inner[3], &instructions);
+    render_instructions(frame, inner[3], &instructions, &state.theme, glyphs);
 }
 
-/// Popup placeholder da seção de opções.
-fn render_soon_popup(frame: &mut ratatui::Frame<'_>) {
-    let area = centered_rect(34, 22, frame.area());
+/// Popup de busca no chat: uma linha de texto, confirmada com Enter.
+pub fn render_search_popup(frame: &mut ratatui::Frame<'_>, state: &UiState) {
+    let glyphs = Glyphs::select(state.accessibility);
+    let area = centered_rect(50, 28, frame.area());
     frame.render_widget(Clear, area);
     frame.render_widget(
-        Block::default()
-            .title("Options")
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Blue)),
+        glyphs
+            .block()
+            .title("Search messages")
+            .border_style(Style::default().fg(Color::Cyan)),
         area,
     );
 
@@ -159,22 +190,32 @@ fn render_soon_popup(frame: &mut ratatui::Frame<'_>) {
         .direction(Direction::Vertical)
         .margin(1)
         .constraints([
-            Constraint::Length(1),
+            Constraint::Length(2),
+            Constraint::Length(3),
             Constraint::Min(0),
             Constraint::Length(1),
         ])
         .split(area);
 
-    let popup = Paragraph::new("Soon").alignment(Alignment::Center).style(
-        Style::default()
-            .fg(Color::Yellow)
-            .add_modifier(Modifier::BOLD),
-    );
-    frame.render_widget(popup, inner[1]);
+    let hint = Paragraph::new("Matches are highlighted; n and N jump between them.")
+        .style(Style::default().fg(Color::Gray))
+        .wrap(Wrap { trim: true });
+    frame.render_widget(hint, inner[0]);
+
+    let input_width = inner[1].width.saturating_sub(2) as usize;
+    let (visible, cursor_col) =
+        visible_input_window(&state.search_input, state.search_input.len(), input_width);
+    let input = Paragraph::new(visible)
+        .block(glyphs.block().title("Search"))
+        .style(Style::default().fg(Color::Yellow));
+    frame.render_widget(input, inner[1]);
+    if input_width > 0 && inner[1].height > 2 {
+        frame.set_cursor_position((inner[1].x + 1 + cursor_col as u16, inner[1].y + 1));
+    }
 
     let instructions = [
         InstructionItem {
-            label: "Close",
+            label: "Search",
             key: "Enter",
         },
         InstructionItem {
@@ -182,7 +223,194 @@ fn render_soon_popup(frame: &mut ratatui::Frame<'_>) {
             key: "Esc",
         },
     ];
-    render_instructions(frame, inner[2], &instructions);
+    render_instructions(frame, inner[3], &instructions, &state.theme, glyphs);
+}
+
+/// Trecho de uma linha de input que cabe em `width` colunas, mantendo o cursor visível.
+///
+/// Enquanto o texto cabe, mostra tudo; depois a janela acompanha o cursor, que
+/// fica na última coluna. Retorna o trecho e a coluna do cursor dentro dele.
+fn visible_input_window(input: &str, cursor: usize, width: usize) -> (String, usize) {
+    if width == 0 {
+        return (String::new(), 0);
+    }
+    let chars: Vec<char> = input.chars().collect();
+    let cursor_chars = input[..cursor.min(input.len())].chars().count();
+    let start = (cursor_chars + 1).saturating_sub(width);
+    let end = (start + width).min(chars.len());
+    (chars[start..end].iter().collect(), cursor_chars - start)
+}
+
+/// Popup de opções da conta.
+fn render_options_popup(frame: &mut ratatui::Frame<'_>, theme: &Theme, glyphs: &Glyphs) {
+    let instructions = [
+        InstructionItem {
+            label: "Delete account",
+            key: "D",
+        },
+        InstructionItem {
+            label: "Close",
+            key: "Esc",
+        },
+    ];
+    render_message_popup(
+        frame,
+        "Options",
+        Color::Blue,
+        "Account settings",
+        &instructions,
+        theme,
+        glyphs,
+    );
+}
+
+/// Confirmação antes de apagar a conta e todas as mensagens do usuário.
+fn render_confirm_delete_popup(frame: &mut ratatui::Frame<'_>, theme: &Theme, glyphs: &Glyphs) {
+    let instructions = [
+        InstructionItem {
+            label: "Delete",
+            key: "Y",
+        },
+        InstructionItem {
+            label: "Cancel",
+            key: "Esc",
+        },
+    ];
+    render_message_popup(
+        frame,
+        "Delete account",
+        Color::Red,
+        "Delete your user and all your messages? This cannot be undone and closes the app.",
+        &instructions,
+        theme,
+        glyphs,
+    );
+}
+
+/// Confirmação para apagar uma mensagem própria, com o texto dela como prévia.
+pub fn render_confirm_delete_message_popup(
+    frame: &mut ratatui::Frame<'_>,
+    preview: &str,
+    theme: &Theme,
+    glyphs: &Glyphs,
+) {
+    let instructions = [
+        InstructionItem {
+            label: "Delete",
+            key: "Y",
+        },
+        InstructionItem {
+            label: "Cancel",
+            key: "Esc",
+        },
+    ];
+    render_message_popup(
+        frame,
+        "Delete message",
+        Color::Red,
+        &format!("Delete this message for everyone? \"{preview}\""),
+        &instructions,
+        theme,
+        glyphs,
+    );
+}
+
+/// Ajuda: as teclas da tela (as mesmas do rodapé) e os comandos `/...` do chat.
+pub fn render_help_popup(
+    frame: &mut ratatui::Frame<'_>,
+    keys: &[InstructionItem<'_>],
+    theme: &Theme,
+    glyphs: &Glyphs,
+) {
+    let area = centered_rect(70, 80, frame.area());
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        glyphs
+            .block()
+            .title("Help")
+            .border_style(Style::default().fg(Color::Cyan)),
+        area,
+    );
+
+    let inner = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(area);
+
+    let rows: Vec<(&str, &str)> = keys
+        .iter()
+        .map(|item| (item.key, item.label))
+        .chain(COMMAND_HELP.iter().copied())
+        .collect();
+    let width = rows
+        .iter()
+        .map(|(left, _)| left.chars().count())
+        .max()
+        .unwrap_or(0);
+    let heading = Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD);
+    let row = |(left, right): (&str, &str)| {
+        Line::from(vec![
+            Span::styled(
+                format!("{left:<width$}  "),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(right.to_string(), Style::default().fg(theme.dim)),
+        ])
+    };
+    let mut lines = vec![Line::styled("Keys", heading)];
+    lines.extend(rows[..keys.len()].iter().copied().map(row));
+    lines.push(Line::default());
+    lines.push(Line::styled("Commands", heading));
+    lines.extend(rows[keys.len()..].iter().copied().map(row));
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner[0]);
+
+    let instructions = [InstructionItem {
+        label: "Close",
+        key: "Esc/Enter",
+    }];
+    render_instructions(frame, inner[1], &instructions, theme, glyphs);
+}
+
+/// Popup simples: texto central e rodapé de teclas.
+fn render_message_popup(
+    frame: &mut ratatui::Frame<'_>,
+    title: &str,
+    color: Color,
+    text: &str,
+    instructions: &[InstructionItem<'_>],
+    theme: &Theme,
+    glyphs: &Glyphs,
+) {
+    let area = centered_rect(50, 28, frame.area());
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        glyphs
+            .block()
+            .title(title)
+            .border_style(Style::default().fg(color)),
+        area,
+    );
+
+    let inner = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(area);
+
+    let popup = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .style(Style::default().fg(color).add_modifier(Modifier::BOLD));
+    frame.render_widget(popup, inner[1]);
+
+    render_instructions(frame, inner[2], instructions, theme, glyphs);
 }
 
 /// Utilitário para centralizar blocos por percentual de largura/altura.
@@ -205,3 +433,7 @@ fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
         ])
         .split(vertical[1])[1]
 }
+
+#[cfg(test)]
+#[path = "../tests/ui_menu_screen_tests.rs"]
+mod tests;
@@ -1,37 +1,93 @@
+use crate::config::KeybindingsConfig;
 use crate::ui::{
+    filter::{mask_for_display, mask_profanity},
+    glyphs::Glyphs,
     instructions::{InstructionItem, render_instructions},
-    ui_state::UiState,
+    mentions::{MessageSegment, split_mentions},
+    theme::Theme,
+    ui_menu_screen::{render_confirm_delete_message_popup, render_help_popup, render_search_popup},
+    ui_state::{ScrollPosition, UiDirectMessage, UiMessage, UiPopup, UiState, UserLookup},
 };
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
-    text::Line,
-    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{List, ListItem, Paragraph, Sparkline, Wrap},
 };
-use std::collections::HashMap;
+use std::{
+    borrow::Cow,
+    ops::Range,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Largura do sparkline de atividade no cabeçalho (um segundo por coluna).
+const ACTIVITY_SPARKLINE_WIDTH: u16 = 30;
+
+/// Texto fixo à esquerda do cabeçalho, o primeiro a sumir em terminais estreitos.
+const HEADER_TEXT: &str = "Messages and user list";
+
+/// Largura reservada ao status do servidor no cabeçalho.
+const HEADER_STATUS_WIDTH: u16 = 18;
+
+/// Linhas visíveis do input antes de ele passar a rolar.
+const INPUT_MAX_LINES: u16 = 5;
+
+/// Valores calculados no render do chat que voltam para o estado (dependem do terminal).
+pub struct ChatRender {
+    /// Scroll máximo da lista de mensagens, para a navegação por teclado.
+    pub messages_max_scroll: usize,
+    /// Scroll que põe `UiState::scroll_to_message` no topo, quando houve pedido de salto.
+    pub jump_scroll: Option<ScrollPosition>,
+    /// Áreas dos painéis de mensagens e usuários, para o scroll do mouse.
+    pub messages_area: Rect,
+    pub users_area: Rect,
+}
 
 /// Renderiza a tela principal de chat (mensagens, usuários, input e rodapé).
-pub fn render_ui(frame: &mut ratatui::Frame<'_>, state: &UiState, is_server_online: bool) {
+///
+/// `my_identity` identifica as mensagens do próprio usuário para destaque visual;
+/// `activity` traz as mensagens por segundo do último minuto (mais antiga primeiro).
+pub fn render_ui(
+    frame: &mut ratatui::Frame<'_>,
+    state: &UiState,
+    my_identity: Option<&str>,
+    is_server_online: bool,
+    activity: &[u64],
+) -> ChatRender {
     // -------- MAIN LAYOUT ----------
 
+    let glyphs = Glyphs::select(state.accessibility);
+    let dim = state.theme.dim;
+
+    let direct_messages = state.recent_direct_messages();
+    let direct_height = if direct_messages.is_empty() {
+        0
+    } else {
+        direct_messages.len() as u16 + 2
+    };
+    let typing_names = state.typing_names(my_identity, now_micros());
+    let typing_height = u16::from(!typing_names.is_empty());
+    let input_height = input_lines_height(
+        &state.input,
+        state.clamped_cursor(),
+        frame.area().width.saturating_sub(2) as usize,
+    ) + 2;
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3), //title
-            Constraint::Min(6),    //body
-            Constraint::Length(3), //input
-            Constraint::Length(1), //instructions
+            Constraint::Length(3),             //title
+            Constraint::Min(6),                //body
+            Constraint::Length(direct_height), //direct messages
+            Constraint::Length(typing_height), //typing
+            Constraint::Length(input_height),  //input
+            Constraint::Length(1),             //instructions
         ])
         .split(frame.area());
 
     // -------- TITLE ----------
 
-    let header_block = Block::default().borders(Borders::ALL).title("ShellRelay");
-    let status_label = if is_server_online {
-        "Server online"
-    } else {
-        "Server offline"
-    };
+    let header_block = glyphs.block().title("ShellRelay");
     let status_color = if is_server_online {
         Color::Green
     } else {
@@ -44,57 +100,149 @@ pub fn render_ui(frame: &mut ratatui::Frame<'_>, state: &UiState, is_server_onli
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
         .split(chunks[1]);
-    let messages_inner_width = body[0].width.saturating_sub(2) as usize;
+    let messages_area = capped_messages_area(body[0], state.max_message_width);
+    let messages_inner_width = messages_area.width.saturating_sub(2) as usize;
 
     // -------- LIST MESSAGE ----------
     // Mapeia identity -> nome para exibir remetentes de forma amigável.
+    // O cache só é remontado quando a lista de usuários muda.
 
-    let user_names_by_identity: HashMap<&str, &str> = state
+    let user_lookup = state.user_lookup();
+    let known_names: Vec<&str> = state
         .users
         .iter()
-        .map(|u| (u.identity.as_str(), u.name.as_str()))
+        .map(|u| u.name.trim())
+        .filter(|name| !name.is_empty())
         .collect();
 
-    let message_lines: Vec<Line<'_>> = state
-        .messages
-        .iter()
-        .flat_map(|m| {
-            let sender = user_names_by_identity
-                .get(m.sender.as_str())
-                .copied()
+    let now = SystemTime::now();
+    let search_query = state.search_query.as_deref().unwrap_or("");
+    // Avisos do sistema ocultos só saem da tela; o buffer continua intacto.
+    let rendered_messages: Vec<(u64, Vec<Line<'_>>)> = state
+        .chat_messages()
+        .into_iter()
+        .map(|m| {
+            let sender = user_lookup
+                .name(&m.sender)
                 .filter(|name| !name.trim().is_empty())
                 .map(str::to_string)
                 .unwrap_or_else(|| short_identity(&m.sender));
 
-            let prefix = match format_message_datetime(&m.sent_at) {
-                Some(date_time) => format!("[{}] {}: ", date_time, sender),
-                None => format!("{}: ", sender),
+            let title = user_title_prefix(user_lookup.title(&m.sender).unwrap_or(""));
+
+            let time_label = message_time_label(
+                &m.sent_at,
+                state.raw_timestamps,
+                state.relative_timestamps,
+                now,
+            );
+            let prefix = match (time_label, m.is_announcement) {
+                (Some(date_time), true) => {
+                    format!("[{}] Announcement from {}{}: ", date_time, title, sender)
+                }
+                (None, true) => format!("Announcement from {}{}: ", title, sender),
+                (Some(date_time), false) => format!("[{}] {}{}: ", date_time, title, sender),
+                (None, false) => format!("{}{}: ", title, sender),
+            };
+            let style = message_style(m, my_identity, state.own_message_color, &state.theme);
+            // A máscara vale só para a tela; o texto guardado não muda.
+            let body = match message_body(m) {
+                body if state.filter_words.is_empty() => body,
+                body => Cow::Owned(mask_profanity(&body, &state.filter_words)),
             };
-            let wrapped_lines = wrap_message_lines(&prefix, &m.text, messages_inner_width);
-            wrapped_lines
+            let wrapped_lines = wrap_message_lines(&prefix, &body, messages_inner_width);
+            let link_continues = link_continuations(&wrapped_lines, messages_inner_width);
+            // Posição (em bytes) do nome dentro do prefixo, colorido só na primeira linha.
+            let sender_at = prefix.len() - sender.len() - ": ".len();
+            let color_sender = !m.is_announcement && !m.is_pending;
+            let mut lines = wrapped_lines
                 .into_iter()
-                .map(Line::from)
-                .collect::<Vec<Line<'_>>>()
+                .enumerate()
+                .map(|(index, line)| {
+                    let line = if m.is_announcement {
+                        pad_to_width(line, messages_inner_width)
+                    } else {
+                        line
+                    };
+                    let line = if index == 0 && color_sender {
+                        let color = color_for_identity(&m.sender);
+                        sender_line(line, sender_at, &sender, color, &known_names).style(style)
+                    } else {
+                        message_line(line, &known_names).style(style)
+                    };
+                    highlight_links(line, link_continues[index])
+                })
+                .collect::<Vec<Line<'_>>>();
+            if let Some(summary) = reaction_summary(&m.reactions) {
+                let indent = " ".repeat(prefix.width());
+                lines.push(Line::styled(
+                    format!("{indent}{summary}"),
+                    Style::default().fg(dim),
+                ));
+            }
+            (m.id, lines)
         })
         .collect();
-    let messages_visible_rows = body[0].height.saturating_sub(2) as usize;
+    let mut jump_line = None;
+    let mut message_lines: Vec<Line<'_>> = Vec::new();
+    for (id, lines) in rendered_messages {
+        if state.scroll_to_message == Some(id) {
+            jump_line = Some(message_lines.len());
+        }
+        let selected = state.delete_selection == Some(id);
+        message_lines.extend(lines.into_iter().map(|line| {
+            let line = highlight_matches(line, search_query);
+            if selected {
+                line.patch_style(Style::default().add_modifier(Modifier::REVERSED))
+            } else {
+                line
+            }
+        }));
+    }
+    if state.has_older_messages() {
+        message_lines.insert(
+            0,
+            load_older_line(state.loading_older_messages, glyphs, dim),
+        );
+        jump_line = jump_line.map(|line| line + 1);
+    }
+    let messages_visible_rows = messages_area.height.saturating_sub(2) as usize;
     let messages_max_scroll = if messages_visible_rows == 0 {
         0
     } else {
         message_lines.len().saturating_sub(messages_visible_rows)
     };
-    let messages_scroll = messages_max_scroll
-        .saturating_sub(state.messages_scroll_from_bottom.min(messages_max_scroll));
+    // Salto pedido (busca, seleção para apagar): a mensagem vai para o topo
+    // (ou o mais perto possível).
+    let jump_scroll = jump_line
+        .map(|line| ScrollPosition::Offset(messages_max_scroll - line.min(messages_max_scroll)));
+    let messages_scroll = jump_scroll
+        .unwrap_or(state.messages_scroll)
+        .resolve(messages_max_scroll);
+    let messages_title = if state.delete_selection.is_some() {
+        "Messages - delete: Up/Down select, Enter deletes, Esc cancels".to_string()
+    } else if let Some(query) = &state.search_query {
+        format!("Messages - search: {query} (n older, N newer, Esc ends)")
+    } else if state.idle_animation && state.is_room_empty(my_identity) {
+        format!(
+            "Messages {} Waiting for others{}",
+            glyphs.spinner_frame(state.animation_tick),
+            glyphs.ellipsis
+        )
+    } else {
+        "Messages".to_string()
+    };
     let messages = Paragraph::new(message_lines)
-        .block(Block::default().borders(Borders::ALL).title("Messages"))
+        .block(glyphs.block().title(messages_title))
         .scroll((messages_scroll.min(u16::MAX as usize) as u16, 0))
         .wrap(Wrap { trim: false });
 
     // -------- LIST USERS ----------
     // Lista lateral com scroll independente da lista de mensagens.
 
+    let visible_users = state.visible_users();
     let users_visible_rows = body[1].height.saturating_sub(2) as usize;
-    let reserve_hint_row = users_visible_rows > 1 && state.users.len() > users_visible_rows;
+    let reserve_hint_row = users_visible_rows > 1 && visible_users.len() > users_visible_rows;
     let users_list_rows = if reserve_hint_row {
         users_visible_rows - 1
     } else {
@@ -103,48 +251,87 @@ pub fn render_ui(frame: &mut ratatui::Frame<'_>, state: &UiState, is_server_onli
     let users_max_scroll = if users_list_rows == 0 {
         0
     } else {
-        state.users.len().saturating_sub(users_list_rows)
+        visible_users.len().saturating_sub(users_list_rows)
     };
     let users_scroll = state.users_scroll.min(users_max_scroll);
     let users_end = users_scroll
         .saturating_add(users_list_rows)
-        .min(state.users.len());
+        .min(visible_users.len());
 
-    let user_items: Vec<ListItem<'_>> = state
-        .users
+    let user_items: Vec<ListItem<'_>> = visible_users
         .get(users_scroll..users_end)
         .unwrap_or(&[])
         .iter()
         .map(|u| {
-            let dot = if u.online { "●" } else { "○" };
-            let color = if u.online {
-                Color::Green
+            let dot = if u.online {
+                glyphs.online
             } else {
-                Color::DarkGray
+                glyphs.offline
             };
-            let line = format!("{} {} ({})", dot, u.name, short_identity(&u.identity));
-            ListItem::new(Line::from(line).style(Style::default().fg(color)))
+            let color = if u.online { Color::Green } else { dim };
+            let line = format!(
+                "{} {}{} ({})",
+                dot,
+                user_title_prefix(&u.title),
+                u.name,
+                short_identity(&u.identity)
+            );
+            let mut spans = vec![Span::styled(line, Style::default().fg(color))];
+            if let Some(status) = user_status_suffix(&u.status, glyphs.separator) {
+                spans.push(Span::styled(
+                    status,
+                    Style::default().fg(dim).add_modifier(Modifier::DIM),
+                ));
+            }
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
     let users_online = state.users.iter().filter(|u| u.online).count();
     let users_offline = state.users.len().saturating_sub(users_online);
-    let users_title = format!(
-        "Users (Online: {} | Offline: {})",
-        users_online, users_offline
-    );
+    let users_title = if state.hide_offline_users {
+        format!(
+            "Users (Online: {} | Offline: {} hidden)",
+            users_online, users_offline
+        )
+    } else {
+        format!(
+            "Users (Online: {} | Offline: {})",
+            users_online, users_offline
+        )
+    };
 
     let users = List::new(user_items)
-        .block(Block::default().borders(Borders::ALL).title(users_title))
+        .block(glyphs.block().title(users_title))
         .highlight_style(Style::default().bg(Color::DarkGray));
 
     // -------- INPUT ----------
 
-    let input_title = "Message";
-    let input = Paragraph::new(state.input.as_str())
-        .block(Block::default().borders(Borders::ALL).title(input_title))
+    // Mensagem recusada: borda vermelha e o motivo no título até a próxima edição.
+    let input_block = match &state.input_error {
+        Some(reason) => glyphs
+            .block()
+            .title(format!("Message - rejected: {reason}"))
+            .border_style(Style::default().fg(Color::Red)),
+        None => glyphs.block().title("Message"),
+    };
+    // Quebra fixa por caractere: a mesma usada para posicionar o cursor.
+    let input_inner_width = chunks[4].width.saturating_sub(2) as usize;
+    let input_inner_height = chunks[4].height.saturating_sub(2);
+    let (cursor_row, cursor_col) =
+        input_cursor_position(&state.input, state.clamped_cursor(), input_inner_width);
+    let input_scroll = cursor_row.saturating_sub(input_inner_height.saturating_sub(1));
+    // O input também passa pela máscara (citações e o que está sendo digitado);
+    // cada letra vira um `*`, então a quebra e o cursor não mudam.
+    let input_text = mask_for_display(&state.input, &state.filter_words);
+    let input_lines: Vec<Line<'_>> = wrap_fixed_lines(&input_text, input_inner_width)
+        .into_iter()
+        .map(Line::from)
+        .collect();
+    let input = Paragraph::new(input_lines)
+        .block(input_block)
         .style(Style::default().fg(Color::Yellow))
-        .wrap(Wrap { trim: false });
+        .scroll((input_scroll, 0));
 
     frame.render_widget(header_block, chunks[0]);
     let header_inner = Rect {
@@ -154,39 +341,137 @@ pub fn render_ui(frame: &mut ratatui::Frame<'_>, state: &UiState, is_server_onli
         height: chunks[0].height.saturating_sub(2),
     };
     if header_inner.width > 0 && header_inner.height > 0 {
+        let layout = header_layout(header_inner.width, is_server_online);
         let header_content = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Min(0), Constraint::Length(18)])
+            .constraints([
+                Constraint::Length(layout.text_width),
+                Constraint::Length(layout.sparkline_width),
+                Constraint::Length(layout.status_width),
+            ])
             .split(header_inner);
 
-        let header_text = Paragraph::new(vec![
-            Line::from("Messages and user list").style(Style::default().fg(Color::Yellow)),
-        ]);
-        frame.render_widget(header_text, header_content[0]);
+        if layout.text_width > 0 {
+            let header_text = Paragraph::new(vec![
+                Line::from(HEADER_TEXT).style(Style::default().fg(Color::Yellow)),
+            ]);
+            frame.render_widget(header_text, header_content[0]);
+        }
 
-        let header_status = Paragraph::new(status_label)
+        let header_status = Paragraph::new(layout.status_label)
             .alignment(Alignment::Right)
             .style(Style::default().fg(status_color));
-        frame.render_widget(header_status, header_content[1]);
+        frame.render_widget(header_status, header_content[2]);
+
+        let sparkline_area = header_content[1];
+        let data = activity_window(activity, sparkline_area.width as usize);
+        let sparkline = Sparkline::default()
+            .data(data)
+            .max(data.iter().copied().max().unwrap_or(0).max(1))
+            .bar_set(glyphs.bars.clone())
+            .style(Style::default().fg(Color::Cyan));
+        frame.render_widget(sparkline, sparkline_area);
     }
 
     //body chunk[1]
-    frame.render_widget(messages, body[0]);
-    render_messages_overflow_hint(frame, body[0], messages_scroll, messages_max_scroll);
+    frame.render_widget(messages, messages_area);
+    render_messages_overflow_hint(
+        frame,
+        messages_area,
+        messages_scroll,
+        messages_max_scroll,
+        state.unread_count(),
+        glyphs,
+        dim,
+    );
 
     // users chunk
     frame.render_widget(users, body[1]);
     if reserve_hint_row {
-        render_users_overflow_hint(frame, body[1], users_scroll, users_max_scroll);
+        render_users_overflow_hint(frame, body[1], users_scroll, users_max_scroll, glyphs, dim);
+    }
+
+    if !direct_messages.is_empty() {
+        let direct_lines: Vec<Line<'_>> = direct_messages
+            .iter()
+            .map(|dm| {
+                Line::from(direct_message_text(
+                    dm,
+                    &user_lookup,
+                    my_identity,
+                    &state.filter_words,
+                ))
+            })
+            .collect();
+        let direct = Paragraph::new(direct_lines)
+            .block(
+                glyphs
+                    .block()
+                    .title("Direct messages (/w <name> <text>)")
+                    .border_style(Style::default().fg(state.theme.whisper)),
+            )
+            .style(whisper_style(&state.theme));
+        frame.render_widget(direct, chunks[2]);
+    }
+
+    if let Some(text) = typing_text(&typing_names) {
+        let typing =
+            Paragraph::new(text).style(Style::default().fg(dim).add_modifier(Modifier::ITALIC));
+        frame.render_widget(typing, chunks[3]);
     }
 
-    frame.render_widget(input, chunks[2]);
+    frame.render_widget(input, chunks[4]);
+    if state.popup.is_none() && input_inner_width > 0 && input_inner_height > 0 {
+        frame.set_cursor_position((
+            chunks[4].x + 1 + cursor_col,
+            chunks[4].y + 1 + cursor_row - input_scroll,
+        ));
+    }
+
+    let instructions = chat_instructions(&crate::config::current().keybindings, !state.hide_system);
+    render_instructions(frame, chunks[5], &instructions, &state.theme, glyphs);
+
+    match state.popup {
+        Some(UiPopup::Help) => render_help_popup(frame, &instructions, &state.theme, glyphs),
+        Some(UiPopup::Search) => render_search_popup(frame, state),
+        Some(UiPopup::ConfirmDeleteMessage) => {
+            let preview = state
+                .delete_target(my_identity)
+                .map(|m| mask_for_display(&m.text, &state.filter_words))
+                .unwrap_or_default();
+            render_confirm_delete_message_popup(frame, &preview, &state.theme, glyphs);
+        }
+        _ => {}
+    }
+
+    ChatRender {
+        messages_max_scroll,
+        jump_scroll,
+        messages_area,
+        users_area: body[1],
+    }
+}
 
-    let instructions = [
+/// Teclas do chat, na ordem do rodapé; o popup de ajuda lista as mesmas.
+///
+/// O rótulo dos avisos do sistema mostra o que a tecla fará (`show_system` atual).
+pub fn chat_instructions(
+    keybindings: &KeybindingsConfig,
+    show_system: bool,
+) -> [InstructionItem<'_>; 18] {
+    [
         InstructionItem {
             label: "Send",
             key: "Enter",
         },
+        InstructionItem {
+            label: "Help",
+            key: &keybindings.help,
+        },
+        InstructionItem {
+            label: "New line",
+            key: "Shift+Enter",
+        },
         InstructionItem {
             label: "Messages",
             key: "PgUp/PgDn",
@@ -195,16 +480,63 @@ pub fn render_ui(frame: &mut ratatui::Frame<'_>, state: &UiState, is_server_onli
             label: "Users",
             key: "Up/Down",
         },
+        InstructionItem {
+            label: "History",
+            key: "Alt+Up/Down",
+        },
         InstructionItem {
             label: "Delete",
             key: "Backspace",
         },
+        InstructionItem {
+            label: "Complete name",
+            key: "Tab",
+        },
+        InstructionItem {
+            label: "Quote",
+            key: &keybindings.quote,
+        },
+        InstructionItem {
+            label: "Copy",
+            key: &keybindings.copy,
+        },
+        InstructionItem {
+            label: "Export",
+            key: &keybindings.export,
+        },
+        InstructionItem {
+            label: "JSON",
+            key: &keybindings.export_json,
+        },
+        InstructionItem {
+            label: "Search",
+            key: &keybindings.search,
+        },
+        InstructionItem {
+            label: "Delete message",
+            key: &keybindings.delete_message,
+        },
+        InstructionItem {
+            label: "Relative time",
+            key: &keybindings.timestamps,
+        },
+        InstructionItem {
+            label: if show_system {
+                "Hide system"
+            } else {
+                "Show system"
+            },
+            key: &keybindings.system_messages,
+        },
         InstructionItem {
             label: "Menu",
-            key: "F1",
+            key: &keybindings.menu,
         },
-    ];
-    render_instructions(frame, chunks[3], &instructions);
+        InstructionItem {
+            label: "Re-roll bots",
+            key: &keybindings.reroll_bots,
+        },
+    ]
 }
 
 fn render_users_overflow_hint(
@@ -212,6 +544,8 @@ fn render_users_overflow_hint(
     users_area: Rect,
     users_scroll: usize,
     users_max_scroll: usize,
+    glyphs: &Glyphs,
+    dim: Color,
 ) {
     if users_max_scroll == 0 {
         return;
@@ -230,25 +564,52 @@ fn render_users_overflow_hint(
     }
 
     let hint_text = if users_scroll == 0 {
-        "Scroll down ↓"
+        format!("Scroll down {}", glyphs.down)
     } else if users_scroll >= users_max_scroll {
-        "Scroll up ↑"
+        format!("Scroll up {}", glyphs.up)
     } else {
-        "Scroll ↑↓"
+        format!("Scroll {}{}", glyphs.up, glyphs.down)
     };
 
     let hint = Paragraph::new(hint_text)
         .alignment(Alignment::Right)
-        .style(Style::default().fg(Color::DarkGray));
+        .style(Style::default().fg(dim));
     frame.render_widget(hint, hint_area);
 }
 
+/// Texto do hint de overflow; com mensagens não lidas abaixo, mostra `N new ↓`.
+fn messages_overflow_hint_text(
+    messages_scroll: usize,
+    messages_max_scroll: usize,
+    unread: usize,
+    glyphs: &Glyphs,
+) -> String {
+    let scrolled_up = messages_scroll < messages_max_scroll;
+    if unread > 0 && scrolled_up {
+        return if messages_scroll == 0 {
+            format!("{unread} new {}", glyphs.down)
+        } else {
+            format!("Older {} | {unread} new {}", glyphs.up, glyphs.down)
+        };
+    }
+    if messages_scroll == 0 {
+        format!("Older messages {}", glyphs.up)
+    } else if messages_scroll >= messages_max_scroll {
+        format!("Newer messages {}", glyphs.down)
+    } else {
+        format!("Older {} | Newer {}", glyphs.up, glyphs.down)
+    }
+}
+
 /// Hint de navegação da caixa de mensagens quando existe overflow vertical.
 fn render_messages_overflow_hint(
     frame: &mut ratatui::Frame<'_>,
     messages_area: Rect,
     messages_scroll: usize,
     messages_max_scroll: usize,
+    unread: usize,
+    glyphs: &Glyphs,
+    dim: Color,
 ) {
     if messages_max_scroll == 0 {
         return;
@@ -266,20 +627,326 @@ fn render_messages_overflow_hint(
         return;
     }
 
-    let hint_text = if messages_scroll == 0 {
-        "Older messages ↑"
-    } else if messages_scroll >= messages_max_scroll {
-        "Newer messages ↓"
-    } else {
-        "Older ↑ | Newer ↓"
-    };
-
+    let hint_text =
+        messages_overflow_hint_text(messages_scroll, messages_max_scroll, unread, glyphs);
     let hint = Paragraph::new(hint_text)
         .alignment(Alignment::Right)
-        .style(Style::default().fg(Color::DarkGray));
+        .style(Style::default().fg(dim));
     frame.render_widget(hint, hint_area);
 }
 
+/// Affordance no topo do painel quando há mensagens antigas fora da janela.
+fn load_older_line(loading: bool, glyphs: &Glyphs, dim: Color) -> Line<'static> {
+    let text = if loading {
+        format!(
+            "{} Loading older messages{}",
+            glyphs.reload, glyphs.ellipsis
+        )
+    } else {
+        format!("{} Load older messages (PgUp/Home)", glyphs.reload)
+    };
+    Line::from(text)
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(dim))
+}
+
+/// Estilo das mensagens privadas: cor própria do tema, em itálico.
+fn whisper_style(theme: &Theme) -> Style {
+    Style::default()
+        .fg(theme.whisper)
+        .add_modifier(Modifier::ITALIC)
+}
+
+/// Linha de uma mensagem privada: `[DM] [data] Lia -> you: texto`, com o texto
+/// mascarado por `filter_words`.
+fn direct_message_text(
+    dm: &UiDirectMessage,
+    names: &UserLookup,
+    my_identity: Option<&str>,
+    filter_words: &[String],
+) -> String {
+    let display = |identity: &str| {
+        if Some(identity) == my_identity {
+            return "you".to_string();
+        }
+        names
+            .name(identity)
+            .filter(|name| !name.trim().is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| short_identity(identity))
+    };
+    let route = format!("{} -> {}", display(&dm.sender), display(&dm.recipient));
+    let text = mask_for_display(&dm.text, filter_words);
+    match format_message_datetime(&dm.sent_at) {
+        Some(date_time) => format!("[DM] [{date_time}] {route}: {text}"),
+        None => format!("[DM] {route}: {text}"),
+    }
+}
+
+/// Divisão do cabeçalho em texto, sparkline e status, da esquerda para a direita.
+#[derive(Debug, PartialEq, Eq)]
+struct HeaderLayout {
+    text_width: u16,
+    sparkline_width: u16,
+    status_width: u16,
+    status_label: &'static str,
+}
+
+/// Decide o que cabe no cabeçalho com `width` colunas internas.
+///
+/// O status nunca some: abaixo de `HEADER_STATUS_WIDTH` ele ocupa tudo com o
+/// rótulo curto. Depois vem a sparkline (até `ACTIVITY_SPARKLINE_WIDTH`), e o
+/// texto da esquerda só aparece se couber inteiro; senão a sobra vai para o status.
+fn header_layout(width: u16, is_server_online: bool) -> HeaderLayout {
+    let (long_label, short_label) = if is_server_online {
+        ("Server online", "Online")
+    } else {
+        ("Server offline", "Offline")
+    };
+    if width < HEADER_STATUS_WIDTH {
+        return HeaderLayout {
+            text_width: 0,
+            sparkline_width: 0,
+            status_width: width,
+            status_label: short_label,
+        };
+    }
+
+    let rest = width - HEADER_STATUS_WIDTH;
+    let sparkline_width = rest.min(ACTIVITY_SPARKLINE_WIDTH);
+    let text_width = rest - sparkline_width;
+    let text_width = if text_width as usize >= HEADER_TEXT.len() {
+        text_width
+    } else {
+        0
+    };
+    HeaderLayout {
+        text_width,
+        sparkline_width,
+        status_width: width - sparkline_width - text_width,
+        status_label: long_label,
+    }
+}
+
+/// Área do painel de mensagens com o conteúdo limitado a `max_width` colunas
+/// (além das bordas), centralizada no espaço disponível; `0` = largura toda.
+fn capped_messages_area(area: Rect, max_width: u16) -> Rect {
+    let width = max_width.saturating_add(2);
+    if max_width == 0 || area.width <= width {
+        return area;
+    }
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        width,
+        ..area
+    }
+}
+
+/// Título entre colchetes antes do nome (`"[host] "`); vazio sem título.
+fn user_title_prefix(title: &str) -> String {
+    let title = title.trim();
+    if title.is_empty() {
+        String::new()
+    } else {
+        format!("[{title}] ")
+    }
+}
+
+/// Status exibido após o nome no painel de usuários; `None` quando vazio.
+fn user_status_suffix(status: &str, separator: &str) -> Option<String> {
+    let status = status.trim();
+    (!status.is_empty()).then(|| format!(" {separator} {status}"))
+}
+
+/// Altura do texto do input (sem bordas): cresce com as linhas até `INPUT_MAX_LINES`.
+///
+/// Conta também a linha vazia aberta pelo cursor após uma linha cheia.
+fn input_lines_height(input: &str, cursor: usize, width: usize) -> u16 {
+    let wrapped = wrap_fixed_lines(input, width).len();
+    let (cursor_row, _) = input_cursor_position(input, cursor, width);
+    let lines = wrapped.max(cursor_row as usize + 1);
+    (lines.min(INPUT_MAX_LINES as usize) as u16).max(1)
+}
+
+/// Linha e coluna do cursor no input quebrado por `wrap_fixed_lines`.
+///
+/// `cursor` é a posição em bytes; uma linha cheia empurra o cursor para o início
+/// da próxima, como faz o terminal ao digitar na última coluna.
+fn input_cursor_position(input: &str, cursor: usize, width: usize) -> (u16, u16) {
+    if width == 0 {
+        return (0, 0);
+    }
+    let before = &input[..cursor.min(input.len())];
+    let mut row = 0usize;
+    let mut lines = before.split('\n').peekable();
+    while let Some(line) = lines.next() {
+        let len = line.chars().count();
+        if lines.peek().is_some() {
+            row += len.saturating_sub(1) / width + 1;
+        } else {
+            row += len / width;
+            let col = len % width;
+            return (row.min(u16::MAX as usize) as u16, col as u16);
+        }
+    }
+    (row.min(u16::MAX as usize) as u16, 0)
+}
+
+/// Últimos `width` segundos do histórico: o sparkline desenha da esquerda para a
+/// direita, então a janela precisa terminar no segundo mais recente.
+fn activity_window(activity: &[u64], width: usize) -> &[u64] {
+    &activity[activity.len().saturating_sub(width)..]
+}
+
+/// Linha "X is typing..." acima do input; `None` quando ninguém digita.
+fn typing_text(names: &[String]) -> Option<String> {
+    match names {
+        [] => None,
+        [name] => Some(format!("{name} is typing...")),
+        [first, second] => Some(format!("{first} and {second} are typing...")),
+        _ => Some("Several people are typing...".to_string()),
+    }
+}
+
+/// Instante atual em microssegundos Unix (mesma base do `Timestamp` do servidor).
+fn now_micros() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_micros() as i64)
+        .unwrap_or_default()
+}
+
+/// Hora exibida no prefixo da mensagem: crua (depuração), relativa ou data e hora.
+fn message_time_label(sent_at: &str, raw: bool, relative: bool, now: SystemTime) -> Option<String> {
+    if raw {
+        return (!sent_at.is_empty()).then(|| sent_at.to_string());
+    }
+    if relative {
+        humanize_age(sent_at, now).or_else(|| format_message_datetime(sent_at))
+    } else {
+        format_message_datetime(sent_at)
+    }
+}
+
+/// Texto exibido da mensagem, com marcador quando foi editada.
+fn message_body(message: &UiMessage) -> Cow<'_, str> {
+    if message.is_edited {
+        Cow::Owned(format!("{} (editado)", message.text))
+    } else {
+        Cow::Borrowed(message.text.as_str())
+    }
+}
+
+/// Linha de reações abaixo da mensagem (`👍 3  🎉 1`); `None` sem reações.
+fn reaction_summary(reactions: &[(String, usize)]) -> Option<String> {
+    if reactions.is_empty() {
+        return None;
+    }
+    let parts: Vec<String> = reactions
+        .iter()
+        .map(|(emoji, count)| format!("{emoji} {count}"))
+        .collect();
+    Some(parts.join("  "))
+}
+
+/// Monta a linha renderizada destacando menções `@nome` a usuários conhecidos.
+fn message_line(line: String, known_names: &[&str]) -> Line<'static> {
+    let mention_style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let spans: Vec<Span<'static>> = split_mentions(&line, known_names)
+        .into_iter()
+        .map(|segment| match segment {
+            MessageSegment::Plain(text) => Span::raw(text.to_string()),
+            MessageSegment::Mention(text) => Span::styled(text.to_string(), mention_style),
+        })
+        .collect();
+    Line::from(spans)
+}
+
+/// Primeira linha da mensagem com o nome do remetente na cor dele.
+///
+/// Se a quebra de linha cortou o nome (terminal estreito), cai no render comum.
+fn sender_line(
+    line: String,
+    sender_at: usize,
+    sender: &str,
+    color: Color,
+    known_names: &[&str],
+) -> Line<'static> {
+    let sender_end = sender_at + sender.len();
+    if line.get(sender_at..sender_end) != Some(sender) {
+        return message_line(line, known_names);
+    }
+    let mut spans = vec![
+        Span::raw(line[..sender_at].to_string()),
+        Span::styled(sender.to_string(), Style::default().fg(color)),
+    ];
+    spans.extend(message_line(line[sender_end..].to_string(), known_names).spans);
+    Line::from(spans)
+}
+
+/// Paleta de cores dos remetentes (sem amarelo, reservado às menções).
+const SENDER_PALETTE: [Color; 8] = [
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::Green,
+    Color::Magenta,
+    Color::Cyan,
+];
+
+/// Cor estável de um remetente: hash FNV-1a da identity sobre `SENDER_PALETTE`.
+/// Mensagens locais do "System" ficam sempre em cinza.
+fn color_for_identity(identity: &str) -> Color {
+    if identity == "System" {
+        return Color::Gray;
+    }
+    let hash = identity
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    SENDER_PALETTE[(hash % SENDER_PALETTE.len() as u64) as usize]
+}
+
+/// Estilo de uma mensagem: anúncios do host ocupam a linha inteira em destaque;
+/// mensagens do próprio usuário usam a cor configurada.
+fn message_style(
+    message: &UiMessage,
+    my_identity: Option<&str>,
+    own_color: Option<Color>,
+    theme: &Theme,
+) -> Style {
+    if message.is_pending {
+        return Style::default().fg(Color::DarkGray);
+    }
+    if message.is_announcement {
+        return Style::default()
+            .fg(theme.announcement)
+            .bg(theme.announcement_bg)
+            .add_modifier(Modifier::BOLD);
+    }
+
+    match (my_identity, own_color) {
+        (Some(me), Some(color)) if !me.is_empty() && me == message.sender => {
+            Style::default().fg(color)
+        }
+        _ => Style::default(),
+    }
+}
+
+/// Completa a linha com espaços para o fundo destacado cobrir toda a largura.
+fn pad_to_width(mut line: String, width: usize) -> String {
+    let len = line.width();
+    if len < width {
+        line.push_str(&" ".repeat(width - len));
+    }
+    line
+}
+
 /// Abrevia identity longa para caber no layout do terminal.
 fn short_identity(identity: &str) -> String {
     const MAX: usize = 18;
@@ -293,7 +960,7 @@ fn short_identity(identity: &str) -> String {
 }
 
 /// Tenta normalizar timestamps em formato curto `dd/mm/yyyy hh:mm`.
-fn format_message_datetime(raw: &str) -> Option<String> {
+pub(crate) fn format_message_datetime(raw: &str) -> Option<String> {
     let raw = raw.trim();
     if raw.is_empty() {
         return None;
@@ -309,12 +976,14 @@ fn format_message_datetime(raw: &str) -> Option<String> {
     }
 
     let mut date_parts = date_raw.split('-');
-    let year = date_parts.next();
-    let month = date_parts.next();
-    let day = date_parts.next();
-    if year.is_none() || month.is_none() || day.is_none() || date_parts.next().is_some() {
+    let (Some(year), Some(month), Some(day), None) = (
+        date_parts.next(),
+        date_parts.next(),
+        date_parts.next(),
+        date_parts.next(),
+    ) else {
         return Some(raw.to_string());
-    }
+    };
 
     let time_part = time_raw
         .split(['Z', '+'])
@@ -324,19 +993,192 @@ fn format_message_datetime(raw: &str) -> Option<String> {
         .next()
         .unwrap_or(time_raw);
 
-    let hm = if time_part.len() >= 5 {
-        &time_part[..5]
-    } else {
+    // `get` evita panic quando o corte cairia no meio de um caractere UTF-8.
+    let Some(hm) = time_part.get(..5) else {
         return Some(raw.to_string());
     };
+    let (hour, minute) = hm.split_once(':').unwrap_or(("", ""));
 
-    Some(format!(
-        "{}/{}/{} {}",
-        day.unwrap_or_default(),
-        month.unwrap_or_default(),
-        year.unwrap_or_default(),
-        hm
-    ))
+    let valid = is_number_in(year, 4, 0..=9999)
+        && is_number_in(month, 2, 1..=12)
+        && is_number_in(day, 2, 1..=31)
+        && is_number_in(hour, 2, 0..=23)
+        && is_number_in(minute, 2, 0..=59);
+    if !valid {
+        return Some(raw.to_string());
+    }
+
+    Some(format!("{day}/{month}/{year} {hm}"))
+}
+
+/// Destaca as ocorrências de `query` em cada span da linha, mantendo o estilo original.
+///
+/// Ocorrências divididas entre spans (ou entre linhas quebradas) ficam sem destaque.
+fn highlight_matches<'a>(line: Line<'a>, query: &str) -> Line<'a> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return line;
+    }
+    let highlight = Style::default().fg(Color::Black).bg(Color::Yellow);
+    let mut spans = Vec::with_capacity(line.spans.len());
+    for span in line.spans {
+        let lower = span.content.to_lowercase();
+        // Minúsculas com outro tamanho em bytes quebrariam os índices: mantém o span.
+        if lower.len() != span.content.len() || !lower.contains(&query) {
+            spans.push(span);
+            continue;
+        }
+        let mut rest = 0;
+        for (start, _) in lower.match_indices(&query) {
+            let end = start + query.len();
+            if start > rest {
+                spans.push(Span::styled(
+                    span.content[rest..start].to_string(),
+                    span.style,
+                ));
+            }
+            spans.push(Span::styled(
+                span.content[start..end].to_string(),
+                span.style.patch(highlight),
+            ));
+            rest = end;
+        }
+        if rest < span.content.len() {
+            spans.push(Span::styled(span.content[rest..].to_string(), span.style));
+        }
+    }
+    Line { spans, ..line }
+}
+
+/// Esquemas reconhecidos como link nas mensagens.
+const LINK_SCHEMES: [&str; 2] = ["https://", "http://"];
+
+/// Pontuação final que costuma fechar a frase, não a URL (`veja https://x.io.`).
+const LINK_TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', ')', '\'', '"'];
+
+/// Trechos de `text` que são URLs `http(s)://`, em bytes.
+///
+/// `continued` indica que a linha começa com o resto de uma URL quebrada na
+/// linha anterior: a primeira palavra também conta como link.
+fn link_ranges(text: &str, continued: bool) -> Vec<Range<usize>> {
+    let lower = text.to_ascii_lowercase();
+    let token_end = |start: usize| {
+        let end = text[start..]
+            .find(char::is_whitespace)
+            .map_or(text.len(), |offset| start + offset);
+        start
+            + text[start..end]
+                .trim_end_matches(LINK_TRAILING_PUNCTUATION)
+                .len()
+    };
+
+    let mut ranges = Vec::new();
+    let mut from = 0;
+    if continued {
+        let start = text.len() - text.trim_start().len();
+        let end = token_end(start);
+        if end > start {
+            ranges.push(start..end);
+            from = end;
+        }
+    }
+    while let Some((start, scheme_len)) = LINK_SCHEMES
+        .iter()
+        .filter_map(|scheme| {
+            lower[from..]
+                .find(scheme)
+                .map(|at| (from + at, scheme.len()))
+        })
+        .min()
+    {
+        // Só o esquema (`http://` sozinho) não vira link.
+        let end = token_end(start);
+        if end > start + scheme_len {
+            ranges.push(start..end);
+        }
+        from = end.max(start + scheme_len);
+    }
+    ranges
+}
+
+/// Para cada linha quebrada, se ela continua uma URL cortada na anterior.
+///
+/// Só palavras maiores que a linha são cortadas (ver `wrap_plain_lines`), então
+/// a URL continua quando a linha anterior está cheia e termina dentro de um link.
+fn link_continuations(lines: &[String], width: usize) -> Vec<bool> {
+    let mut flags = Vec::with_capacity(lines.len());
+    let mut continues = false;
+    for line in lines {
+        flags.push(continues);
+        let ends_in_link = !line.ends_with(char::is_whitespace)
+            && link_ranges(line, continues)
+                .last()
+                .is_some_and(|range| !line[range.start..].contains(char::is_whitespace));
+        continues = ends_in_link && line.width() >= width;
+    }
+    flags
+}
+
+/// Sublinha em ciano as URLs de cada span, mantendo o estilo do resto do texto.
+///
+/// `continued` vale para o primeiro span com texto (ver `link_ranges`).
+fn highlight_links(line: Line<'_>, mut continued: bool) -> Line<'_> {
+    let link = Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::UNDERLINED);
+    let mut spans = Vec::with_capacity(line.spans.len());
+    for span in line.spans {
+        let ranges = link_ranges(&span.content, continued);
+        if !span.content.trim().is_empty() {
+            continued = false;
+        }
+        if ranges.is_empty() {
+            spans.push(span);
+            continue;
+        }
+        let mut rest = 0;
+        for range in ranges {
+            if range.start > rest {
+                spans.push(Span::styled(
+                    span.content[rest..range.start].to_string(),
+                    span.style,
+                ));
+            }
+            spans.push(Span::styled(
+                span.content[range.clone()].to_string(),
+                span.style.patch(link),
+            ));
+            rest = range.end;
+        }
+        if rest < span.content.len() {
+            spans.push(Span::styled(span.content[rest..].to_string(), span.style));
+        }
+    }
+    Line { spans, ..line }
+}
+
+/// Idade de uma mensagem com menos de uma hora (`"now"`, `"42s ago"`, `"5m ago"`).
+///
+/// `None` para mensagens mais antigas ou timestamps ilegíveis; quem chama
+/// volta para `format_message_datetime`.
+fn humanize_age(sent_at: &str, now: SystemTime) -> Option<String> {
+    let sent = chrono::DateTime::parse_from_rfc3339(sent_at.trim()).ok()?;
+    let now = chrono::DateTime::<chrono::Utc>::from(now);
+    // Relógio local atrasado: mensagem "do futuro" conta como agora.
+    let secs = (now - sent.to_utc()).num_seconds().max(0);
+    match secs {
+        0 => Some("now".to_string()),
+        1..=59 => Some(format!("{secs}s ago")),
+        60..=3599 => Some(format!("{}m ago", secs / 60)),
+        _ => None,
+    }
+}
+
+/// Confere se `value` tem exatamente `digits` dígitos ASCII e cabe no intervalo.
+fn is_number_in(value: &str, digits: usize, range: std::ops::RangeInclusive<u32>) -> bool {
+    value.len() == digits
+        && value.bytes().all(|b| b.is_ascii_digit())
+        && value.parse::<u32>().is_ok_and(|n| range.contains(&n))
 }
 
 /// Quebra uma mensagem em múltiplas linhas sem perder o contexto do prefixo
@@ -346,7 +1188,7 @@ fn wrap_message_lines(prefix: &str, text: &str, total_width: usize) -> Vec<Strin
         return vec![];
     }
 
-    let prefix_width = prefix.chars().count();
+    let prefix_width = prefix.width();
     if prefix_width >= total_width {
         let full = format!("{prefix}{text}");
         return wrap_plain_lines(&full, total_width);
@@ -373,38 +1215,88 @@ fn wrap_message_lines(prefix: &str, text: &str, total_width: usize) -> Vec<Strin
     out
 }
 
-/// Quebra texto bruto por largura fixa preservando quebras de linha existentes.
+/// Quebra texto nos espaços, preservando quebras de linha existentes.
+///
+/// Só palavras maiores que a linha são cortadas no meio; o espaço onde a linha
+/// quebra é descartado. Larguras em colunas do terminal (CJK e emoji ocupam duas).
 fn wrap_plain_lines(text: &str, width: usize) -> Vec<String> {
     if width == 0 {
         return vec![];
     }
 
     let mut out = Vec::new();
-    let lines: Vec<&str> = if text.is_empty() {
-        vec![""]
-    } else {
-        text.split('\n').collect()
-    };
-
-    for line in lines {
-        if line.is_empty() {
-            out.push(String::new());
-            continue;
+    for line in text.split('\n') {
+        let first = out.len();
+        let mut current = String::new();
+        let mut current_len = 0usize;
+        for word in line.split(' ') {
+            let word_len = word.width();
+            if current_len > 0 && current_len + 1 + word_len <= width {
+                current.push(' ');
+                current.push_str(word);
+                current_len += 1 + word_len;
+                continue;
+            }
+            if current_len > 0 {
+                out.push(std::mem::take(&mut current));
+            }
+            // Palavra maior que a linha: corta em pedaços de `width`.
+            let mut chunks = split_display_width(word, width);
+            let last = chunks.pop().unwrap_or_default();
+            out.extend(chunks);
+            current_len = last.width();
+            current = last;
         }
-
-        let chars: Vec<char> = line.chars().collect();
-        let mut start = 0usize;
-        while start < chars.len() {
-            let end = (start + width).min(chars.len());
-            let chunk: String = chars[start..end].iter().collect();
-            out.push(chunk);
-            start = end;
+        if current_len > 0 || out.len() == first {
+            out.push(current);
         }
     }
 
     out
 }
 
+/// Quebra por largura fixa (caractere a caractere), preservando quebras de linha.
+///
+/// Usada no input, onde a posição do cursor depende de quebras previsíveis.
+fn wrap_fixed_lines(text: &str, width: usize) -> Vec<String> {
+    if width == 0 {
+        return vec![];
+    }
+    text.split('\n')
+        .flat_map(|line| split_fixed_width(line, width))
+        .collect()
+}
+
+/// Pedaços de até `width` caracteres; linha vazia vira um pedaço vazio.
+fn split_fixed_width(line: &str, width: usize) -> Vec<String> {
+    let chars: Vec<char> = line.chars().collect();
+    if chars.is_empty() {
+        return vec![String::new()];
+    }
+    chars
+        .chunks(width)
+        .map(|chunk| chunk.iter().collect())
+        .collect()
+}
+
+/// Pedaços de até `width` colunas; um caractere mais largo que `width` fica sozinho.
+fn split_display_width(word: &str, width: usize) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut current = String::new();
+    let mut current_width = 0usize;
+    for c in word.chars() {
+        let c_width = c.width().unwrap_or(0);
+        if current_width > 0 && current_width + c_width > width {
+            out.push(std::mem::take(&mut current));
+            current_width = 0;
+        }
+        current.push(c);
+        current_width += c_width;
+    }
+    out.push(current);
+    out
+}
+
 #[cfg(test)]
 #[path = "../tests/ui_message_screen_tests.rs"]
 mod tests;
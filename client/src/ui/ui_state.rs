@@ -1,3 +1,42 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    layout::{Position, Rect},
+    style::Color,
+};
+use serde::Serialize;
+
+use crate::ui::{mentions::name_candidates, theme::Theme};
+
+/// Quantidade de mensagens carregadas por página no painel de mensagens.
+pub const MESSAGES_PAGE_SIZE: usize = 100;
+
+/// Quantidade de mensagens privadas exibidas na seção de DMs.
+pub const DIRECT_MESSAGES_VISIBLE: usize = 4;
+
+/// Indicador "digitando..." mais antigo que isto é descartado (client caiu sem avisar).
+pub const TYPING_EXPIRY_MICROS: i64 = 5_000_000;
+
+/// Intervalo mínimo entre dois `set_typing(true)` enquanto o usuário digita.
+pub const TYPING_REFRESH: Duration = Duration::from_secs(2);
+
+/// Tamanho máximo do nome, em caracteres (o servidor rejeita nomes maiores).
+///
+/// Cópia de `MAX_NAME_LEN` em `server/src/lib.rs`: o client não depende do
+/// crate do módulo, então mudar um exige mudar o outro.
+pub const MAX_NAME_LEN: usize = 24;
+
+/// Tamanho máximo (em caracteres) do trecho citado por Ctrl+Q.
+pub const QUOTE_MAX_CHARS: usize = 80;
+
+/// Quantidade de entradas guardadas no histórico do input (Alt+Up/Alt+Down).
+pub const INPUT_HISTORY_LIMIT: usize = 50;
+
 /// Telas principais da aplicação.
 #[derive(Clone, Copy, Default, PartialEq, Eq)]
 pub enum UiScreen {
@@ -10,7 +49,14 @@ pub enum UiScreen {
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum UiPopup {
     ChooseName,
-    Soon,
+    Options,
+    ConfirmDeleteAccount,
+    /// Confirma apagar a mensagem selecionada (`delete_selection`).
+    ConfirmDeleteMessage,
+    /// Ajuda do chat (`/help`).
+    Help,
+    /// Campo de busca nas mensagens do chat.
+    Search,
 }
 
 /// Itens disponíveis no menu principal.
@@ -37,7 +83,58 @@ impl MainMenuItem {
     }
 }
 
-#[derive(Clone, Default)]
+/// Posição do scroll da lista de mensagens.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScrollPosition {
+    /// Travado nas mensagens mais novas (acompanha novas mensagens).
+    #[default]
+    Bottom,
+    /// Travado na mensagem mais antiga carregada.
+    Top,
+    /// Distância em linhas a partir do final.
+    Offset(usize),
+}
+
+impl ScrollPosition {
+    /// Sobe `step` linhas em direção às mensagens antigas.
+    pub fn scroll_up(self, step: usize, max_scroll: usize) -> Self {
+        match self {
+            Self::Top => Self::Top,
+            Self::Bottom => Self::Offset(step).normalized(max_scroll),
+            Self::Offset(n) => Self::Offset(n.saturating_add(step)).normalized(max_scroll),
+        }
+    }
+
+    /// Desce `step` linhas em direção às mensagens novas.
+    pub fn scroll_down(self, step: usize, max_scroll: usize) -> Self {
+        let from_bottom = match self {
+            Self::Bottom => return Self::Bottom,
+            Self::Top => max_scroll,
+            Self::Offset(n) => n.min(max_scroll),
+        };
+        Self::Offset(from_bottom.saturating_sub(step)).normalized(max_scroll)
+    }
+
+    /// Linha do topo visível (offset para `Paragraph::scroll`) dado o scroll máximo atual.
+    pub fn resolve(self, max_scroll: usize) -> usize {
+        match self {
+            Self::Bottom => max_scroll,
+            Self::Top => 0,
+            Self::Offset(n) => max_scroll.saturating_sub(n.min(max_scroll)),
+        }
+    }
+
+    /// Converte offsets nos extremos para `Bottom`/`Top`.
+    fn normalized(self, max_scroll: usize) -> Self {
+        match self {
+            Self::Offset(0) => Self::Bottom,
+            Self::Offset(n) if max_scroll > 0 && n >= max_scroll => Self::Top,
+            other => other,
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize)]
 pub struct UiMessage {
     /// ID para ordenação estável no chat.
     pub id: u64,
@@ -46,13 +143,129 @@ pub struct UiMessage {
     pub text: String,
     /// Timestamp textual recebido do backend.
     pub sent_at: String,
+    /// Aviso do host (renderizado em destaque).
+    pub is_announcement: bool,
+    /// Eco otimista ainda não confirmado pelo servidor.
+    pub is_pending: bool,
+    /// Texto corrigido depois do envio (`edited_at` no servidor).
+    pub is_edited: bool,
+    /// Contagem de reações por emoji, na ordem da primeira reação.
+    pub reactions: Vec<(String, usize)>,
+}
+
+/// Mensagem privada em que esta conexão é remetente ou destinatária.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UiDirectMessage {
+    pub id: u64,
+    pub sender: String,
+    pub recipient: String,
+    pub text: String,
+    /// Timestamp textual recebido do backend.
+    pub sent_at: String,
 }
 
+/// Mensagem enviada por esta conexão aguardando a linha real do servidor.
+#[derive(Clone, Debug, Default)]
+pub struct PendingMessage {
+    /// ID local (faixa de mensagens locais) usado na renderização.
+    pub local_id: u64,
+    pub sender: String,
+    pub text: String,
+    /// Momento do envio em microssegundos desde a época Unix.
+    pub sent_at_micros: i64,
+    /// Maior ID remoto conhecido no envio; só linhas mais novas podem confirmar.
+    pub after_id: u64,
+}
+
+/// Linha da tabela `typing`: quem está digitando e desde quando (µs Unix).
 #[derive(Clone, Default)]
+pub struct UiTyping {
+    pub identity: String,
+    pub started_at_micros: i64,
+}
+
+#[derive(Clone, Default, Serialize)]
 pub struct UiUser {
     pub identity: String,
     pub name: String,
     pub online: bool,
+    /// Status curto definido com `set_status` (vazio = nada exibido).
+    pub status: String,
+    /// Título entre colchetes antes do nome, definido com `set_title` (vazio = nenhum).
+    pub title: String,
+}
+
+/// Troca de presença ainda dentro da carência (`UiState::offline_grace`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PendingPresence {
+    /// Caiu, mas segue online na tela até a carência passar.
+    Offline(Instant),
+    /// Entrou e já aparece online; o aviso sai quando a carência passar.
+    Joined(Instant),
+}
+
+impl PendingPresence {
+    fn since(self) -> Instant {
+        match self {
+            Self::Offline(since) | Self::Joined(since) => since,
+        }
+    }
+}
+
+/// Nome e título por identity, montados a partir de `UiState::users`.
+///
+/// Guardado em cache no `UiState` e reconstruído só quando `users_version` muda,
+/// para o render não remontar os mapas a cada frame.
+#[derive(Clone, Debug, Default)]
+pub struct UserLookup {
+    /// `users_version` usada na construção (`None` = nunca construído).
+    version: Option<u64>,
+    names: HashMap<String, String>,
+    titles: HashMap<String, String>,
+}
+
+impl UserLookup {
+    pub fn build(users: &[UiUser], version: u64) -> Self {
+        Self {
+            version: Some(version),
+            names: users
+                .iter()
+                .map(|u| (u.identity.clone(), u.name.clone()))
+                .collect(),
+            titles: users
+                .iter()
+                .map(|u| (u.identity.clone(), u.title.clone()))
+                .collect(),
+        }
+    }
+
+    pub fn is_current(&self, version: u64) -> bool {
+        self.version == Some(version)
+    }
+
+    pub fn name(&self, identity: &str) -> Option<&str> {
+        self.names.get(identity).map(String::as_str)
+    }
+
+    pub fn title(&self, identity: &str) -> Option<&str> {
+        self.titles.get(identity).map(String::as_str)
+    }
+}
+
+/// Painéis do chat que rolam com a roda do mouse.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChatPanel {
+    Messages,
+    Users,
+}
+
+/// Estado do Tab: onde começa a palavra completada e os nomes possíveis.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NameCompletion {
+    /// Byte do input onde começa o nome inserido.
+    pub start: usize,
+    pub candidates: Vec<String>,
+    pub index: usize,
 }
 
 #[derive(Clone, Default)]
@@ -61,23 +274,712 @@ pub struct UiState {
     pub screen: UiScreen,
     /// Popup modal atual, se existir.
     pub popup: Option<UiPopup>,
+    /// Motivo da última rejeição de `set_name` (exibido no popup de nome).
+    pub name_error: Option<String>,
     /// Índice selecionado no menu principal.
     pub menu_selected: usize,
-    /// Distância do final da lista de mensagens (0 = "travado" nas mais novas).
-    pub messages_scroll_from_bottom: usize,
+    /// Posição do scroll da lista de mensagens.
+    pub messages_scroll: ScrollPosition,
+    /// Páginas extras de mensagens antigas carregadas além da primeira.
+    pub messages_extra_pages: usize,
+    /// Pedido de "Load older" em andamento (evita pedidos duplicados).
+    pub loading_older_messages: bool,
+    /// Scroll máximo calculado no último render (depende da largura/altura do terminal).
+    pub messages_max_scroll: usize,
     /// Offset vertical da lista de usuários.
     pub users_scroll: usize,
+    /// Área do painel de mensagens no último render (hit-test do mouse).
+    pub messages_area: Rect,
+    /// Área do painel de usuários no último render (hit-test do mouse).
+    pub users_area: Rect,
     /// Evita disparar eventos de presença antes da primeira sincronização.
     pub users_presence_initialized: bool,
     /// Contador para IDs de mensagens locais do sistema.
     pub next_system_message_id: u64,
     /// Mensagens locais não persistidas no servidor.
     pub system_messages: Vec<UiMessage>,
+    /// Ecos otimistas de mensagens enviadas e ainda não confirmadas.
+    pub pending_messages: Vec<PendingMessage>,
     /// Lista renderizada no painel de mensagens (backend + locais).
     pub messages: Vec<UiMessage>,
+    /// Mensagem de chat mais nova vista com o scroll no fim; as seguintes contam como não lidas.
+    pub last_read_message_id: u64,
     pub users: Vec<UiUser>,
+    /// Identities dos bots desta sessão, atualizadas pelo loop principal.
+    pub bot_identities: HashSet<String>,
+    /// Incrementado a cada troca de `users` (invalida `user_lookup`).
+    pub users_version: u64,
+    /// Cache identity -> nome/título; `Arc` para o snapshot por frame não copiar os mapas.
+    pub user_lookup: Arc<UserLookup>,
+    /// Quedas e entradas recentes, ainda dentro da carência: identity -> troca.
+    pub pending_presence: HashMap<String, PendingPresence>,
+    /// Carência antes de exibir alguém como offline ou de avisar que entrou
+    /// (`ui.offline_grace_secs`).
+    pub offline_grace: Duration,
+    /// Indicadores "digitando..." vindos do servidor.
+    pub typing: Vec<UiTyping>,
+    /// Último `set_typing(true)` enviado por esta conexão (`None` = não digitando).
+    pub typing_sent_at: Option<Instant>,
+    /// Mensagens privadas desta conexão, em ordem de envio.
+    pub direct_messages: Vec<UiDirectMessage>,
+    /// Oculta os avisos locais do sistema no chat (só somem da tela); padrão: visíveis.
+    pub hide_system: bool,
+    /// Oculta usuários offline do painel lateral (`/hideoffline`).
+    pub hide_offline_users: bool,
+    /// Exibe animação "Waiting for others…" quando a sala está vazia.
+    pub idle_animation: bool,
+    /// Contador de ticks do loop principal, usado em animações.
+    pub animation_tick: u64,
     /// Buffer do input atual.
     pub input: String,
+    /// Motivo da última mensagem recusada; deixa a borda do input vermelha até a próxima edição.
+    pub input_error: Option<String>,
+    /// Posição do cursor em `input`, em bytes (sempre numa fronteira de caractere).
+    pub cursor: usize,
+    /// Textos enviados pelo input, do mais antigo ao mais recente.
+    pub input_history: Vec<String>,
+    /// Entrada do histórico exibida no input (`None` = editando texto novo).
+    pub history_cursor: Option<usize>,
+    /// Texto que estava sendo digitado antes de navegar pelo histórico.
+    pub history_draft: String,
+    /// Completamento de nome em andamento (Tab repetido alterna os candidatos).
+    pub name_completion: Option<NameCompletion>,
+    /// Mensagem citada no início do input; repetir o atalho cita a anterior.
+    pub quoted_message_id: Option<u64>,
+    /// Mostra a hora das mensagens recentes como idade ("2m ago") em vez de data e hora.
+    pub relative_timestamps: bool,
+    /// Mostra `sent_at` sem interpretar, para depurar fuso horário (`--debug`).
+    pub raw_timestamps: bool,
+    /// Largura máxima do conteúdo do painel de mensagens (`ui.max_message_width`; `0` = toda).
+    pub max_message_width: u16,
+    /// Palavras (em minúsculas) mascaradas com `*` em tudo que vai para a tela (`--filter-file`).
+    pub filter_words: Vec<String>,
+    /// Texto digitado no popup de busca.
+    pub search_input: String,
+    /// Busca ativa: ocorrências ficam destacadas e `n`/`N` navegam entre elas.
+    pub search_query: Option<String>,
+    /// Mensagem em foco na busca.
+    pub search_match: Option<u64>,
+    /// Mensagem para a qual rolar no próximo render (foco da busca, seleção
+    /// para apagar...); atendido uma vez e desfeito.
+    pub scroll_to_message: Option<u64>,
+    /// Mensagem própria selecionada para apagar; Up/Down trocam, Enter confirma.
+    pub delete_selection: Option<u64>,
+    /// Paleta de cores ativa (`ui.theme`).
+    pub theme: Theme,
+    /// Modo de acessibilidade: glifos só em ASCII e paleta de alto contraste.
+    pub accessibility: bool,
+    /// Cor de destaque das mensagens enviadas pelo próprio usuário (`None` desativa).
+    pub own_message_color: Option<Color>,
+    /// Pedido de re-sorteio das personas dos bots, consumido pelo loop principal.
+    pub reroll_bots_requested: bool,
+    /// Pedido de `/preview <bot> [pergunta]`, consumido pelo loop principal.
+    pub bot_preview_requested: Option<(String, String)>,
+    /// Pedido de `/ai`/`/ask` (bot escolhido, texto), consumido pelo loop principal.
+    pub ai_reply_requested: Option<(Option<String>, String)>,
+    /// Pedido de `/connect <uri> <module>`, consumido pelo loop principal.
+    pub connect_requested: Option<(String, String)>,
     /// Flag global de encerramento do app.
     pub should_quit: bool,
 }
+
+impl UiState {
+    /// Troca a lista de usuários e invalida o cache de nomes.
+    pub fn set_users(&mut self, users: Vec<UiUser>) {
+        self.users = users;
+        self.users_version = self.users_version.wrapping_add(1);
+    }
+
+    /// Reconstrói o cache de nomes se a lista de usuários mudou desde a última vez.
+    pub fn refresh_user_lookup(&mut self) {
+        if !self.user_lookup.is_current(self.users_version) {
+            self.user_lookup = Arc::new(UserLookup::build(&self.users, self.users_version));
+        }
+    }
+
+    /// Cache de nomes; se estiver desatualizado, monta um temporário sem guardá-lo.
+    pub fn user_lookup(&self) -> Cow<'_, UserLookup> {
+        if self.user_lookup.is_current(self.users_version) {
+            Cow::Borrowed(&self.user_lookup)
+        } else {
+            Cow::Owned(UserLookup::build(&self.users, self.users_version))
+        }
+    }
+
+    /// Janela de mensagens renderizadas (as mais recentes dentro das páginas carregadas).
+    pub fn windowed_messages(&self) -> &[UiMessage] {
+        let window = MESSAGES_PAGE_SIZE.saturating_mul(self.messages_extra_pages.saturating_add(1));
+        let start = self.messages.len().saturating_sub(window);
+        &self.messages[start..]
+    }
+
+    /// Indica se existem mensagens mais antigas fora da janela atual.
+    pub fn has_older_messages(&self) -> bool {
+        self.windowed_messages().len() < self.messages.len()
+    }
+
+    /// Painel sob a posição do mouse, pelas áreas do último render.
+    pub fn panel_at(&self, column: u16, row: u16) -> Option<ChatPanel> {
+        let position = Position::new(column, row);
+        if self.messages_area.contains(position) {
+            Some(ChatPanel::Messages)
+        } else if self.users_area.contains(position) {
+            Some(ChatPanel::Users)
+        } else {
+            None
+        }
+    }
+
+    /// Marca como lidas todas as mensagens de chat atuais.
+    pub fn mark_messages_read(&mut self) {
+        let newest = self
+            .messages
+            .iter()
+            .filter(|m| counts_as_unread(m))
+            .map(|m| m.id)
+            .max()
+            .unwrap_or(0);
+        self.last_read_message_id = self.last_read_message_id.max(newest);
+    }
+
+    /// Mensagens de chat chegadas depois de `last_read_message_id`.
+    pub fn unread_count(&self) -> usize {
+        self.messages
+            .iter()
+            .filter(|m| counts_as_unread(m) && m.id > self.last_read_message_id)
+            .count()
+    }
+
+    /// Mensagens exibidas no chat: a janela carregada, sem os avisos do sistema se ocultos.
+    pub fn chat_messages(&self) -> Vec<&UiMessage> {
+        self.windowed_messages()
+            .iter()
+            .filter(|m| !self.hide_system || m.sender != "System")
+            .collect()
+    }
+
+    /// Mensagem que a tecla de cópia copia: a ocorrência focada da busca ou,
+    /// sem ela, a mais recente visível no chat.
+    pub fn copy_target(&self) -> Option<&UiMessage> {
+        let visible = self.chat_messages();
+        self.search_match
+            .and_then(|id| visible.iter().find(|m| m.id == id).copied())
+            .or_else(|| visible.last().copied())
+    }
+
+    /// Aplica o texto do popup de busca e foca a ocorrência mais recente.
+    ///
+    /// Texto vazio encerra a busca.
+    pub fn start_search(&mut self) {
+        let query = self.search_input.trim().to_string();
+        self.search_input.clear();
+        self.popup = None;
+        self.search_match = None;
+        self.search_query = (!query.is_empty()).then_some(query);
+        self.step_search(true);
+    }
+
+    /// Foca a ocorrência vizinha: `older` sobe para mensagens antigas
+    /// (`keybindings.search_next`), senão desce (`keybindings.search_previous`).
+    ///
+    /// Sem foco, começa pela mais recente; nas pontas o foco fica onde está.
+    /// Só as mensagens já carregadas entram na busca.
+    pub fn step_search(&mut self, older: bool) {
+        let Some(query) = &self.search_query else {
+            return;
+        };
+        let messages = self.chat_messages();
+        let ids: Vec<u64> = find_matching_message_indices(&messages, query)
+            .into_iter()
+            .map(|index| messages[index].id)
+            .collect();
+        let Some(last) = ids.len().checked_sub(1) else {
+            return;
+        };
+        let next = match self
+            .search_match
+            .and_then(|id| ids.iter().position(|&m| m == id))
+        {
+            None => last,
+            Some(current) if older => current.saturating_sub(1),
+            Some(current) => (current + 1).min(last),
+        };
+        self.search_match = Some(ids[next]);
+        self.scroll_to_message = self.search_match;
+    }
+
+    /// Encerra a busca e tira os destaques.
+    pub fn clear_search(&mut self) {
+        self.search_query = None;
+        if self.scroll_to_message == self.search_match {
+            self.scroll_to_message = None;
+        }
+        self.search_match = None;
+    }
+
+    /// Foca a mensagem própria vizinha para apagar: `older` sobe, senão desce.
+    ///
+    /// Sem seleção, começa pela mais recente; sem mensagens próprias visíveis,
+    /// a seleção é desfeita.
+    pub fn step_delete_selection(&mut self, older: bool, my_identity: Option<&str>) {
+        let ids: Vec<u64> = self
+            .chat_messages()
+            .into_iter()
+            .filter(|m| can_delete(m, my_identity))
+            .map(|m| m.id)
+            .collect();
+        let Some(last) = ids.len().checked_sub(1) else {
+            self.delete_selection = None;
+            return;
+        };
+        let next = match self
+            .delete_selection
+            .and_then(|id| ids.iter().position(|&m| m == id))
+        {
+            None => last,
+            Some(current) if older => current.saturating_sub(1),
+            Some(current) => (current + 1).min(last),
+        };
+        self.delete_selection = Some(ids[next]);
+        self.scroll_to_message = self.delete_selection;
+    }
+
+    /// Mensagem selecionada para apagar, se ainda estiver visível e for própria.
+    pub fn delete_target(&self, my_identity: Option<&str>) -> Option<&UiMessage> {
+        let id = self.delete_selection?;
+        self.chat_messages()
+            .into_iter()
+            .find(|m| m.id == id && can_delete(m, my_identity))
+    }
+
+    /// Sala vazia: ninguém online além do próprio usuário e dos bots.
+    pub fn is_room_empty(&self, my_identity: Option<&str>) -> bool {
+        !self.users.iter().any(|u| {
+            u.online
+                && Some(u.identity.as_str()) != my_identity
+                && !self.bot_identities.contains(&u.identity)
+        })
+    }
+
+    /// Decide se o scroll chegou ao topo e deve disparar o carregamento de mais mensagens.
+    pub fn should_load_older_messages(&self) -> bool {
+        !self.loading_older_messages
+            && self.has_older_messages()
+            && self.messages_scroll.resolve(self.messages_max_scroll) == 0
+    }
+
+    /// Busca usuário pelo nome exibido, sem diferenciar maiúsculas.
+    pub fn find_user_by_name(&self, name: &str) -> Option<&UiUser> {
+        let wanted = name.trim().to_lowercase();
+        self.users.iter().find(|u| u.name.to_lowercase() == wanted)
+    }
+
+    /// Mensagem confirmada mais recente do chat (alvo do `/react`).
+    pub fn latest_reactable_message_id(&self) -> Option<u64> {
+        self.messages
+            .iter()
+            .rev()
+            .find(|m| !m.is_pending && m.sender != "System")
+            .map(|m| m.id)
+    }
+
+    /// Cita uma mensagem no início do input, preservando o que já foi digitado.
+    ///
+    /// A primeira chamada cita a mensagem mais recente; chamadas seguintes, com
+    /// a citação ainda intacta, voltam uma mensagem de cada vez (e recomeçam do
+    /// fim ao passar da mais antiga).
+    pub fn quote_previous_message(&mut self) {
+        let candidates: Vec<&UiMessage> = self
+            .messages
+            .iter()
+            .filter(|m| !m.is_pending && m.sender != "System")
+            .collect();
+
+        let current = self
+            .quoted_message_id
+            .and_then(|id| candidates.iter().position(|m| m.id == id))
+            .filter(|&at| self.input.starts_with(&self.quote_for(candidates[at])));
+        let body = match current {
+            Some(at) => self.input[self.quote_for(candidates[at]).len()..].to_string(),
+            None => self.input.clone(),
+        };
+        let target = match current {
+            Some(at) if at > 0 => candidates.get(at - 1),
+            _ => candidates.last(),
+        };
+
+        let Some(target) = target else {
+            return;
+        };
+        self.quoted_message_id = Some(target.id);
+        self.input = format!("{}{body}", self.quote_for(target));
+        self.cursor = self.input.len();
+    }
+
+    /// Prefixo de citação de uma mensagem, com o nome atual do remetente.
+    fn quote_for(&self, message: &UiMessage) -> String {
+        let sender = self
+            .users
+            .iter()
+            .find(|u| u.identity == message.sender)
+            .map_or(message.sender.as_str(), |u| u.name.as_str());
+        quote_prefill(sender, &message.text)
+    }
+
+    /// Últimas mensagens privadas exibidas na seção de DMs.
+    pub fn recent_direct_messages(&self) -> &[UiDirectMessage] {
+        let start = self
+            .direct_messages
+            .len()
+            .saturating_sub(DIRECT_MESSAGES_VISIBLE);
+        &self.direct_messages[start..]
+    }
+
+    /// Usuários exibidos no painel lateral, respeitando o filtro de offline.
+    pub fn visible_users(&self) -> Vec<&UiUser> {
+        self.users
+            .iter()
+            .filter(|u| u.online || !self.hide_offline_users)
+            .collect()
+    }
+
+    /// Insere `c` na posição do cursor e avança o cursor.
+    pub fn insert_at_cursor(&mut self, c: char) {
+        let at = self.clamped_cursor();
+        self.input.insert(at, c);
+        self.cursor = at + c.len_utf8();
+        self.input_error = None;
+    }
+
+    /// Apaga o caractere antes do cursor (Backspace).
+    pub fn delete_before_cursor(&mut self) {
+        let at = self.clamped_cursor();
+        if let Some((start, _)) = self.input[..at].char_indices().next_back() {
+            self.input.remove(start);
+            self.cursor = start;
+        }
+        self.input_error = None;
+    }
+
+    pub fn move_cursor_left(&mut self) {
+        let at = self.clamped_cursor();
+        self.cursor = self.input[..at]
+            .char_indices()
+            .next_back()
+            .map_or(0, |(start, _)| start);
+    }
+
+    pub fn move_cursor_right(&mut self) {
+        let at = self.clamped_cursor();
+        self.cursor = self.input[at..]
+            .chars()
+            .next()
+            .map_or(at, |c| at + c.len_utf8());
+    }
+
+    pub fn move_cursor_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_cursor_end(&mut self) {
+        self.cursor = self.input.len();
+    }
+
+    /// Esvazia o input e volta o cursor para o início.
+    pub fn clear_input(&mut self) {
+        self.input.clear();
+        self.cursor = 0;
+        self.quoted_message_id = None;
+        self.history_cursor = None;
+        self.name_completion = None;
+        self.input_error = None;
+    }
+
+    /// Devolve ao input uma mensagem recusada, com o motivo.
+    ///
+    /// Se outro texto já está sendo digitado, ele fica; só o motivo aparece.
+    pub fn restore_rejected_send(&mut self, text: &str, reason: &str) {
+        if self.input.trim().is_empty() {
+            self.input = text.to_string();
+            self.cursor = self.input.len();
+        }
+        self.input_error = Some(reason.to_string());
+    }
+
+    /// Completa (Tab) a última palavra do input com o nome de um usuário online.
+    ///
+    /// Um `@` no começo da palavra é preservado. Se o input ainda termina no nome
+    /// completado pelo Tab anterior, troca pelo próximo candidato.
+    pub fn complete_name_at_end(&mut self) {
+        if let Some(completion) = &mut self.name_completion
+            && self.input.get(completion.start..) == Some(&completion.candidates[completion.index])
+        {
+            completion.index = (completion.index + 1) % completion.candidates.len();
+            let name = completion.candidates[completion.index].clone();
+            self.input.truncate(completion.start);
+            self.input.push_str(&name);
+            self.cursor = self.input.len();
+            return;
+        }
+
+        let last_word = self.input.rsplit(char::is_whitespace).next().unwrap_or("");
+        let word_start = self.input.len() - last_word.len();
+        let start = if self.input[word_start..].starts_with('@') {
+            word_start + 1
+        } else {
+            word_start
+        };
+        let candidates = name_candidates(&self.input[start..], &self.users);
+        let Some(first) = candidates.first() else {
+            self.name_completion = None;
+            return;
+        };
+        self.input.truncate(start);
+        self.input.push_str(first);
+        self.cursor = self.input.len();
+        self.name_completion = Some(NameCompletion {
+            start,
+            candidates,
+            index: 0,
+        });
+    }
+
+    /// Guarda um texto enviado no histórico (sem repetir o último e até `INPUT_HISTORY_LIMIT`).
+    pub fn push_input_history(&mut self, text: &str) {
+        if text.is_empty() || self.input_history.last().is_some_and(|last| last == text) {
+            return;
+        }
+        self.input_history.push(text.to_string());
+        let overflow = self.input_history.len().saturating_sub(INPUT_HISTORY_LIMIT);
+        self.input_history.drain(..overflow);
+    }
+
+    /// Troca o input pela entrada anterior do histórico, guardando o rascunho atual.
+    pub fn history_previous(&mut self) {
+        let index = match self.history_cursor {
+            Some(0) => return,
+            Some(index) => index - 1,
+            None if self.input_history.is_empty() => return,
+            None => {
+                self.history_draft = self.input.clone();
+                self.input_history.len() - 1
+            }
+        };
+        self.show_history_entry(Some(index));
+    }
+
+    /// Avança no histórico; depois da entrada mais recente, devolve o rascunho.
+    pub fn history_next(&mut self) {
+        let Some(index) = self.history_cursor else {
+            return;
+        };
+        let next = (index + 1 < self.input_history.len()).then_some(index + 1);
+        self.show_history_entry(next);
+    }
+
+    fn show_history_entry(&mut self, index: Option<usize>) {
+        self.input = match index {
+            Some(index) => self.input_history[index].clone(),
+            None => std::mem::take(&mut self.history_draft),
+        };
+        self.cursor = self.input.len();
+        self.history_cursor = index;
+        self.quoted_message_id = None;
+    }
+
+    /// Cursor limitado ao tamanho do input e recuado até uma fronteira de caractere.
+    pub fn clamped_cursor(&self) -> usize {
+        let mut at = self.cursor.min(self.input.len());
+        while !self.input.is_char_boundary(at) {
+            at -= 1;
+        }
+        at
+    }
+
+    /// Nomes de quem está digitando: online, exceto `me`, sem indicadores vencidos.
+    pub fn typing_names(&self, me: Option<&str>, now_micros: i64) -> Vec<String> {
+        self.typing
+            .iter()
+            .filter(|t| now_micros - t.started_at_micros < TYPING_EXPIRY_MICROS)
+            .filter(|t| Some(t.identity.as_str()) != me)
+            .filter_map(|t| {
+                self.users
+                    .iter()
+                    .find(|u| u.online && u.identity == t.identity)
+            })
+            .map(|u| u.name.clone())
+            .collect()
+    }
+
+    /// Marca que o usuário digitou; `true` quando é hora de (re)enviar `set_typing(true)`.
+    pub fn mark_typing(&mut self, now: Instant) -> bool {
+        let due = self
+            .typing_sent_at
+            .is_none_or(|sent| now.duration_since(sent) >= TYPING_REFRESH);
+        if due {
+            self.typing_sent_at = Some(now);
+        }
+        due
+    }
+
+    /// Aplica a carência de presença a um snapshot recém-sincronizado e devolve
+    /// quem entrou e quem saiu, para avisar no chat já.
+    ///
+    /// Quem estava online na tela e caiu continua online até `offline_grace`
+    /// passar; voltar antes disso cancela o timer sem piscar na lista. Quem entra
+    /// aparece na hora, mas o aviso espera a mesma carência: entrar e sair dentro
+    /// dela não gera aviso nenhum. Antes do primeiro sync não há avisos.
+    pub fn apply_offline_grace(
+        &mut self,
+        users: &mut [UiUser],
+        now: Instant,
+    ) -> (Vec<UiUser>, Vec<UiUser>) {
+        let (mut connected, mut disconnected) = (Vec::new(), Vec::new());
+        let announce = self.users_presence_initialized;
+        for user in users.iter_mut() {
+            let shown_online = self
+                .users
+                .iter()
+                .any(|u| u.online && u.identity == user.identity);
+            let pending = self.pending_presence.get(&user.identity).copied();
+            if user.online {
+                match pending {
+                    Some(PendingPresence::Offline(_)) => {
+                        self.pending_presence.remove(&user.identity);
+                    }
+                    None if announce && !shown_online => {
+                        if self.offline_grace.is_zero() {
+                            connected.push(user.clone());
+                        } else {
+                            self.pending_presence
+                                .insert(user.identity.clone(), PendingPresence::Joined(now));
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            match pending {
+                Some(PendingPresence::Joined(_)) => {
+                    self.pending_presence.remove(&user.identity);
+                }
+                Some(PendingPresence::Offline(since))
+                    if now.duration_since(since) < self.offline_grace =>
+                {
+                    user.online = true;
+                }
+                Some(PendingPresence::Offline(_)) => {
+                    self.pending_presence.remove(&user.identity);
+                    disconnected.push(user.clone());
+                }
+                None if shown_online && !self.offline_grace.is_zero() => {
+                    self.pending_presence
+                        .insert(user.identity.clone(), PendingPresence::Offline(now));
+                    user.online = true;
+                }
+                None if shown_online && announce => disconnected.push(user.clone()),
+                None => {}
+            }
+        }
+        // Quem sumiu do snapshot (conta apagada) sai sem carência.
+        for previous in &self.users {
+            if !previous.online || users.iter().any(|u| u.identity == previous.identity) {
+                continue;
+            }
+            let unannounced = matches!(
+                self.pending_presence.get(&previous.identity),
+                Some(PendingPresence::Joined(_))
+            );
+            if announce && !unannounced {
+                disconnected.push(previous.clone());
+            }
+        }
+        self.pending_presence
+            .retain(|identity, _| users.iter().any(|u| &u.identity == identity));
+        (connected, disconnected)
+    }
+
+    /// Encerra as carências vencidas e devolve quem entrou (aviso adiado) e
+    /// quem passou a aparecer offline.
+    pub fn expire_offline_grace(&mut self, now: Instant) -> (Vec<UiUser>, Vec<UiUser>) {
+        let grace = self.offline_grace;
+        let expired: Vec<(String, PendingPresence)> = self
+            .pending_presence
+            .iter()
+            .filter(|(_, pending)| now.duration_since(pending.since()) >= grace)
+            .map(|(identity, pending)| (identity.clone(), *pending))
+            .collect();
+        let (mut connected, mut went_offline) = (Vec::new(), Vec::new());
+        for (identity, pending) in expired {
+            self.pending_presence.remove(&identity);
+            let Some(user) = self.users.iter_mut().find(|u| u.identity == identity) else {
+                continue;
+            };
+            match pending {
+                PendingPresence::Joined(_) => connected.push(user.clone()),
+                PendingPresence::Offline(_) => {
+                    user.online = false;
+                    went_offline.push(user.clone());
+                }
+            }
+        }
+        if !went_offline.is_empty() {
+            sort_users(&mut self.users);
+        }
+        (connected, went_offline)
+    }
+}
+
+/// Ordem do painel lateral: online primeiro, depois por nome.
+pub fn sort_users(users: &mut [UiUser]) {
+    users.sort_by(|a, b| {
+        b.online
+            .cmp(&a.online)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+}
+
+/// Só mensagens do próprio usuário já confirmadas pelo servidor podem ser apagadas.
+pub fn can_delete(message: &UiMessage, my_identity: Option<&str>) -> bool {
+    my_identity.is_some_and(|me| !me.is_empty() && me == message.sender) && !message.is_pending
+}
+
+/// Posições, em `messages`, das mensagens cujo texto contém `query` (sem diferenciar maiúsculas).
+///
+/// Busca vazia não casa com nada.
+pub(crate) fn find_matching_message_indices(messages: &[&UiMessage], query: &str) -> Vec<usize> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    messages
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| m.text.to_lowercase().contains(&query))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Avisos locais do sistema e ecos do próprio envio não contam como não lidos.
+fn counts_as_unread(message: &UiMessage) -> bool {
+    !message.is_pending && message.sender != "System"
+}
+
+/// Linha de citação (`> nome: texto`) que antecede a resposta no input.
+///
+/// Quebras de linha viram espaços para a citação ocupar uma linha só, e textos
+/// longos são cortados em `QUOTE_MAX_CHARS` caracteres com "...".
+pub fn quote_prefill(sender: &str, text: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let quoted = if flat.chars().count() > QUOTE_MAX_CHARS {
+        let cut: String = flat.chars().take(QUOTE_MAX_CHARS).collect();
+        format!("{}...", cut.trim_end())
+    } else {
+        flat
+    };
+    format!("> {sender}: {quoted}\n")
+}
+
+#[cfg(test)]
+#[path = "../tests/ui_state_tests.rs"]
+mod tests;
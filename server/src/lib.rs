@@ -1,4 +1,92 @@
-use spacetimedb::{Identity, ReducerContext, Table, Timestamp, reducer, table};
+use std::fmt;
+
+use spacetimedb::{Identity, ReducerContext, SpacetimeType, Table, Timestamp, reducer, table};
+
+/// Tamanho máximo de uma mensagem, em caracteres (não bytes: texto acentuado conta 1 por letra).
+pub const MAX_MESSAGE_LEN: usize = 2000;
+
+/// Intervalo mínimo entre duas mensagens da mesma identity (anti-flood).
+pub const MIN_SEND_INTERVAL_MICROS: i64 = 500_000;
+
+/// Tamanho máximo de uma reação, em caracteres (emoji com modificadores ocupa vários).
+pub const MAX_EMOJI_LEN: usize = 8;
+
+/// Tamanho máximo do status exibido sob o nome, em caracteres.
+pub const MAX_STATUS_LEN: usize = 60;
+
+/// Tamanho máximo do nome escolhido no chat, em caracteres.
+///
+/// O client repete o valor em `client/src/ui/ui_state.rs` para validar antes de
+/// enviar; mantenha os dois iguais.
+pub const MAX_NAME_LEN: usize = 24;
+
+/// Nome de quem conectou e ainda não escolheu um; nunca fica reservado.
+pub const DEFAULT_NAME: &str = "Anônimo";
+
+/// Tamanho máximo do título exibido entre colchetes antes do nome, em caracteres.
+pub const MAX_TITLE_LEN: usize = 16;
+
+/// Títulos que só o host pode atribuir (comparação sem caixa).
+pub const PRIVILEGED_TITLES: &[&str] = &["host", "admin", "mod"];
+
+/// Motivos de rejeição dos reducers.
+///
+/// O texto vira a mensagem de `Status::Failed` recebida pelo client, então
+/// cada variante precisa ter uma mensagem própria e estável.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReducerError {
+    NotInChat,
+    EmptyMessage,
+    EmptyName,
+    NameTooLong,
+    UserNotFound,
+    NotHost,
+    MessageNotFound,
+    NotSender,
+    MessageTooLong,
+    NameTaken,
+    TooFast,
+    RecipientNotFound,
+    InvalidEmoji,
+    TitleTooLong,
+    ReservedTitle,
+}
+
+impl fmt::Display for ReducerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Self::NameTooLong => {
+                return write!(f, "name too long (max {MAX_NAME_LEN} characters)");
+            }
+            Self::MessageTooLong => {
+                return write!(f, "message too long (max {MAX_MESSAGE_LEN} characters)");
+            }
+            Self::TitleTooLong => {
+                return write!(f, "title too long (max {MAX_TITLE_LEN} characters)");
+            }
+            Self::NotInChat => "not in chat: choose a name first",
+            Self::EmptyMessage => "empty message",
+            Self::EmptyName => "empty name",
+            Self::UserNotFound => "unknown user",
+            Self::NotHost => "only the host can do this",
+            Self::MessageNotFound => "unknown message",
+            Self::NotSender => "only the sender can edit or delete this message",
+            Self::NameTaken => "name already in use",
+            Self::TooFast => "sending too fast, wait a moment",
+            Self::RecipientNotFound => "unknown recipient",
+            Self::InvalidEmoji => "reaction must be 1 to 8 characters",
+            Self::ReservedTitle => "only the host can assign this title",
+        };
+        f.write_str(text)
+    }
+}
+
+/// Tipo da mensagem: conversa normal ou aviso do host.
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageKind {
+    Chat,
+    Announcement,
+}
 
 #[table(name = user, public)]
 pub struct User {
@@ -6,6 +94,9 @@ pub struct User {
     pub identity: Identity, // ID único da conexão (vem do SpacetimeDB)
     pub name: String, // Nome que o usuário escolher
     pub online: bool,
+    pub last_sent: Timestamp, // Última mensagem aceita (rate limit)
+    pub status: String,       // Frase curta exibida no painel de usuários
+    pub title: String,        // Título entre colchetes antes do nome (vazio = nenhum)
 }
 
 #[table(name = message, public)]
@@ -13,24 +104,81 @@ pub struct Message {
     #[primary_key]
     #[auto_inc]
     pub id: u64, // ID automático da mensagem
-    pub sender: Identity,   // Quem mandou
-    pub text: String,       // O conteúdo
-    pub sent_at: Timestamp, // Hora do envio
+    pub sender: Identity,             // Quem mandou
+    pub text: String,                 // O conteúdo
+    pub sent_at: Timestamp,           // Hora do envio
+    pub kind: MessageKind,            // Conversa ou anúncio do host
+    pub edited_at: Option<Timestamp>, // Última edição (None = nunca editada)
+}
+
+// Mensagens privadas. A tabela é pública: cada client filtra as linhas em que
+// é remetente ou destinatário.
+#[table(name = direct_message, public)]
+pub struct DirectMessage {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    pub sender: Identity,
+    pub recipient: Identity,
+    pub text: String,
+    pub sent_at: Timestamp,
+}
+
+// Reação de um usuário a uma mensagem; no máximo uma linha por
+// (message_id, identity, emoji), garantido pelo `toggle_reaction`.
+#[table(name = reaction, public)]
+pub struct Reaction {
+    #[primary_key]
+    #[auto_inc]
+    pub id: u64,
+    pub message_id: u64,
+    pub identity: Identity,
+    pub emoji: String,
+}
+
+// Quem está digitando agora. `started_at` é renovado a cada `set_typing(true)`;
+// os clients descartam linhas antigas (ex.: client que caiu sem avisar).
+#[table(name = typing, public)]
+pub struct Typing {
+    #[primary_key]
+    pub identity: Identity,
+    pub started_at: Timestamp,
+}
+
+// Configuração privada do módulo (linha única, id = 0).
+#[table(name = config)]
+pub struct Config {
+    #[primary_key]
+    pub id: u32,
+    pub owner: Identity, // Quem publicou o módulo (host)
 }
 
 // 2. REDUCERS (A Lógica / API)
 // ---------------------------------------------------------
 
+// Chamado uma única vez na primeira publicação: quem publica vira o host.
+#[reducer(init)]
+pub fn init(ctx: &ReducerContext) {
+    ctx.db.config().insert(Config {
+        id: 0,
+        owner: ctx.sender,
+    });
+}
+
 // Chamado automaticamente quando alguém conecta
 #[reducer(client_connected)]
 pub fn identity_connected(ctx: &ReducerContext) {
     // Cria usuário se não existir, mas não entra no chat ainda.
     // O usuário só fica online depois de escolher nome no set_name.
-    if let None = ctx.db.user().identity().find(ctx.sender) {
+    // Depois de um `delete_account`, reconectar cria uma conta nova e vazia.
+    if ctx.db.user().identity().find(ctx.sender).is_none() {
         ctx.db.user().insert(User {
             identity: ctx.sender,
-            name: "Anônimo".to_string(),
+            name: DEFAULT_NAME.to_string(),
             online: false,
+            last_sent: Timestamp::UNIX_EPOCH,
+            status: String::new(),
+            title: String::new(),
         });
     } else {
         // Se já existe, mantém offline até confirmar nome novamente
@@ -44,6 +192,7 @@ pub fn identity_connected(ctx: &ReducerContext) {
 // Chamado automaticamente quando alguém desconecta
 #[reducer(client_disconnected)]
 pub fn identity_disconnected(ctx: &ReducerContext) {
+    ctx.db.typing().identity().delete(ctx.sender);
     if let Some(mut user) = ctx.db.user().identity().find(ctx.sender) {
         user.online = false;
         ctx.db.user().identity().update(user);
@@ -52,40 +201,427 @@ pub fn identity_disconnected(ctx: &ReducerContext) {
 
 // Função que o Client vai chamar para enviar mensagem
 #[reducer]
-pub fn send_message(ctx: &ReducerContext, text: String) {
+pub fn send_message(ctx: &ReducerContext, text: String) -> Result<(), ReducerError> {
     // Só permite enviar depois de entrar no chat (online=true)
-    if let Some(user) = ctx.db.user().identity().find(ctx.sender) {
-        if !user.online {
-            return;
-        }
-    } else {
-        return;
-    }
-
-    // Validação simples: não aceita mensagem vazia
-    if text.trim().is_empty() {
-        return;
-    }
+    let user = ctx.db.user().identity().find(ctx.sender);
+    validate_message(user.as_ref().map(|u| u.online), &text)?;
+    let Some(mut user) = user else {
+        return Err(ReducerError::NotInChat);
+    };
+    check_rate_limit(user.last_sent, ctx.timestamp)?;
+    user.last_sent = ctx.timestamp;
+    ctx.db.user().identity().update(user);
+    ctx.db.typing().identity().delete(ctx.sender);
 
     ctx.db.message().insert(Message {
         id: 0, // O autoinc resolve isso
         sender: ctx.sender,
         text,
         sent_at: ctx.timestamp,
+        kind: MessageKind::Chat,
+        edited_at: None,
+    });
+    Ok(())
+}
+
+// Mensagem privada para outro usuário existente.
+#[reducer]
+pub fn send_direct(
+    ctx: &ReducerContext,
+    recipient: Identity,
+    text: String,
+) -> Result<(), ReducerError> {
+    let sender_online = ctx.db.user().identity().find(ctx.sender).map(|u| u.online);
+    let recipient_exists = ctx.db.user().identity().find(recipient).is_some();
+    validate_direct(sender_online, recipient_exists, &text)?;
+
+    ctx.db.direct_message().insert(DirectMessage {
+        id: 0,
+        sender: ctx.sender,
+        recipient,
+        text: text.trim().to_string(),
+        sent_at: ctx.timestamp,
     });
+    Ok(())
+}
+
+// Aviso do host: só o dono do módulo pode anunciar.
+#[reducer]
+pub fn announce(ctx: &ReducerContext, text: String) -> Result<(), ReducerError> {
+    let owner = ctx.db.config().id().find(0).map(|c| c.owner);
+    if !can_announce(owner, ctx.sender) {
+        return Err(ReducerError::NotHost);
+    }
+
+    check_message_text(&text)?;
+    let cleaned = text.trim().to_string();
+
+    ctx.db.message().insert(Message {
+        id: 0,
+        sender: ctx.sender,
+        text: cleaned,
+        sent_at: ctx.timestamp,
+        kind: MessageKind::Announcement,
+        edited_at: None,
+    });
+    Ok(())
+}
+
+// Corrige o texto de uma mensagem própria; `sent_at` original é mantido.
+#[reducer]
+pub fn edit_message(ctx: &ReducerContext, id: u64, new_text: String) -> Result<(), ReducerError> {
+    let mut message = ctx
+        .db
+        .message()
+        .id()
+        .find(id)
+        .ok_or(ReducerError::MessageNotFound)?;
+    message.text = validate_edit(message.sender, ctx.sender, &new_text)?;
+    message.edited_at = Some(ctx.timestamp);
+    ctx.db.message().id().update(message);
+    Ok(())
+}
+
+// Apaga uma mensagem própria junto com as reações dela.
+#[reducer]
+pub fn delete_message(ctx: &ReducerContext, id: u64) -> Result<(), ReducerError> {
+    let message = ctx
+        .db
+        .message()
+        .id()
+        .find(id)
+        .ok_or(ReducerError::MessageNotFound)?;
+    validate_delete(message.sender, ctx.sender)?;
+    ctx.db.message().id().delete(id);
+    let reaction_ids: Vec<u64> = ctx
+        .db
+        .reaction()
+        .iter()
+        .filter(|r| r.message_id == id)
+        .map(|r| r.id)
+        .collect();
+    for reaction_id in reaction_ids {
+        ctx.db.reaction().id().delete(reaction_id);
+    }
+    Ok(())
+}
+
+// Adiciona a reação se ainda não existe; remove se já existe (toggle).
+#[reducer]
+pub fn toggle_reaction(
+    ctx: &ReducerContext,
+    message_id: u64,
+    emoji: String,
+) -> Result<(), ReducerError> {
+    let emoji = clean_emoji(&emoji)?;
+    if ctx.db.message().id().find(message_id).is_none() {
+        return Err(ReducerError::MessageNotFound);
+    }
+
+    match existing_reaction(ctx.db.reaction().iter(), message_id, ctx.sender, &emoji) {
+        Some(id) => {
+            ctx.db.reaction().id().delete(id);
+        }
+        None => {
+            ctx.db.reaction().insert(Reaction {
+                id: 0,
+                message_id,
+                identity: ctx.sender,
+                emoji,
+            });
+        }
+    }
+    Ok(())
 }
 
 // Função para mudar o nome de usuário
 #[reducer]
-pub fn set_name(ctx: &ReducerContext, new_name: String) {
+pub fn set_name(ctx: &ReducerContext, new_name: String) -> Result<(), ReducerError> {
+    let cleaned = clean_name(&new_name)?;
+    if name_taken(ctx.db.user().iter(), ctx.sender, &cleaned) {
+        return Err(ReducerError::NameTaken);
+    }
+
+    let mut user = ctx
+        .db
+        .user()
+        .identity()
+        .find(ctx.sender)
+        .ok_or(ReducerError::UserNotFound)?;
+    user.name = cleaned;
+    user.online = true;
+    ctx.db.user().identity().update(user);
+    Ok(())
+}
+
+// Define o status curto de quem chama; texto vazio limpa o status.
+#[reducer]
+pub fn set_status(ctx: &ReducerContext, text: String) -> Result<(), ReducerError> {
+    let mut user = ctx
+        .db
+        .user()
+        .identity()
+        .find(ctx.sender)
+        .ok_or(ReducerError::UserNotFound)?;
+    user.status = clean_status(&text);
+    ctx.db.user().identity().update(user);
+    Ok(())
+}
+
+// Define o título de `target`: cada um escolhe o próprio (cosmético), e só o host
+// muda o de outros ou atribui títulos reservados. Título vazio remove.
+#[reducer]
+pub fn set_title(
+    ctx: &ReducerContext,
+    target: Identity,
+    title: String,
+) -> Result<(), ReducerError> {
+    let owner = ctx.db.config().id().find(0).map(|c| c.owner);
+    let cleaned = validate_title(
+        can_announce(owner, ctx.sender),
+        ctx.sender == target,
+        &title,
+    )?;
+    let mut user = ctx
+        .db
+        .user()
+        .identity()
+        .find(target)
+        .ok_or(ReducerError::UserNotFound)?;
+    user.title = cleaned;
+    ctx.db.user().identity().update(user);
+    Ok(())
+}
+
+// Liga/desliga o indicador "digitando..." de quem chama.
+#[reducer]
+pub fn set_typing(ctx: &ReducerContext, is_typing: bool) -> Result<(), ReducerError> {
+    if !is_typing {
+        ctx.db.typing().identity().delete(ctx.sender);
+        return Ok(());
+    }
+    let online = ctx.db.user().identity().find(ctx.sender).map(|u| u.online);
+    validate_typing(online)?;
+    let row = Typing {
+        identity: ctx.sender,
+        started_at: ctx.timestamp,
+    };
+    if ctx.db.typing().identity().find(ctx.sender).is_some() {
+        ctx.db.typing().identity().update(row);
+    } else {
+        ctx.db.typing().insert(row);
+    }
+    Ok(())
+}
+
+// Remove o usuário que chamou, todas as mensagens dele, reações e conversas privadas.
+// O client desconecta em seguida; `identity_disconnected` ignora a linha ausente.
+#[reducer]
+pub fn delete_account(ctx: &ReducerContext) -> Result<(), ReducerError> {
+    if !ctx.db.user().identity().delete(ctx.sender) {
+        return Err(ReducerError::UserNotFound);
+    }
+    ctx.db.typing().identity().delete(ctx.sender);
+
+    for id in owned_message_ids(ctx.db.message().iter(), ctx.sender) {
+        ctx.db.message().id().delete(id);
+    }
+    let reaction_ids: Vec<u64> = ctx
+        .db
+        .reaction()
+        .iter()
+        .filter(|r| r.identity == ctx.sender || ctx.db.message().id().find(r.message_id).is_none())
+        .map(|r| r.id)
+        .collect();
+    for id in reaction_ids {
+        ctx.db.reaction().id().delete(id);
+    }
+    let direct_ids: Vec<u64> = ctx
+        .db
+        .direct_message()
+        .iter()
+        .filter(|dm| dm.sender == ctx.sender || dm.recipient == ctx.sender)
+        .map(|dm| dm.id)
+        .collect();
+    for id in direct_ids {
+        ctx.db.direct_message().id().delete(id);
+    }
+    Ok(())
+}
+
+// 3. REGRAS PURAS (testáveis fora do host)
+// ---------------------------------------------------------
+
+// `sender_online` é `None` quando o usuário não existe na tabela.
+fn validate_message(sender_online: Option<bool>, text: &str) -> Result<(), ReducerError> {
+    if sender_online != Some(true) {
+        return Err(ReducerError::NotInChat);
+    }
+    check_message_text(text)
+}
+
+// Regras de conteúdo compartilhadas por envio, edição e anúncio.
+fn check_message_text(text: &str) -> Result<(), ReducerError> {
+    let trimmed = text.trim();
+    // Validação simples: não aceita mensagem vazia
+    if trimmed.is_empty() {
+        return Err(ReducerError::EmptyMessage);
+    }
+    if trimmed.chars().count() > MAX_MESSAGE_LEN {
+        return Err(ReducerError::MessageTooLong);
+    }
+    Ok(())
+}
+
+// Mesmas regras do chat, mais a existência do destinatário.
+fn validate_direct(
+    sender_online: Option<bool>,
+    recipient_exists: bool,
+    text: &str,
+) -> Result<(), ReducerError> {
+    validate_message(sender_online, text)?;
+    if !recipient_exists {
+        return Err(ReducerError::RecipientNotFound);
+    }
+    Ok(())
+}
+
+// Só quem já entrou no chat aparece digitando.
+fn validate_typing(sender_online: Option<bool>) -> Result<(), ReducerError> {
+    if sender_online != Some(true) {
+        return Err(ReducerError::NotInChat);
+    }
+    Ok(())
+}
+
+fn clean_emoji(emoji: &str) -> Result<String, ReducerError> {
+    let cleaned = emoji.trim();
+    let len = cleaned.chars().count();
+    if len == 0 || len > MAX_EMOJI_LEN {
+        return Err(ReducerError::InvalidEmoji);
+    }
+    Ok(cleaned.to_string())
+}
+
+// Linha já existente para o mesmo (mensagem, usuário, emoji).
+fn existing_reaction(
+    reactions: impl IntoIterator<Item = Reaction>,
+    message_id: u64,
+    identity: Identity,
+    emoji: &str,
+) -> Option<u64> {
+    reactions
+        .into_iter()
+        .find(|r| r.message_id == message_id && r.identity == identity && r.emoji == emoji)
+        .map(|r| r.id)
+}
+
+fn clean_name(new_name: &str) -> Result<String, ReducerError> {
     let cleaned = new_name.trim().to_string();
     if cleaned.is_empty() {
-        return;
+        return Err(ReducerError::EmptyName);
+    }
+    if cleaned.chars().count() > MAX_NAME_LEN {
+        return Err(ReducerError::NameTooLong);
     }
+    Ok(cleaned)
+}
 
-    if let Some(mut user) = ctx.db.user().identity().find(ctx.sender) {
-        user.name = cleaned;
-        user.online = true;
-        ctx.db.user().identity().update(user);
+// Sem espaços nas pontas e cortado em `MAX_STATUS_LEN` caracteres.
+fn clean_status(text: &str) -> String {
+    text.trim()
+        .chars()
+        .take(MAX_STATUS_LEN)
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
+
+// Quem pode dar qual título; retorna o título sem espaços nem colchetes nas pontas.
+fn validate_title(
+    caller_is_host: bool,
+    is_self: bool,
+    title: &str,
+) -> Result<String, ReducerError> {
+    if !is_self && !caller_is_host {
+        return Err(ReducerError::NotHost);
+    }
+    let cleaned = title
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .trim()
+        .to_string();
+    if cleaned.chars().count() > MAX_TITLE_LEN {
+        return Err(ReducerError::TitleTooLong);
+    }
+    let reserved = PRIVILEGED_TITLES
+        .iter()
+        .any(|t| t.eq_ignore_ascii_case(&cleaned));
+    if reserved && !caller_is_host {
+        return Err(ReducerError::ReservedTitle);
     }
+    Ok(cleaned)
 }
+
+// Só quem enviou pode editar; retorna o novo texto já sem espaços nas pontas.
+fn validate_edit(
+    message_sender: Identity,
+    caller: Identity,
+    new_text: &str,
+) -> Result<String, ReducerError> {
+    if message_sender != caller {
+        return Err(ReducerError::NotSender);
+    }
+    check_message_text(new_text)?;
+    Ok(new_text.trim().to_string())
+}
+
+// Só quem enviou pode apagar a mensagem.
+fn validate_delete(message_sender: Identity, caller: Identity) -> Result<(), ReducerError> {
+    if message_sender != caller {
+        return Err(ReducerError::NotSender);
+    }
+    Ok(())
+}
+
+// Rejeita envios mais próximos que `MIN_SEND_INTERVAL_MICROS` do anterior aceito.
+fn check_rate_limit(last_sent: Timestamp, now: Timestamp) -> Result<(), ReducerError> {
+    let elapsed = now.to_micros_since_unix_epoch() - last_sent.to_micros_since_unix_epoch();
+    if elapsed < MIN_SEND_INTERVAL_MICROS {
+        return Err(ReducerError::TooFast);
+    }
+    Ok(())
+}
+
+// Nome já usado por outra identity (sem diferenciar maiúsculas); o próprio nome
+// atual é permitido. Linhas offline também reservam o nome: quem cai e volta com
+// o mesmo token não pode encontrar o nome tomado por outro. `DEFAULT_NAME` é de
+// todos que ainda não escolheram nome, então nunca conta como tomado.
+fn name_taken(users: impl IntoIterator<Item = User>, caller: Identity, name: &str) -> bool {
+    let wanted = name.to_lowercase();
+    if wanted == DEFAULT_NAME.to_lowercase() {
+        return false;
+    }
+    users
+        .into_iter()
+        .any(|u| u.identity != caller && u.name.to_lowercase() == wanted)
+}
+
+// Só o host registrado no `init` pode publicar anúncios.
+fn can_announce(owner: Option<Identity>, sender: Identity) -> bool {
+    owner.is_some_and(|owner| owner == sender)
+}
+
+// IDs das mensagens enviadas por `owner` (alvo do `delete_account`).
+fn owned_message_ids(messages: impl IntoIterator<Item = Message>, owner: Identity) -> Vec<u64> {
+    messages
+        .into_iter()
+        .filter(|m| m.sender == owner)
+        .map(|m| m.id)
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "tests/lib_tests.rs"]
+mod tests;
@@ -0,0 +1,363 @@
+use spacetimedb::{Identity, Timestamp};
+
+use std::collections::HashSet;
+
+use super::{
+    DEFAULT_NAME, MAX_MESSAGE_LEN, MAX_NAME_LEN, MAX_STATUS_LEN, MAX_TITLE_LEN,
+    MIN_SEND_INTERVAL_MICROS, Message, MessageKind, Reaction, ReducerError, User, can_announce,
+    check_rate_limit, clean_emoji, clean_name, clean_status, existing_reaction, name_taken,
+    owned_message_ids, validate_delete, validate_direct, validate_edit, validate_message,
+    validate_title, validate_typing,
+};
+
+fn identity(byte: u8) -> Identity {
+    Identity::from_byte_array([byte; 32])
+}
+
+fn message(id: u64, sender: Identity) -> Message {
+    Message {
+        id,
+        sender,
+        text: format!("msg {id}"),
+        sent_at: Timestamp::UNIX_EPOCH,
+        kind: MessageKind::Chat,
+        edited_at: None,
+    }
+}
+
+#[test]
+fn can_announce_only_for_owner() {
+    let host = identity(1);
+    let guest = identity(2);
+    assert!(can_announce(Some(host), host));
+    assert!(!can_announce(Some(host), guest));
+}
+
+#[test]
+fn can_announce_rejects_everyone_without_registered_owner() {
+    assert!(!can_announce(None, identity(1)));
+}
+
+#[test]
+fn validate_message_rejects_with_distinct_reasons() {
+    assert_eq!(validate_message(None, "oi"), Err(ReducerError::NotInChat));
+    assert_eq!(
+        validate_message(Some(false), "oi"),
+        Err(ReducerError::NotInChat)
+    );
+    assert_eq!(
+        validate_message(Some(true), "   "),
+        Err(ReducerError::EmptyMessage)
+    );
+    assert_eq!(validate_message(Some(true), "oi"), Ok(()));
+}
+
+#[test]
+fn clean_name_trims_and_rejects_empty() {
+    assert_eq!(clean_name("  Rafael "), Ok("Rafael".to_string()));
+    assert_eq!(clean_name("   "), Err(ReducerError::EmptyName));
+}
+
+#[test]
+fn clean_name_rejects_names_over_the_limit_in_characters() {
+    let longest = "é".repeat(MAX_NAME_LEN);
+    assert_eq!(clean_name(&format!(" {longest} ")), Ok(longest.clone()));
+    assert_eq!(
+        clean_name(&format!("{longest}x")),
+        Err(ReducerError::NameTooLong)
+    );
+}
+
+/// Variante seguinte na lista de todas; o `match` exaustivo faz uma variante
+/// nova quebrar a compilação até entrar na sequência.
+fn next_reducer_error(error: &ReducerError) -> Option<ReducerError> {
+    use ReducerError::*;
+    match error {
+        NotInChat => Some(EmptyMessage),
+        EmptyMessage => Some(EmptyName),
+        EmptyName => Some(NameTooLong),
+        NameTooLong => Some(UserNotFound),
+        UserNotFound => Some(NotHost),
+        NotHost => Some(MessageNotFound),
+        MessageNotFound => Some(NotSender),
+        NotSender => Some(MessageTooLong),
+        MessageTooLong => Some(NameTaken),
+        NameTaken => Some(TooFast),
+        TooFast => Some(RecipientNotFound),
+        RecipientNotFound => Some(InvalidEmoji),
+        InvalidEmoji => Some(TitleTooLong),
+        TitleTooLong => Some(ReservedTitle),
+        ReservedTitle => None,
+    }
+}
+
+#[test]
+fn reducer_error_messages_are_unique() {
+    let all: Vec<ReducerError> =
+        std::iter::successors(Some(ReducerError::NotInChat), next_reducer_error).collect();
+    let texts: HashSet<String> = all.iter().map(ToString::to_string).collect();
+    assert_eq!(texts.len(), all.len());
+}
+
+#[test]
+fn length_errors_quote_the_current_limits() {
+    assert!(
+        ReducerError::NameTooLong
+            .to_string()
+            .contains(&format!("max {MAX_NAME_LEN} "))
+    );
+    assert!(
+        ReducerError::MessageTooLong
+            .to_string()
+            .contains(&format!("max {MAX_MESSAGE_LEN} "))
+    );
+    assert!(
+        ReducerError::TitleTooLong
+            .to_string()
+            .contains(&format!("max {MAX_TITLE_LEN} "))
+    );
+}
+
+#[test]
+fn delete_account_targets_only_the_callers_messages() {
+    let leaving = identity(1);
+    let staying = identity(2);
+    let messages = || {
+        vec![
+            message(1, leaving),
+            message(2, staying),
+            message(3, leaving),
+            message(4, staying),
+        ]
+    };
+
+    assert_eq!(owned_message_ids(messages(), leaving), vec![1, 3]);
+    assert_eq!(owned_message_ids(messages(), staying), vec![2, 4]);
+    assert_eq!(
+        owned_message_ids(messages(), identity(3)),
+        Vec::<u64>::new()
+    );
+}
+
+#[test]
+fn validate_edit_requires_sender_and_non_empty_text() {
+    let author = identity(1);
+    assert_eq!(
+        validate_edit(author, author, "  corrigido "),
+        Ok("corrigido".to_string())
+    );
+    assert_eq!(
+        validate_edit(author, identity(2), "oi"),
+        Err(ReducerError::NotSender)
+    );
+    assert_eq!(
+        validate_edit(author, author, "   "),
+        Err(ReducerError::EmptyMessage)
+    );
+}
+
+#[test]
+fn validate_delete_requires_sender() {
+    let author = identity(1);
+    assert_eq!(validate_delete(author, author), Ok(()));
+    assert_eq!(
+        validate_delete(author, identity(2)),
+        Err(ReducerError::NotSender)
+    );
+}
+
+#[test]
+fn validate_message_rejects_text_over_the_char_limit() {
+    let too_long = "a".repeat(MAX_MESSAGE_LEN + 1);
+    assert_eq!(
+        validate_message(Some(true), &too_long),
+        Err(ReducerError::MessageTooLong)
+    );
+    assert_eq!(
+        validate_edit(identity(1), identity(1), &too_long),
+        Err(ReducerError::MessageTooLong)
+    );
+}
+
+#[test]
+fn validate_message_counts_chars_not_bytes() {
+    // 2000 letras acentuadas ocupam 4000 bytes, mas ainda cabem no limite.
+    let accented = "é".repeat(MAX_MESSAGE_LEN);
+    assert_eq!(validate_message(Some(true), &accented), Ok(()));
+    // Espaços nas pontas não contam.
+    let padded = format!("  {}  ", "a".repeat(MAX_MESSAGE_LEN));
+    assert_eq!(validate_message(Some(true), &padded), Ok(()));
+}
+
+fn user(identity: Identity, name: &str) -> User {
+    User {
+        identity,
+        name: name.to_string(),
+        online: true,
+        last_sent: Timestamp::UNIX_EPOCH,
+        status: String::new(),
+        title: String::new(),
+    }
+}
+
+#[test]
+fn name_taken_blocks_other_identities_case_insensitively() {
+    let a = identity(1);
+    let b = identity(2);
+    let users = || vec![user(a, "Rafael"), user(b, "Lia")];
+
+    // B tenta o nome de A: rejeitado, então B continua como "Lia".
+    assert!(name_taken(users(), b, "rafael"));
+    assert!(name_taken(users(), b, "RAFAEL"));
+    // Reconfirmar o próprio nome continua permitido.
+    assert!(!name_taken(users(), a, "Rafael"));
+    assert!(!name_taken(users(), b, "Mira"));
+}
+
+#[test]
+fn default_name_is_never_taken() {
+    let users = || {
+        vec![
+            user(identity(1), DEFAULT_NAME),
+            user(identity(2), DEFAULT_NAME),
+        ]
+    };
+    assert!(!name_taken(users(), identity(3), DEFAULT_NAME));
+    assert!(!name_taken(users(), identity(3), "anônimo"));
+}
+
+#[test]
+fn name_taken_counts_offline_rows() {
+    let offline = || User {
+        online: false,
+        ..user(identity(1), "Rafael")
+    };
+    assert!(name_taken(vec![offline()], identity(2), "Rafael"));
+    // O dono volta e reconfirma o próprio nome.
+    assert!(!name_taken(vec![offline()], identity(1), "Rafael"));
+}
+
+#[test]
+fn rate_limit_stores_only_the_first_of_two_close_messages() {
+    // Mesma sequência do `send_message`: valida, grava e atualiza `last_sent`.
+    let mut last_sent = Timestamp::UNIX_EPOCH;
+    let mut stored = Vec::new();
+    let start = 1_000_000_000;
+    for (text, at) in [("primeira", start), ("segunda", start + 100_000)] {
+        let now = Timestamp::from_micros_since_unix_epoch(at);
+        if check_rate_limit(last_sent, now).is_ok() {
+            stored.push(text);
+            last_sent = now;
+        }
+    }
+    assert_eq!(stored, vec!["primeira"]);
+}
+
+#[test]
+fn rate_limit_allows_messages_after_the_interval() {
+    let first = Timestamp::from_micros_since_unix_epoch(1_000_000_000);
+    let later = Timestamp::from_micros_since_unix_epoch(1_000_000_000 + MIN_SEND_INTERVAL_MICROS);
+    assert_eq!(check_rate_limit(first, later), Ok(()));
+    assert_eq!(check_rate_limit(first, first), Err(ReducerError::TooFast));
+}
+
+#[test]
+fn validate_direct_requires_text_and_existing_recipient() {
+    assert_eq!(validate_direct(Some(true), true, "psiu"), Ok(()));
+    assert_eq!(
+        validate_direct(Some(true), false, "psiu"),
+        Err(ReducerError::RecipientNotFound)
+    );
+    assert_eq!(
+        validate_direct(Some(true), true, "  "),
+        Err(ReducerError::EmptyMessage)
+    );
+    assert_eq!(
+        validate_direct(Some(false), true, "psiu"),
+        Err(ReducerError::NotInChat)
+    );
+}
+
+#[test]
+fn clean_emoji_accepts_short_strings_only() {
+    assert_eq!(clean_emoji(" 👍 "), Ok("👍".to_string()));
+    assert_eq!(clean_emoji("👩‍💻"), Ok("👩‍💻".to_string()));
+    assert_eq!(clean_emoji(""), Err(ReducerError::InvalidEmoji));
+    assert_eq!(clean_emoji("muito longo"), Err(ReducerError::InvalidEmoji));
+}
+
+#[test]
+fn existing_reaction_matches_message_identity_and_emoji() {
+    let me = identity(1);
+    let reactions = || {
+        vec![
+            Reaction {
+                id: 10,
+                message_id: 1,
+                identity: me,
+                emoji: "👍".to_string(),
+            },
+            Reaction {
+                id: 11,
+                message_id: 1,
+                identity: identity(2),
+                emoji: "🎉".to_string(),
+            },
+        ]
+    };
+    assert_eq!(existing_reaction(reactions(), 1, me, "👍"), Some(10));
+    assert_eq!(existing_reaction(reactions(), 1, me, "🎉"), None);
+    assert_eq!(existing_reaction(reactions(), 2, me, "👍"), None);
+}
+
+#[test]
+fn validate_typing_requires_being_in_chat() {
+    assert_eq!(validate_typing(Some(true)), Ok(()));
+    assert_eq!(validate_typing(Some(false)), Err(ReducerError::NotInChat));
+    assert_eq!(validate_typing(None), Err(ReducerError::NotInChat));
+}
+
+#[test]
+fn clean_status_trims_and_caps_length() {
+    assert_eq!(clean_status("  lendo grimórios  "), "lendo grimórios");
+    assert_eq!(clean_status("   "), "");
+    let long = "ç".repeat(MAX_STATUS_LEN + 10);
+    assert_eq!(clean_status(&long).chars().count(), MAX_STATUS_LEN);
+}
+
+#[test]
+fn users_set_cosmetic_titles_on_themselves_only() {
+    assert_eq!(
+        validate_title(false, true, " [bardo] "),
+        Ok("bardo".to_string())
+    );
+    assert_eq!(validate_title(false, true, ""), Ok(String::new()));
+    assert_eq!(
+        validate_title(false, false, "bardo"),
+        Err(ReducerError::NotHost)
+    );
+}
+
+#[test]
+fn reserved_titles_and_other_users_need_the_host() {
+    assert_eq!(
+        validate_title(false, true, "Admin"),
+        Err(ReducerError::ReservedTitle)
+    );
+    assert_eq!(validate_title(true, true, "host"), Ok("host".to_string()));
+    assert_eq!(validate_title(true, false, "mod"), Ok("mod".to_string()));
+    assert_eq!(
+        validate_title(true, false, "guest"),
+        Ok("guest".to_string())
+    );
+}
+
+#[test]
+fn titles_are_limited_in_characters() {
+    let longest = "á".repeat(MAX_TITLE_LEN);
+    assert_eq!(validate_title(false, true, &longest), Ok(longest.clone()));
+    assert_eq!(
+        validate_title(true, true, &format!("{longest}x")),
+        Err(ReducerError::TitleTooLong)
+    );
+}